@@ -1,9 +0,0 @@
-create table user_statistics (
-    id BIGINT PRIMARY KEY,
-    account_id BIGINT NOT NULL,
-    total_messages BIGINT NOT NULL
-
---    key varchar(255) UNIQUE NOT NULL,
---    value varchar(255) NOT NULL
-);
-
@@ -0,0 +1,9 @@
+create table if not exists account_follows (
+    follower_id BIGINT NOT NULL REFERENCES user_accounts (id),
+    organizer_id BIGINT NOT NULL REFERENCES user_accounts (id),
+    follow_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (follower_id, organizer_id),
+    CHECK (follower_id <> organizer_id)
+);
+
+create index if not exists account_follows_organizer_idx on account_follows (organizer_id);
@@ -0,0 +1 @@
+alter table user_accounts add column if not exists locale varchar(15);
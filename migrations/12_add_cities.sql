@@ -0,0 +1,37 @@
+-- cities are stored as ids of `City` enum, free text of user city is kept in `legacy_city`,
+-- unrecognised cities are left empty and users are asked to choose city again
+alter table user_accounts add column if not exists legacy_city varchar(255);
+alter table user_accounts alter column city drop NOT NULL;
+do $$
+begin
+    if (select data_type from information_schema.columns
+        where table_name = 'user_accounts' and column_name = 'city') <> 'integer' then
+        update user_accounts set legacy_city = city;
+        alter table user_accounts alter column city type INT using (
+            case
+                when lower(trim(city)) in (
+                    'saint petersburg', 'saint-petersburg', 'st. petersburg', 'st petersburg',
+                    'petersburg', 'spb',
+                    'санкт-петербург', 'Санкт-Петербург', 'санкт петербург', 'Санкт Петербург',
+                    'петербург', 'Петербург', 'спб', 'СПб', 'СПБ', 'питер', 'Питер'
+                ) then 0
+                when lower(trim(city)) in ('moscow', 'msk', 'москва', 'Москва', 'мск', 'Мск', 'МСК') then 1
+                when lower(trim(city)) in ('novosibirsk', 'новосибирск', 'Новосибирск') then 2
+                when lower(trim(city)) in (
+                    'yekaterinburg', 'ekaterinburg', 'екатеринбург', 'Екатеринбург', 'екб', 'ЕКБ'
+                ) then 3
+                when lower(trim(city)) in ('kazan', 'казань', 'Казань') then 4
+                when lower(trim(city)) in (
+                    'nizhny novgorod', 'nizhniy novgorod',
+                    'нижний новгород', 'Нижний Новгород', 'Нижний новгород', 'нн', 'НН'
+                ) then 5
+            end
+        );
+    end if;
+end $$;
+
+alter table resonanse_events add column if not exists city INT;
+update resonanse_events set city = 0 where event_format <> 1 and city is null;
+
+alter table deleted_events add column if not exists city INT;
+update deleted_events set city = 0 where event_format <> 1 and city is null;
@@ -0,0 +1,13 @@
+create table if not exists group_feed_cities (
+    chat_id BIGINT NOT NULL REFERENCES group_feeds (chat_id) ON DELETE CASCADE,
+    city INT NOT NULL,
+    PRIMARY KEY (chat_id, city)
+);
+
+-- existing feeds keep getting events of all cities
+insert into group_feed_cities (chat_id, city)
+select chat_id, unnest(array[0, 1, 2, 3, 4, 5])
+from group_feeds
+where not exists (
+    select 1 from group_feed_cities where group_feed_cities.chat_id = group_feeds.chat_id
+);
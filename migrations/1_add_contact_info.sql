@@ -1 +0,0 @@
-alter table resonanse_events add contact_info varchar(255);
\ No newline at end of file
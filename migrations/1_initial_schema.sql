@@ -0,0 +1,119 @@
+-- Schema which was kept in `db_init` before migrations were versioned.
+-- Databases created from it already have all these tables, `if not exists`
+-- lets them record this migration as applied without changes
+
+create table if not exists user_accounts (
+    -- base data
+    id BIGSERIAL PRIMARY KEY,
+    username varchar(255) UNIQUE,
+
+    -- user data
+    first_name varchar(255) NOT NULL,
+    last_name varchar(255) NOT NULL,
+    city varchar(255) NOT NULL,
+    about varchar(1023) NOT NULL,
+
+    headline varchar(255),
+    goals varchar(255),
+    interests varchar(255),
+    language varchar(255),
+    age SMALLINT,
+    education varchar(255),
+
+    hobby varchar(255),
+    music varchar(255),
+    sport varchar(255),
+    books varchar(255),
+    food varchar(255),
+    worldview varchar(255),
+    alcohol varchar(255),
+
+    -- contacts data
+    email varchar(255),
+    phone varchar(255),
+    tg_username varchar(255),
+    tg_user_id BIGINT,
+    instagram varchar(255),
+
+    -- auth data
+    password_hash varchar(1023),
+
+    -- other
+    user_type INT NOT NULL
+);
+
+create table if not exists resonanse_events (
+    id UUID PRIMARY KEY,
+    is_private BOOL NOT NULL,
+    is_commercial BOOL NOT NULL,
+    is_online BOOL NOT NULL,
+    is_paid BOOL NOT NULL,
+    event_kind INT NOT NULL,
+    title varchar(255) NOT NULL,
+    description varchar(4095) NOT NULL,
+    brief_description varchar(1023),
+    subject INT NOT NULL,
+    datetime_from TIMESTAMP NOT NULL,
+    datetime_to TIMESTAMP,
+    location_latitude FLOAT8,
+    location_longitude FLOAT8,
+    location_title varchar(255) NOT NULL,
+    creator_id BIGINT NOT NULL REFERENCES user_accounts (id),
+    event_type INT NOT NULL,
+    picture UUID,
+    contact_info varchar(255),
+    creation_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    attendance_confirmation_days_before INT
+);
+
+create table if not exists deleted_events (
+    id UUID PRIMARY KEY,
+    is_private BOOL NOT NULL,
+    is_commercial BOOL NOT NULL,
+    is_online BOOL NOT NULL,
+    is_paid BOOL NOT NULL,
+    event_kind INT NOT NULL,
+    title varchar(255) NOT NULL,
+    description varchar(4095) NOT NULL,
+    brief_description varchar(1023),
+    subject INT NOT NULL,
+    datetime_from TIMESTAMP NOT NULL,
+    datetime_to TIMESTAMP,
+    location_latitude FLOAT8,
+    location_longitude FLOAT8,
+    location_title varchar(255) NOT NULL,
+    creator_id BIGINT NOT NULL REFERENCES user_accounts (id),
+    event_type INT NOT NULL,
+    picture UUID,
+    contact_info varchar(255),
+    creation_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    update_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    attendance_confirmation_days_before INT
+);
+
+create table if not exists bot_statistics (
+    id BIGINT PRIMARY KEY,
+    key varchar(255) UNIQUE NOT NULL,
+    value varchar(255) NOT NULL
+);
+
+create table if not exists event_tg_table (
+    post_id BIGINT PRIMARY KEY,
+    event_id UUID NOT NULL REFERENCES resonanse_events (id)
+);
+
+create table if not exists user_likes (
+    user_id BIGINT NOT NULL REFERENCES user_accounts (id),
+    event_id UUID NOT NULL REFERENCES resonanse_events (id),
+    event_score INT NOT NULL,
+    PRIMARY KEY (user_id, event_id)
+);
+
+create table if not exists user_statistics (
+    id BIGINT PRIMARY KEY,
+    account_id BIGINT NOT NULL,
+    total_messages BIGINT NOT NULL
+
+--    key varchar(255) UNIQUE NOT NULL,
+--    value varchar(255) NOT NULL
+);
@@ -0,0 +1,3 @@
+alter table resonanse_events add column if not exists timezone varchar(63) NOT NULL DEFAULT 'Europe/Moscow';
+alter table deleted_events add column if not exists timezone varchar(63) NOT NULL DEFAULT 'Europe/Moscow';
+alter table user_accounts add column if not exists timezone varchar(63);
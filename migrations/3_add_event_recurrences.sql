@@ -0,0 +1,12 @@
+create table if not exists event_recurrences (
+    event_id UUID PRIMARY KEY REFERENCES resonanse_events (id) ON DELETE CASCADE,
+    frequency INT NOT NULL,
+    until_date DATE,
+    occurrences_count INT
+);
+
+create table if not exists cancelled_occurrences (
+    event_id UUID NOT NULL REFERENCES event_recurrences (event_id) ON DELETE CASCADE,
+    occurrence_datetime TIMESTAMP NOT NULL,
+    PRIMARY KEY (event_id, occurrence_datetime)
+);
@@ -0,0 +1,13 @@
+alter table resonanse_events add column if not exists event_format INT NOT NULL DEFAULT 0;
+alter table resonanse_events add column if not exists price_amount BIGINT;
+alter table resonanse_events add column if not exists price_currency varchar(3);
+alter table resonanse_events add column if not exists meeting_url varchar(1023);
+alter table resonanse_events alter column location_title drop NOT NULL;
+update resonanse_events set event_format = 1 where is_online = true and event_format = 0;
+
+alter table deleted_events add column if not exists event_format INT NOT NULL DEFAULT 0;
+alter table deleted_events add column if not exists price_amount BIGINT;
+alter table deleted_events add column if not exists price_currency varchar(3);
+alter table deleted_events add column if not exists meeting_url varchar(1023);
+alter table deleted_events alter column location_title drop NOT NULL;
+update deleted_events set event_format = 1 where is_online = true and event_format = 0;
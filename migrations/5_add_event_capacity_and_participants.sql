@@ -0,0 +1,10 @@
+alter table resonanse_events add column if not exists capacity INT;
+alter table deleted_events add column if not exists capacity INT;
+
+create table if not exists event_participants (
+    event_id UUID NOT NULL REFERENCES resonanse_events (id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES user_accounts (id),
+    status INT NOT NULL,
+    registration_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (event_id, user_id)
+);
@@ -0,0 +1,8 @@
+alter table resonanse_events add column if not exists invite_token varchar(63) UNIQUE;
+alter table deleted_events add column if not exists invite_token varchar(63);
+
+create table if not exists event_allowed_accounts (
+    event_id UUID NOT NULL REFERENCES resonanse_events (id) ON DELETE CASCADE,
+    account_id BIGINT NOT NULL REFERENCES user_accounts (id),
+    PRIMARY KEY (event_id, account_id)
+);
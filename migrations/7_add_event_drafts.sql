@@ -0,0 +1,6 @@
+create table if not exists event_drafts (
+    id UUID PRIMARY KEY,
+    account_id BIGINT NOT NULL REFERENCES user_accounts (id),
+    draft JSONB NOT NULL,
+    update_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
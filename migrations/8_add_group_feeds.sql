@@ -0,0 +1,11 @@
+create table if not exists group_feeds (
+    chat_id BIGINT PRIMARY KEY,
+    title varchar(255),
+    creation_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+create table if not exists group_feed_subjects (
+    chat_id BIGINT NOT NULL REFERENCES group_feeds (chat_id) ON DELETE CASCADE,
+    subject INT NOT NULL,
+    PRIMARY KEY (chat_id, subject)
+);
@@ -0,0 +1,24 @@
+create table if not exists communities (
+    id BIGSERIAL PRIMARY KEY,
+    name varchar(255) NOT NULL,
+    description varchar(4095) NOT NULL,
+    poster_image_link varchar(1023),
+    private BOOL NOT NULL DEFAULT false,
+    invite_token varchar(63) UNIQUE,
+    telegram_channel_link varchar(255),
+    community_chat BOOL NOT NULL DEFAULT false,
+    category varchar(255) NOT NULL,
+    location varchar(255) NOT NULL,
+    owner_id BIGINT NOT NULL REFERENCES user_accounts (id),
+    creation_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+create table if not exists community_members (
+    community_id BIGINT NOT NULL REFERENCES communities (id) ON DELETE CASCADE,
+    account_id BIGINT NOT NULL REFERENCES user_accounts (id),
+    join_time TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (community_id, account_id)
+);
+
+alter table resonanse_events add column if not exists community_id BIGINT REFERENCES communities (id) ON DELETE SET NULL;
+alter table deleted_events add column if not exists community_id BIGINT;
@@ -1,36 +1,44 @@
-// use axum::{routing, Router};
-//
-// mod services;
-// use resonanse_common::models;
-//
-// const fn get_routing() -> Router {
-//     // todo set up timeouts
-//     Router::new()
-//         .route("/", routing::get(root))
-//         .route("/user/signin", routing::get(user_signin).post(user_signin))
-//         .route("/user/signup", routing::get(user_signup))
-//         .route("/user/me", routing::get(user_me))
-//         .route("/user/get", routing::get(get_user))
-// }
-//
-// fn root() {}
-// fn user_signin() {}
-// fn user_signup() {}
-// fn user_me() {}
-// fn get_user() {}
-//
-// #[tokio::main]
-// async fn main() {
-//     // build our application with a single route
-//     let app = get_routing();
-//
-//     // run it with hyper on localhost:3000
-//     axum::Server::bind(&"0.0.0.0:3000".parse().unwrap())
-//         .serve(app.into_make_service())
-//         .await
-//         .unwrap();
-// }
+use std::sync::Arc;
 
-fn main() {
-    println!("test backend");
+use resonanse_common::configuration::BackendConfig;
+use resonanse_common::migrations::{run_migrate_command, run_migrations, MigrateCommand};
+use resonanse_common::repository::PgCommunitiesRepository;
+
+mod services;
+
+#[tokio::main]
+async fn main() {
+    let config = BackendConfig::load().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let pool = resonanse_common::PgPool::connect(&config.common.postgres_db_url)
+        .await
+        .unwrap();
+
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("migrate") {
+        let Some(command) = args
+            .get(2)
+            .and_then(|command| command.parse::<MigrateCommand>().ok())
+        else {
+            eprintln!("usage: {} migrate <status|apply>", args[0]);
+            std::process::exit(2);
+        };
+        run_migrate_command(&pool, command).await.unwrap();
+        return;
+    }
+
+    run_migrations(&pool).await.unwrap();
+    let communities_repository = Arc::new(PgCommunitiesRepository::new(pool.clone()));
+
+    // todo set up timeouts
+    let app = services::communities::get_routing(communities_repository)
+        .merge(services::metrics::get_routing());
+
+    tracing::info!("backend listening on {}", config.listen_addr);
+    axum::Server::bind(&config.listen_addr)
+        .serve(app.into_make_service())
+        .await
+        .unwrap();
 }
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+
+use resonanse_common::models::Community;
+use resonanse_common::repository::CommunitiesRepository;
+
+const DEFAULT_PAGE_SIZE: i64 = 20;
+const MAX_PAGE_SIZE: i64 = 100;
+
+type CommunitiesState = Arc<dyn CommunitiesRepository>;
+
+/// Same fields as community creation request of python backend
+#[derive(Deserialize)]
+pub struct CreateCommunityRequest {
+    name: String,
+    description: String,
+    poster_image_link: Option<String>,
+    private: bool,
+    telegram_channel_link: Option<String>,
+    community_chat: bool,
+    category: String,
+    location: String,
+    owner_id: i64,
+}
+
+#[derive(Deserialize)]
+pub struct Pagination {
+    page: Option<i64>,
+    page_size: Option<i64>,
+}
+
+pub fn get_routing(communities_repository: CommunitiesState) -> Router {
+    Router::new()
+        .route(
+            "/api/communities",
+            get(get_public_communities).post(create_community),
+        )
+        .route("/api/communities/:community_id", get(get_community))
+        .with_state(communities_repository)
+}
+
+fn into_status_code(err: sqlx::Error) -> StatusCode {
+    match err {
+        sqlx::Error::RowNotFound => StatusCode::NOT_FOUND,
+        err => {
+            tracing::error!("communities request failed: {:?}", err);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+async fn create_community(
+    State(communities_repository): State<CommunitiesState>,
+    Json(request): Json<CreateCommunityRequest>,
+) -> Result<Json<Community>, StatusCode> {
+    let community = Community {
+        id: 0,
+        name: request.name,
+        description: request.description,
+        poster_image_link: request.poster_image_link,
+        is_private: request.private,
+        invite_token: None,
+        telegram_channel_link: request.telegram_channel_link,
+        community_chat: request.community_chat,
+        category: request.category,
+        location: request.location,
+        owner_id: request.owner_id,
+        creation_time: chrono::Utc::now().naive_utc(),
+    };
+
+    communities_repository
+        .create_community(community)
+        .await
+        .map(Json)
+        .map_err(into_status_code)
+}
+
+async fn get_public_communities(
+    State(communities_repository): State<CommunitiesState>,
+    Query(pagination): Query<Pagination>,
+) -> Result<Json<Vec<Community>>, StatusCode> {
+    let page = pagination.page.unwrap_or(0).max(0);
+    let page_size = pagination
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    communities_repository
+        .get_public_communities(page, page_size)
+        .await
+        .map(Json)
+        .map_err(into_status_code)
+}
+
+/// Private communities are hidden, they are shown in bot only to members
+async fn get_community(
+    State(communities_repository): State<CommunitiesState>,
+    Path(community_id): Path<i64>,
+) -> Result<Json<Community>, StatusCode> {
+    let community = communities_repository
+        .get_community(community_id)
+        .await
+        .map_err(into_status_code)?;
+    if community.is_private {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    Ok(Json(community))
+}
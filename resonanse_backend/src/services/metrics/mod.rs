@@ -0,0 +1,17 @@
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+
+use resonanse_common::metrics;
+
+pub fn get_routing() -> Router {
+    Router::new().route("/metrics", get(get_metrics))
+}
+
+async fn get_metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, metrics::METRICS_CONTENT_TYPE)],
+        metrics::render(),
+    )
+}
@@ -1 +1,3 @@
 mod accounts;
+pub mod communities;
+pub mod metrics;
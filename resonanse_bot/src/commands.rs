@@ -20,6 +20,57 @@ pub enum Command {
     SendFeedback,
     #[command(description = "Внести donation")]
     SendDonation,
+    #[command(description = "Импорт из календаря")]
+    ImportEvents,
+    #[command(description = "Черновики")]
+    Drafts,
+    #[command(description = "Сообщества")]
+    Communities,
+    #[command(description = "Подписки на организаторов")]
+    Following,
+    #[command(description = "Профиль")]
+    Profile,
+    #[command(description = "Язык")]
+    Language,
     // #[command(description = "Выбор события", parse_with = accept_two_digits)]
     // Event{event_num: i64},
 }
+
+impl Command {
+    pub fn metric_label(&self) -> &'static str {
+        match self {
+            Command::Start => "start",
+            Command::About => "about",
+            Command::CreateEvent => "create_event",
+            Command::GetEvents => "get_events",
+            Command::RunWebApp => "run_web_app",
+            Command::SendFeedback => "send_feedback",
+            Command::SendDonation => "send_donation",
+            Command::ImportEvents => "import_events",
+            Command::Drafts => "drafts",
+            Command::Communities => "communities",
+            Command::Following => "following",
+            Command::Profile => "profile",
+            Command::Language => "language",
+        }
+    }
+}
+
+/// Commands available in groups, where bot posts event feed
+#[derive(BotCommands, Clone)]
+#[command(
+    rename_rule = "snake_case",
+    description = "These commands are supported:"
+)]
+pub enum GroupCommand {
+    #[command(description = "Настроить ленту событий")]
+    Feed,
+}
+
+impl GroupCommand {
+    pub fn metric_label(&self) -> &'static str {
+        match self {
+            GroupCommand::Feed => "feed",
+        }
+    }
+}
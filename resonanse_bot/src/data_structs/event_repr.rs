@@ -1,3 +1,4 @@
+use chrono_tz::Tz;
 use teloxide::payloads::{SendMessage, SendPhoto};
 use teloxide::prelude::*;
 use teloxide::requests::{JsonRequest, MultipartRequest};
@@ -6,57 +7,138 @@ use teloxide::utils::markdown;
 use teloxide::Bot;
 
 use resonanse_common::file_storage::get_event_image_path_by_uuid;
-use resonanse_common::models::BaseEvent;
+use resonanse_common::models::{BaseEvent, EventRecurrence};
 
-use crate::config::DEFAULT_DATETIME_FORMAT;
+use crate::config::{config, DEFAULT_DATETIME_FORMAT, DEFAULT_DATE_FORMAT};
+use crate::utils::format_event_price;
 
 pub enum EventPostMessageRequest {
     WithPoster(MultipartRequest<SendPhoto>),
     Text(JsonRequest<SendMessage>),
 }
 
-pub fn prepare_event_msg_with_base_event(
-    bot: &Bot,
-    chat_id: ChatId,
-    base_event: BaseEvent,
-    event_reply_markup: Option<ReplyMarkup>,
-) -> EventPostMessageRequest {
-    let formatted_data = match base_event.datetime_to {
-        None => base_event
-            .datetime_from
-            .format(DEFAULT_DATETIME_FORMAT)
-            .to_string(),
-        Some(datetime_to) => {
-            format!(
-                "{} - {}",
-                base_event.datetime_from.format(DEFAULT_DATETIME_FORMAT),
-                datetime_to.format(DEFAULT_DATETIME_FORMAT)
+/// Formats event dates as wall-clock time of viewer's timezone
+pub fn format_event_datetime(base_event: &BaseEvent, viewer_tz: Tz) -> String {
+    let datetime_from = base_event.datetime_from_instant().with_timezone(&viewer_tz);
+
+    match base_event.datetime_to_instant() {
+        None => format!(
+            "{} ({})",
+            datetime_from.format(DEFAULT_DATETIME_FORMAT),
+            datetime_from.format("%Z"),
+        ),
+        Some(datetime_to) => format!(
+            "{} - {} ({})",
+            datetime_from.format(DEFAULT_DATETIME_FORMAT),
+            datetime_to
+                .with_timezone(&viewer_tz)
+                .format(DEFAULT_DATETIME_FORMAT),
+            datetime_from.format("%Z"),
+        ),
+    }
+}
+
+/// Formats recurrence rule like "Еженедельно до 31.12.2024"
+pub fn format_event_recurrence(recurrence: &EventRecurrence) -> String {
+    let frequency = t!(&recurrence.frequency.to_string());
+    match (recurrence.until_date, recurrence.occurrences_count) {
+        (Some(until_date), _) => format!(
+            "{} {}",
+            frequency,
+            t!(
+                "recurrence.until",
+                until_date = until_date.format(DEFAULT_DATE_FORMAT)
             )
-        }
-    };
+        ),
+        (None, Some(occurrences_count)) => format!(
+            "{} {}",
+            frequency,
+            t!("recurrence.count", occurrences_count = occurrences_count)
+        ),
+        (None, None) => frequency,
+    }
+}
 
-    let msg_text = t!(
+/// Place, meeting link and price lines of event post, already escaped for MarkdownV2
+fn format_event_location(base_event: &BaseEvent) -> String {
+    let mut location_lines = Vec::new();
+    if let Some(location_title) = base_event.location_title.as_deref() {
+        let place = match base_event.city {
+            Some(city) => format!("{}, {}", location_title, t!(&city.to_string())),
+            None => location_title.to_string(),
+        };
+        location_lines.push(t!(
+            "actions.create_event.event_place",
+            place = markdown::escape(&place)
+        ));
+    }
+    if let Some(meeting_url) = base_event.meeting_url.as_deref() {
+        location_lines.push(format!(
+            "🌐 {}: {}",
+            markdown::escape(&t!(&base_event.event_format.to_string())),
+            markdown::escape(meeting_url)
+        ));
+    }
+    if let Some(price) = base_event.price.as_ref() {
+        location_lines.push(t!(
+            "actions.create_event.event_price",
+            price = markdown::escape(&format_event_price(price))
+        ));
+    }
+
+    location_lines.join("\n")
+}
+
+/// Event post text rendered with event template, formatted as MarkdownV2
+pub fn format_event_post_text(base_event: &BaseEvent, viewer_tz: Tz) -> String {
+    let mut formatted_data = format_event_datetime(base_event, viewer_tz);
+    if let Some(recurrence) = base_event.recurrence.as_ref() {
+        formatted_data.push_str(&format!("\n🔁 {}", format_event_recurrence(recurrence)));
+    }
+
+    t!(
         "actions.create_event.event_template",
-        event_title = markdown::escape(&base_event.title),
+        event_title = match base_event.is_private {
+            true => format!("🔒 {}", markdown::escape(&base_event.title)),
+            false => markdown::escape(&base_event.title),
+        },
         event_description = markdown::escape(&base_event.description),
         event_subject = markdown::escape(&t!(&base_event.subject.to_string())),
         event_datetime = markdown::escape(&formatted_data),
-        event_location = {
-            format!(
-                "📍 Место: _{}_",
-                markdown::escape(&base_event.location_title)
-            )
+        event_location = format_event_location(base_event),
+        event_places = match (base_event.free_places(), base_event.capacity) {
+            (Some(free_places), Some(capacity)) => t!(
+                "actions.create_event.event_places",
+                free_places = free_places,
+                capacity = capacity
+            ),
+            _ => String::new(),
         },
         event_contact_info = match base_event.contact_info.as_deref() {
             None => "".to_string(),
-            Some(contact_info) => format!("Контакт: _{}_", markdown::escape(contact_info)),
+            Some(contact_info) => t!(
+                "actions.create_event.event_contact",
+                contact = markdown::escape(contact_info)
+            ),
         },
-    );
+    )
+}
+
+pub fn prepare_event_msg_with_base_event(
+    bot: &Bot,
+    chat_id: ChatId,
+    base_event: BaseEvent,
+    event_reply_markup: Option<ReplyMarkup>,
+    viewer_tz: Tz,
+) -> EventPostMessageRequest {
+    let msg_text = format_event_post_text(&base_event, viewer_tz);
 
     match base_event.picture {
         Some(picture_uuid) => {
-            let event_image_input_file =
-                InputFile::file(get_event_image_path_by_uuid(picture_uuid));
+            let event_image_input_file = InputFile::file(get_event_image_path_by_uuid(
+                &config().common.storage_dir,
+                picture_uuid,
+            ));
             let mut msg = bot.send_photo(chat_id, event_image_input_file);
             msg.caption = Some(msg_text);
             msg.parse_mode = Some(ParseMode::MarkdownV2);
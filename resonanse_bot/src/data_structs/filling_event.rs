@@ -1,53 +1,97 @@
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::errors::BotHandlerError;
-use resonanse_common::models::{BaseEvent, EventSubject, Location, ResonanseEventKind};
+use resonanse_common::models::{
+    BaseEvent, City, EventFormat, EventPrice, EventRecurrence, EventSubject, EventType, Location,
+    ResonanseEventKind, DEFAULT_TIMEZONE,
+};
 // use resonanse_common::repository::CreateBaseEvent;
 
-#[derive(Clone, Default)]
+#[derive(Clone, Serialize, Deserialize)]
 /// This struct is used during event filling process
 pub struct FillingEvent {
+    /// Id of persisted draft, draft is saved after each filled field
+    pub draft_id: Uuid,
     pub title: Option<String>,
     pub is_private: bool,
     pub event_kind: ResonanseEventKind,
+    pub event_format: EventFormat,
+    /// `None` means event is free
+    pub price: Option<EventPrice>,
     pub subject: Option<EventSubject>,
     pub description: Option<String>,
     pub brief_description: Option<String>,
     pub datetime_from: Option<chrono::NaiveDateTime>,
     pub datetime_to: Option<chrono::NaiveDateTime>,
+    pub timezone: Tz,
+    pub recurrence: Option<EventRecurrence>,
     pub geo_position: Option<Location>,
+    /// City of venue, derived from `geo_position` when it is within known city
+    #[serde(default)]
+    pub city: Option<City>,
     pub location_title: Option<String>,
+    pub meeting_url: Option<String>,
+    /// `None` means unlimited number of participants
+    pub capacity: Option<i32>,
     pub picture: Option<Uuid>,
     pub contact_info: Option<String>,
     pub creator_id: i64,
+    /// Community owned by creator which organizes event
+    #[serde(default)]
+    pub community_id: Option<i64>,
 }
 
 impl FillingEvent {
     pub fn new() -> Self {
         FillingEvent {
+            draft_id: Uuid::new_v4(),
             title: None,
             is_private: false,
             event_kind: ResonanseEventKind::UserOffer,
+            event_format: EventFormat::Offline,
+            price: None,
             subject: None,
             description: None,
             brief_description: None,
             datetime_from: None,
             datetime_to: None,
+            timezone: DEFAULT_TIMEZONE,
+            recurrence: None,
             geo_position: None,
+            city: None,
             location_title: None,
+            meeting_url: None,
+            capacity: None,
             picture: None,
             contact_info: None,
             creator_id: 0,
+            community_id: None,
         }
     }
 
+    fn is_location_title_missed(&self) -> bool {
+        self.event_format.has_venue() && self.location_title.is_none()
+    }
+
+    fn is_city_missed(&self) -> bool {
+        self.event_format.has_venue() && self.city.is_none()
+    }
+
+    fn is_meeting_url_missed(&self) -> bool {
+        self.event_format.is_online() && self.meeting_url.is_none()
+    }
+
     pub fn is_ready(&self) -> bool {
         [
             self.title.is_some(),
             self.description.is_some(),
             self.subject.is_some(),
             self.datetime_from.is_some(),
-            self.location_title.is_some(),
+            !self.is_city_missed(),
+            !self.is_location_title_missed(),
+            !self.is_meeting_url_missed(),
             self.picture.is_some(),
             self.contact_info.is_some(),
         ]
@@ -57,33 +101,68 @@ impl FillingEvent {
 
     pub fn get_missed_data_hint(&self) -> String {
         if self.is_ready() {
-            return "Данные готовы, но превью почему-то не отоброжается\
-            Пожалуйста, сообщите об этом через /send_feedback"
-                .to_string();
+            return t!("actions.create_event.preview_failed");
         }
 
-        let mut missed_data_hint =
-            "Чтобы увидеть превью, нужно указать следующие данные:\n".to_string();
-        for (is_field_missed, hint_text) in [
-            (self.title.is_none(), "Название"),
-            (self.description.is_none(), "Описание"),
-            (self.subject.is_none(), "Тематика"),
-            (self.datetime_from.is_none(), "Дата и время начала"),
-            (self.location_title.is_none(), "Название места"),
-            (self.picture.is_none(), "Постер"),
-            (self.contact_info.is_none(), "Организатор"),
+        let mut missed_data = String::new();
+        for (is_field_missed, hint_key) in [
+            (
+                self.title.is_none(),
+                "actions.create_event.missed_data.title",
+            ),
+            (
+                self.description.is_none(),
+                "actions.create_event.missed_data.description",
+            ),
+            (
+                self.subject.is_none(),
+                "actions.create_event.missed_data.subject",
+            ),
+            (
+                self.datetime_from.is_none(),
+                "actions.create_event.missed_data.datetime_from",
+            ),
+            (
+                self.is_city_missed(),
+                "actions.create_event.missed_data.city",
+            ),
+            (
+                self.is_location_title_missed(),
+                "actions.create_event.missed_data.location_title",
+            ),
+            (
+                self.is_meeting_url_missed(),
+                "actions.create_event.missed_data.meeting_url",
+            ),
+            (
+                self.picture.is_none(),
+                "actions.create_event.missed_data.picture",
+            ),
+            (
+                self.contact_info.is_none(),
+                "actions.create_event.missed_data.contact_info",
+            ),
         ]
         .into_iter()
         {
             if is_field_missed {
-                missed_data_hint.push_str("\n[ ]\\-");
+                missed_data.push_str("\n[ ]\\-");
             } else {
-                missed_data_hint.push_str("\n[✅]\\-");
+                missed_data.push_str("\n[✅]\\-");
             }
-            missed_data_hint.push_str(hint_text);
+            missed_data.push_str(&t!(hint_key));
         }
 
-        missed_data_hint
+        t!(
+            "actions.create_event.filling_event_stub",
+            missed_data = missed_data
+        )
+    }
+}
+
+impl Default for FillingEvent {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -119,28 +198,49 @@ impl TryFrom<FillingEvent> for BaseEvent {
     type Error = BotHandlerError;
 
     fn try_from(value: FillingEvent) -> Result<Self, Self::Error> {
+        if value.is_city_missed()
+            || value.is_location_title_missed()
+            || value.is_meeting_url_missed()
+        {
+            return Err(BotHandlerError::UnfilledEvent);
+        }
+
+        let event_type = match value.event_format {
+            EventFormat::Online => EventType::Online,
+            EventFormat::Offline | EventFormat::Hybrid => EventType::OfflineMeetup,
+        };
+
         Ok(BaseEvent {
             id: Uuid::nil(),
-            is_private: false,
-            is_commercial: false,
-            is_online: false,
-            is_paid: false,
+            is_private: value.is_private,
+            invite_token: None,
+            is_commercial: value.price.is_some(),
+            is_online: value.event_format.is_online(),
+            is_paid: value.price.is_some(),
+            event_format: value.event_format,
+            price: value.price,
             event_kind: value.event_kind,
             title: value.title.ok_or(BotHandlerError::UnfilledEvent)?,
             description: value.description.ok_or(BotHandlerError::UnfilledEvent)?,
             brief_description: value.brief_description,
             subject: value.subject.ok_or(BotHandlerError::UnfilledEvent)?,
             datetime_from: value.datetime_from.ok_or(BotHandlerError::UnfilledEvent)?,
-            // timezone: chrono_tz::Tz::Europe__Moscow,
             datetime_to: value.datetime_to,
+            timezone: value.timezone,
             location: value.geo_position,
-            location_title: value.location_title.ok_or(BotHandlerError::UnfilledEvent)?,
+            city: value.city.filter(|_| value.event_format.has_venue()),
+            location_title: value.location_title,
+            meeting_url: value.meeting_url,
+            capacity: value.capacity,
             creator_id: value.creator_id,
-            event_type: Default::default(),
+            community_id: value.community_id,
+            event_type,
             picture: value.picture,
             // creation_time: Default::default(),
             creation_time: chrono::offset::Local::now().naive_local(),
             contact_info: value.contact_info,
+            recurrence: value.recurrence,
+            participants_count: 0,
         })
     }
 }
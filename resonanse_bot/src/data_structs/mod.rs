@@ -1,5 +1,7 @@
 pub use event_repr::*;
 pub use filling_event::*;
+pub use profile_field::*;
 
 mod event_repr;
 mod filling_event;
+mod profile_field;
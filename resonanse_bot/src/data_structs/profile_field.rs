@@ -0,0 +1,287 @@
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use resonanse_common::models::{City, UserData, CITIES};
+
+use crate::locales::SUPPORTED_LOCALES;
+
+/// Message which clears optional profile field
+pub const PROFILE_CLEAR_VALUE: &str = "-";
+pub const AGE_LIMIT: RangeInclusive<i16> = 14..=120;
+
+/// Profile fields which user fills in the bot, in order of profile view
+pub const PROFILE_FIELDS: [ProfileField; 15] = [
+    ProfileField::City,
+    ProfileField::Headline,
+    ProfileField::About,
+    ProfileField::Goals,
+    ProfileField::Interests,
+    ProfileField::Language,
+    ProfileField::Age,
+    ProfileField::Education,
+    ProfileField::Hobby,
+    ProfileField::Music,
+    ProfileField::Sport,
+    ProfileField::Books,
+    ProfileField::Food,
+    ProfileField::Worldview,
+    ProfileField::Alcohol,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProfileField {
+    City,
+    Headline,
+    About,
+    Goals,
+    Interests,
+    Language,
+    Age,
+    Education,
+    Hobby,
+    Music,
+    Sport,
+    Books,
+    Food,
+    Worldview,
+    Alcohol,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ProfileFieldError {
+    Required,
+    InvalidLength(RangeInclusive<usize>),
+    InvalidAge,
+    UnknownCity,
+}
+
+/// Finds city by its name in any of supported locales
+pub fn parse_city_name(name: &str) -> Option<City> {
+    let name = name.trim().to_lowercase();
+    CITIES.into_iter().find(|city| {
+        SUPPORTED_LOCALES
+            .iter()
+            .any(|locale| t!(&city.to_string(), locale = locale).to_lowercase() == name)
+    })
+}
+
+impl ProfileField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProfileField::City => "city",
+            ProfileField::Headline => "headline",
+            ProfileField::About => "about",
+            ProfileField::Goals => "goals",
+            ProfileField::Interests => "interests",
+            ProfileField::Language => "language",
+            ProfileField::Age => "age",
+            ProfileField::Education => "education",
+            ProfileField::Hobby => "hobby",
+            ProfileField::Music => "music",
+            ProfileField::Sport => "sport",
+            ProfileField::Books => "books",
+            ProfileField::Food => "food",
+            ProfileField::Worldview => "worldview",
+            ProfileField::Alcohol => "alcohol",
+        }
+    }
+
+    pub fn translation_key(&self) -> &'static str {
+        match self {
+            ProfileField::City => "profile.city",
+            ProfileField::Headline => "profile.headline",
+            ProfileField::About => "profile.about",
+            ProfileField::Goals => "profile.goals",
+            ProfileField::Interests => "profile.interests",
+            ProfileField::Language => "profile.language",
+            ProfileField::Age => "profile.age",
+            ProfileField::Education => "profile.education",
+            ProfileField::Hobby => "profile.hobby",
+            ProfileField::Music => "profile.music",
+            ProfileField::Sport => "profile.sport",
+            ProfileField::Books => "profile.books",
+            ProfileField::Food => "profile.food",
+            ProfileField::Worldview => "profile.worldview",
+            ProfileField::Alcohol => "profile.alcohol",
+        }
+    }
+
+    /// Limits are below sizes of `user_accounts` columns
+    pub fn text_limit(&self) -> RangeInclusive<usize> {
+        match self {
+            ProfileField::City => 2..=100,
+            ProfileField::Headline => 3..=100,
+            ProfileField::About => 1..=1000,
+            _ => 1..=255,
+        }
+    }
+
+    pub fn is_required(&self) -> bool {
+        matches!(self, ProfileField::City)
+    }
+
+    pub fn get_value(&self, user_data: &UserData) -> Option<String> {
+        let value = match self {
+            ProfileField::City => user_data.city.map(|city| t!(&city.to_string())),
+            ProfileField::Headline => user_data.headline.clone(),
+            ProfileField::About => Some(user_data.about.clone()),
+            ProfileField::Goals => user_data.goals.clone(),
+            ProfileField::Interests => user_data.interests.clone(),
+            ProfileField::Language => user_data.language.clone(),
+            ProfileField::Age => user_data.age.map(|age| age.to_string()),
+            ProfileField::Education => user_data.education.clone(),
+            ProfileField::Hobby => user_data.hobby.clone(),
+            ProfileField::Music => user_data.music.clone(),
+            ProfileField::Sport => user_data.sport.clone(),
+            ProfileField::Books => user_data.books.clone(),
+            ProfileField::Food => user_data.food.clone(),
+            ProfileField::Worldview => user_data.worldview.clone(),
+            ProfileField::Alcohol => user_data.alcohol.clone(),
+        };
+        value.filter(|v| !v.is_empty())
+    }
+
+    /// Validates user input and sets it to profile, [`PROFILE_CLEAR_VALUE`] clears optional field
+    pub fn set_value(
+        &self,
+        user_data: &mut UserData,
+        input: &str,
+    ) -> Result<(), ProfileFieldError> {
+        let input = input.trim();
+        let value = match input {
+            PROFILE_CLEAR_VALUE if self.is_required() => return Err(ProfileFieldError::Required),
+            PROFILE_CLEAR_VALUE => None,
+            _ => {
+                let text_limit = self.text_limit();
+                if !text_limit.contains(&input.chars().count()) {
+                    return Err(ProfileFieldError::InvalidLength(text_limit));
+                }
+                Some(input.to_string())
+            }
+        };
+
+        match self {
+            ProfileField::City => {
+                user_data.city = match value.as_deref().and_then(parse_city_name) {
+                    None => return Err(ProfileFieldError::UnknownCity),
+                    city => city,
+                }
+            }
+            ProfileField::Headline => user_data.headline = value,
+            ProfileField::About => user_data.about = value.unwrap_or_default(),
+            ProfileField::Goals => user_data.goals = value,
+            ProfileField::Interests => user_data.interests = value,
+            ProfileField::Language => user_data.language = value,
+            ProfileField::Age => {
+                user_data.age = match value {
+                    None => None,
+                    Some(v) => match v.parse::<i16>() {
+                        Ok(age) if AGE_LIMIT.contains(&age) => Some(age),
+                        _ => return Err(ProfileFieldError::InvalidAge),
+                    },
+                }
+            }
+            ProfileField::Education => user_data.education = value,
+            ProfileField::Hobby => user_data.hobby = value,
+            ProfileField::Music => user_data.music = value,
+            ProfileField::Sport => user_data.sport = value,
+            ProfileField::Books => user_data.books = value,
+            ProfileField::Food => user_data.food = value,
+            ProfileField::Worldview => user_data.worldview = value,
+            ProfileField::Alcohol => user_data.alcohol = value,
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for ProfileField {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PROFILE_FIELDS
+            .into_iter()
+            .find(|field| field.as_str() == s)
+            .ok_or(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_user_data() -> UserData {
+        UserData {
+            first_name: "Name".to_string(),
+            last_name: "".to_string(),
+            city: Some(City::SaintPetersburg),
+            headline: None,
+            about: "".to_string(),
+            goals: None,
+            interests: None,
+            language: None,
+            age: None,
+            education: None,
+            hobby: None,
+            music: None,
+            sport: None,
+            books: None,
+            food: None,
+            worldview: None,
+            alcohol: None,
+            timezone: None,
+            locale: None,
+        }
+    }
+
+    #[test]
+    fn validates_profile_fields() {
+        let mut user_data = empty_user_data();
+
+        assert_eq!(
+            ProfileField::Hobby.set_value(&mut user_data, " chess "),
+            Ok(())
+        );
+        assert_eq!(user_data.hobby.as_deref(), Some("chess"));
+        assert_eq!(ProfileField::Hobby.set_value(&mut user_data, "-"), Ok(()));
+        assert_eq!(user_data.hobby, None);
+
+        assert_eq!(
+            ProfileField::City.set_value(&mut user_data, "-"),
+            Err(ProfileFieldError::Required)
+        );
+        assert_eq!(
+            ProfileField::City.set_value(&mut user_data, "Тверь"),
+            Err(ProfileFieldError::UnknownCity)
+        );
+        assert_eq!(
+            ProfileField::City.set_value(&mut user_data, "москва"),
+            Ok(())
+        );
+        assert_eq!(user_data.city, Some(City::Moscow));
+        assert_eq!(
+            ProfileField::City.set_value(&mut user_data, "Kazan"),
+            Ok(())
+        );
+        assert_eq!(user_data.city, Some(City::Kazan));
+        assert_eq!(
+            ProfileField::Headline.set_value(&mut user_data, &"a".repeat(101)),
+            Err(ProfileFieldError::InvalidLength(3..=100))
+        );
+        assert_eq!(ProfileField::About.get_value(&user_data), None);
+
+        assert_eq!(ProfileField::Age.set_value(&mut user_data, "27"), Ok(()));
+        assert_eq!(user_data.age, Some(27));
+        for invalid_age in ["7", "200", "много"] {
+            assert_eq!(
+                ProfileField::Age.set_value(&mut user_data, invalid_age),
+                Err(ProfileFieldError::InvalidAge)
+            );
+        }
+        assert_eq!(user_data.age, Some(27));
+
+        for field in PROFILE_FIELDS {
+            assert_eq!(ProfileField::from_str(field.as_str()), Ok(field));
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use log::{debug, warn};
+
+use resonanse_common::models::{City, Location};
+
+use crate::data_structs::FillingEvent;
+
+const ICS_DATETIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+const ICS_DATE_FORMAT: &str = "%Y%m%d";
+
+/// Single content line of iCalendar document (RFC 5545, section 3.1)
+struct IcsProperty {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl IcsProperty {
+    fn parse(line: &str) -> Option<Self> {
+        // value starts after first colon that is not inside quoted param value
+        let mut in_quotes = false;
+        let mut value_start = None;
+        for (i, c) in line.char_indices() {
+            match c {
+                '"' => in_quotes = !in_quotes,
+                ':' if !in_quotes => {
+                    value_start = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let value_start = value_start?;
+
+        let (name_with_params, value) = (&line[..value_start], &line[value_start + 1..]);
+        let mut parts = name_with_params.split(';');
+        let name = parts.next()?.trim().to_ascii_uppercase();
+        let params = parts
+            .filter_map(|param| {
+                let (k, v) = param.split_once('=')?;
+                Some((
+                    k.trim().to_ascii_uppercase(),
+                    v.trim_matches('"').to_string(),
+                ))
+            })
+            .collect();
+
+        Some(Self {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    fn get_param(&self, param_name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == param_name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Joins folded lines: line starting with space or tab continues previous one
+fn unfold_lines(ics_text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw_line in ics_text.lines() {
+        match raw_line.strip_prefix([' ', '\t']) {
+            Some(continuation) if !lines.is_empty() => {
+                if let Some(last_line) = lines.last_mut() {
+                    last_line.push_str(continuation);
+                }
+            }
+            _ => lines.push(raw_line.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => result.push('\n'),
+            Some(escaped) => result.push(escaped),
+            None => result.push('\\'),
+        }
+    }
+    result.trim().to_string()
+}
+
+/// Converts DTSTART/DTEND value to wall-clock time of event in `event_tz`.
+/// Values with TZID are kept as is, TZID is expected to be applied to event by caller
+fn parse_ics_datetime(property: &IcsProperty, event_tz: Tz) -> Option<NaiveDateTime> {
+    let value = property.value.trim();
+
+    if property.get_param("VALUE") == Some("DATE") || !value.contains('T') {
+        return NaiveDate::parse_from_str(value, ICS_DATE_FORMAT)
+            .ok()?
+            .and_hms_opt(0, 0, 0);
+    }
+
+    if let Some(utc_value) = value.strip_suffix('Z') {
+        let utc_dt: DateTime<Utc> = Utc.from_utc_datetime(
+            &NaiveDateTime::parse_from_str(utc_value, ICS_DATETIME_FORMAT).ok()?,
+        );
+        return Some(utc_dt.with_timezone(&event_tz).naive_local());
+    }
+
+    // local time, either floating or with TZID - both are wall-clock time already
+    NaiveDateTime::parse_from_str(value, ICS_DATETIME_FORMAT).ok()
+}
+
+fn parse_ics_geo(value: &str) -> Option<Location> {
+    let (latitude, longitude) = value.split_once(';')?;
+    Some(Location::from_ll(
+        latitude.trim().parse().ok()?,
+        longitude.trim().parse().ok()?,
+    ))
+}
+
+/// Parses VEVENT components of iCalendar document into event drafts.
+/// Drafts are not validated, user should check them before publication.
+/// `default_tz` is used when neither calendar nor event specify timezone
+pub fn parse_ics_to_filling_events(ics_text: &str, default_tz: Tz) -> Vec<FillingEvent> {
+    let properties = unfold_lines(ics_text)
+        .iter()
+        .filter_map(|line| IcsProperty::parse(line))
+        .collect::<Vec<_>>();
+
+    let calendar_tz = properties
+        .iter()
+        .find(|p| p.name == "X-WR-TIMEZONE")
+        .and_then(|p| p.value.trim().parse::<Tz>().ok())
+        .unwrap_or(default_tz);
+
+    let mut filling_events = Vec::new();
+    let mut current_event: Option<FillingEvent> = None;
+    // nested components of VEVENT (like VALARM) must not override event fields
+    let mut nested_depth = 0usize;
+
+    for property in properties.iter() {
+        match (property.name.as_str(), property.value.trim()) {
+            ("BEGIN", "VEVENT") => {
+                let mut filling_event = FillingEvent::new();
+                filling_event.timezone = calendar_tz;
+                current_event = Some(filling_event);
+                nested_depth = 0;
+            }
+            ("END", "VEVENT") => {
+                if let Some(filling_event) = current_event.take() {
+                    filling_events.push(filling_event);
+                }
+            }
+            ("BEGIN", _) if current_event.is_some() => nested_depth += 1,
+            ("END", _) if current_event.is_some() => nested_depth = nested_depth.saturating_sub(1),
+            _ => {
+                let Some(filling_event) = current_event.as_mut() else {
+                    continue;
+                };
+                if nested_depth > 0 {
+                    continue;
+                }
+                fill_event_property(filling_event, property);
+            }
+        }
+    }
+
+    if current_event.is_some() {
+        warn!("ics document has unclosed VEVENT, skipping it");
+    }
+    debug!("parsed {} events from ics document", filling_events.len());
+
+    filling_events
+}
+
+fn fill_event_property(filling_event: &mut FillingEvent, property: &IcsProperty) {
+    match property.name.as_str() {
+        "SUMMARY" => filling_event.title = Some(unescape_text(&property.value)),
+        "DESCRIPTION" => filling_event.description = Some(unescape_text(&property.value)),
+        "LOCATION" => filling_event.location_title = Some(unescape_text(&property.value)),
+        "DTSTART" => {
+            if let Some(event_tz) = property.get_param("TZID").and_then(|tz| tz.parse().ok()) {
+                filling_event.timezone = event_tz;
+            }
+            filling_event.datetime_from = parse_ics_datetime(property, filling_event.timezone);
+        }
+        "DTEND" => filling_event.datetime_to = parse_ics_datetime(property, filling_event.timezone),
+        "GEO" => {
+            filling_event.geo_position = parse_ics_geo(&property.value);
+            filling_event.city = filling_event
+                .geo_position
+                .as_ref()
+                .and_then(City::from_location);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ICS_SAMPLE: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+X-WR-TIMEZONE:Europe/Moscow\r
+BEGIN:VEVENT\r
+DTSTART;TZID=Europe/Moscow:20231215T190000\r
+DTEND:20231215T190000Z\r
+SUMMARY:Run club\\, weekly\r
+DESCRIPTION:Meet near the park entrance.\\nBring water\r
+ , please\r
+LOCATION:Central park\r
+GEO:59.9386;30.3141\r
+BEGIN:VALARM\r
+DESCRIPTION:Reminder\r
+END:VALARM\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+DTSTART;VALUE=DATE:20231231\r
+SUMMARY:New year\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    #[test]
+    fn parses_vevents() {
+        let events = parse_ics_to_filling_events(ICS_SAMPLE, chrono_tz::UTC);
+        assert_eq!(events.len(), 2);
+
+        let run_club = &events[0];
+        assert_eq!(run_club.timezone, chrono_tz::Europe::Moscow);
+        assert_eq!(run_club.title.as_deref(), Some("Run club, weekly"));
+        assert_eq!(
+            run_club.description.as_deref(),
+            Some("Meet near the park entrance.\nBring water, please")
+        );
+        assert_eq!(run_club.location_title.as_deref(), Some("Central park"));
+        assert_eq!(
+            run_club.datetime_from,
+            NaiveDate::from_ymd_opt(2023, 12, 15).and_then(|d| d.and_hms_opt(19, 0, 0))
+        );
+        assert_eq!(
+            run_club.datetime_to,
+            NaiveDate::from_ymd_opt(2023, 12, 15).and_then(|d| d.and_hms_opt(22, 0, 0))
+        );
+        let geo = run_club.geo_position.as_ref().unwrap();
+        assert_eq!((geo.latitude, geo.longitude), (59.9386, 30.3141));
+        assert_eq!(run_club.city, Some(City::SaintPetersburg));
+
+        let new_year = &events[1];
+        assert_eq!(
+            new_year.datetime_from,
+            NaiveDate::from_ymd_opt(2023, 12, 31).and_then(|d| d.and_hms_opt(0, 0, 0))
+        );
+        assert!(new_year.description.is_none());
+    }
+}
@@ -1,14 +1,20 @@
+pub use icalendar::*;
+
+mod icalendar;
+
 use log::warn;
 use resonanse_common::models::{
     AuthData, BaseAccount, ResoAccountType, UserContactData, UserData, UserTgData,
 };
 use std::cmp::Ordering;
 
+use crate::locales::supported_locale;
+
 pub fn fill_base_account_from_teloxide_user(user: &teloxide::types::User) -> BaseAccount {
     let user_data = UserData {
         first_name: user.first_name.clone(),
         last_name: user.last_name.clone().unwrap_or("".to_string()),
-        city: "Saint-Petersburg".to_string(),
+        city: None,
         headline: None,
         about: "".to_string(),
         goals: None,
@@ -23,6 +29,8 @@ pub fn fill_base_account_from_teloxide_user(user: &teloxide::types::User) -> Bas
         food: None,
         worldview: None,
         alcohol: None,
+        timezone: None,
+        locale: supported_locale(user.language_code.as_deref()).map(|l| l.to_string()),
     };
 
     let tg_user_id = match user.id.0.cmp(&(i64::MAX as u64)) {
@@ -0,0 +1,476 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+use crate::config::DEFAULT_DATETIME_FORMAT;
+
+const DATETIME_FORMAT_1: &str = "%d/%m/%Y %H:%M";
+const DATETIME_FORMAT_2: &str = "%d.%m.%Y %H.%M";
+const DATETIME_FORMAT_3: &str = "%d-%m-%Y %H:%M";
+
+const STRICT_DATETIME_FORMATS: &[&str] = &[
+    DEFAULT_DATETIME_FORMAT,
+    DATETIME_FORMAT_1,
+    DATETIME_FORMAT_2,
+    DATETIME_FORMAT_3,
+];
+
+/// Words that do not change meaning of phrase ("в субботу", "at 7pm")
+const FILLER_WORDS: &[&str] = &[
+    "в", "во", "на", "at", "on", "the", "of", "г", "года", "this",
+];
+/// Fillers after which "19.00" is time rather than day and month
+const TIME_PREPOSITIONS: &[&str] = &["в", "во", "at"];
+const NEXT_WORDS: &[&str] = &["next", "следующий", "следующую", "следующее", "следующей"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DayPart {
+    Am,
+    Pm,
+    /// "дня" - afternoon, "в 3 дня" is 15:00
+    Afternoon,
+    /// "ночи" - "в 12 ночи" is 00:00
+    Night,
+}
+
+impl DayPart {
+    fn parse(token: &str) -> Option<Self> {
+        match token {
+            "am" | "a.m." | "утра" => Some(Self::Am),
+            "pm" | "p.m." | "вечера" => Some(Self::Pm),
+            "дня" => Some(Self::Afternoon),
+            "ночи" => Some(Self::Night),
+            _ => None,
+        }
+    }
+
+    fn apply(self, hour: u32) -> Option<u32> {
+        let hour = match self {
+            Self::Am | Self::Night if hour == 12 => 0,
+            Self::Am | Self::Night => hour,
+            Self::Pm if hour < 12 => hour + 12,
+            Self::Afternoon if hour <= 6 => hour + 12,
+            Self::Pm | Self::Afternoon => hour,
+        };
+        (hour < 24).then_some(hour)
+    }
+}
+
+/// Parts of phrase recognized independently of each other
+#[derive(Default, Debug)]
+struct ParsedPhrase {
+    date: Option<NaiveDate>,
+    day_month: Option<(u32, u32)>,
+    year: Option<i32>,
+    weekday: Option<(Weekday, bool)>,
+    relative_days: Option<i64>,
+    time: Option<NaiveTime>,
+    offset: Option<Duration>,
+}
+
+fn tokenize(input: &str) -> Vec<String> {
+    input
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_string())
+        .collect()
+}
+
+fn parse_weekday(token: &str) -> Option<Weekday> {
+    let weekday = match token {
+        "пн" | "mon" => Weekday::Mon,
+        "вт" | "tue" | "tues" => Weekday::Tue,
+        "ср" | "wed" => Weekday::Wed,
+        "чт" | "thu" | "thurs" => Weekday::Thu,
+        "пт" | "fri" => Weekday::Fri,
+        "сб" | "sat" => Weekday::Sat,
+        "вс" | "sun" => Weekday::Sun,
+        t if t.starts_with("понедельн") || t == "monday" => Weekday::Mon,
+        t if t.starts_with("вторник") || t == "tuesday" => Weekday::Tue,
+        t if t.starts_with("сред") || t == "wednesday" => Weekday::Wed,
+        t if t.starts_with("четверг") || t == "thursday" => Weekday::Thu,
+        t if t.starts_with("пятниц") || t == "friday" => Weekday::Fri,
+        t if t.starts_with("суббот") || t == "saturday" => Weekday::Sat,
+        t if t.starts_with("воскресень") || t == "sunday" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+fn parse_month(token: &str) -> Option<u32> {
+    if matches!(token, "май" | "мая" | "may") {
+        return Some(5);
+    }
+
+    const MONTH_PREFIXES: [(&str, &str, u32); 11] = [
+        ("янв", "jan", 1),
+        ("фев", "feb", 2),
+        ("мар", "mar", 3),
+        ("апр", "apr", 4),
+        ("июн", "jun", 6),
+        ("июл", "jul", 7),
+        ("авг", "aug", 8),
+        ("сен", "sep", 9),
+        ("окт", "oct", 10),
+        ("ноя", "nov", 11),
+        ("дек", "dec", 12),
+    ];
+
+    MONTH_PREFIXES
+        .iter()
+        .find(|(ru_prefix, en_prefix, _)| {
+            token.starts_with(ru_prefix) || token.starts_with(en_prefix)
+        })
+        .map(|(_, _, month)| *month)
+}
+
+fn parse_relative_day(token: &str) -> Option<i64> {
+    match token {
+        "сегодня" | "today" => Some(0),
+        "завтра" | "tomorrow" => Some(1),
+        "послезавтра" => Some(2),
+        _ => None,
+    }
+}
+
+/// Day of month, allows english ordinal suffixes ("25th")
+fn parse_day_of_month(token: &str) -> Option<u32> {
+    let token = ["st", "nd", "rd", "th", "-го", "го"]
+        .iter()
+        .find_map(|suffix| token.strip_suffix(suffix))
+        .unwrap_or(token);
+    let day = token.parse::<u32>().ok()?;
+    (1..=31).contains(&day).then_some(day)
+}
+
+/// "19:00", "7pm", "7:30pm", "7"
+fn parse_time_token(token: &str) -> Option<(u32, u32, Option<DayPart>)> {
+    let (token, day_part) = match ["am", "pm"]
+        .iter()
+        .find_map(|suffix| token.strip_suffix(suffix).map(|rest| (rest, *suffix)))
+    {
+        Some((rest, suffix)) => (rest, DayPart::parse(suffix)),
+        None => (token, None),
+    };
+
+    let (hours_str, minutes_str) = token.split_once(':').unwrap_or((token, "0"));
+    let hours = hours_str.parse::<u32>().ok()?;
+    let minutes = minutes_str.parse::<u32>().ok()?;
+    if hours > 23 || minutes > 59 || hours_str.is_empty() {
+        return None;
+    }
+
+    Some((hours, minutes, day_part))
+}
+
+fn is_hour_word(token: &str) -> bool {
+    matches!(token, "час" | "часа" | "часов" | "o'clock" | "ч")
+}
+
+fn parse_duration_unit(token: &str) -> Option<Duration> {
+    match token {
+        t if t.starts_with("минут") || t.starts_with("min") => Some(Duration::minutes(1)),
+        t if t.starts_with("час") || t.starts_with("hour") => Some(Duration::hours(1)),
+        t if t == "день" || t == "дня" || t == "дней" || t.starts_with("day") => {
+            Some(Duration::days(1))
+        }
+        t if t.starts_with("недел") || t.starts_with("week") => Some(Duration::weeks(1)),
+        _ => None,
+    }
+}
+
+impl ParsedPhrase {
+    fn set_time(&mut self, hours: u32, minutes: u32, day_part: Option<DayPart>) -> Option<()> {
+        let hours = match day_part {
+            Some(day_part) => day_part.apply(hours)?,
+            None => hours,
+        };
+        if self.time.is_some() {
+            return None;
+        }
+        self.time = Some(NaiveTime::from_hms_opt(hours, minutes, 0)?);
+        Some(())
+    }
+
+    /// Consumes tokens starting from `tokens[0]`, returns number of consumed tokens.
+    /// `filler` is filler word which precedes the tokens
+    fn consume(&mut self, tokens: &[String], filler: Option<&str>) -> Option<usize> {
+        let token = tokens[0].as_str();
+        let next_token = tokens.get(1).map(|t| t.as_str());
+
+        if let Some(days) = parse_relative_day(token) {
+            self.relative_days = Some(days);
+            return Some(1);
+        }
+        if let Some(weekday) = parse_weekday(token) {
+            self.weekday = Some((weekday, false));
+            return Some(1);
+        }
+        if NEXT_WORDS.contains(&token) {
+            let weekday = parse_weekday(next_token?)?;
+            self.weekday = Some((weekday, true));
+            return Some(2);
+        }
+        match token {
+            "полдень" | "noon" => return self.set_time(12, 0, None).map(|_| 1),
+            "полночь" | "midnight" => return self.set_time(0, 0, None).map(|_| 1),
+            _ => {}
+        }
+
+        // "через 2 часа", "in 3 days"
+        if token == "через" || token == "in" {
+            let (amount, unit_token_i) = match next_token.and_then(|t| t.parse::<i32>().ok()) {
+                // event cannot start in the past
+                Some(amount) if amount <= 0 => return None,
+                Some(amount) => (amount, 2),
+                None => (1, 1),
+            };
+            let unit = parse_duration_unit(tokens.get(unit_token_i)?)?;
+            if unit >= Duration::days(1) {
+                self.relative_days = Some((unit * amount).num_days());
+            } else {
+                self.offset = Some(unit * amount);
+            }
+            return Some(unit_token_i + 1);
+        }
+
+        // "25 декабря", "25th december"
+        if let (Some(day), Some(month)) =
+            (parse_day_of_month(token), next_token.and_then(parse_month))
+        {
+            self.day_month = Some((day, month));
+            return Some(2);
+        }
+        // "december 25"
+        if let (Some(month), Some(day)) =
+            (parse_month(token), next_token.and_then(parse_day_of_month))
+        {
+            self.day_month = Some((day, month));
+            return Some(2);
+        }
+        // year after day and month: "25 декабря 2024"
+        // "2024г" is accepted as well
+        let year_token = token.trim_end_matches('г');
+        if self.day_month.is_some() && self.year.is_none() && year_token.chars().count() == 4 {
+            if let Ok(year) = year_token.parse::<i32>() {
+                self.year = Some(year);
+                return Some(1);
+            }
+        }
+
+        // "в 19.00"
+        if filler.is_some_and(|filler| TIME_PREPOSITIONS.contains(&filler)) {
+            if let Some((hours, minutes)) = token
+                .split_once('.')
+                .filter(|(_, minutes_str)| minutes_str.len() == 2)
+                .and_then(|(hours_str, minutes_str)| {
+                    Some((
+                        hours_str.parse::<u32>().ok()?,
+                        minutes_str.parse::<u32>().ok()?,
+                    ))
+                })
+                .filter(|(hours, minutes)| *hours <= 23 && *minutes <= 59)
+            {
+                return self.set_time(hours, minutes, None).map(|_| 1);
+            }
+        }
+
+        // "25.12", "25.12.2024", "25/12"
+        if let Some(date_parts) = token
+            .split_once(['.', '/'])
+            .filter(|(_, rest)| !rest.is_empty())
+        {
+            let (day_str, rest) = date_parts;
+            let (month_str, year_str) = rest.split_once(['.', '/']).unwrap_or((rest, ""));
+            let day = day_str.parse::<u32>().ok()?;
+            let month = month_str.parse::<u32>().ok()?;
+            if year_str.is_empty() {
+                self.day_month = Some((day, month));
+            } else {
+                self.date = Some(NaiveDate::from_ymd_opt(year_str.parse().ok()?, month, day)?);
+            }
+            return Some(1);
+        }
+
+        // "19:00", "7pm", "в 19 часов", "в 7 вечера", "7 pm"
+        let (hours, minutes, day_part) = parse_time_token(token)?;
+        let next_day_part = next_token.and_then(DayPart::parse);
+        let is_explicit_time = token.contains(':')
+            || day_part.is_some()
+            || next_day_part.is_some()
+            || next_token.map(is_hour_word).unwrap_or(false)
+            || filler.is_some();
+        if !is_explicit_time {
+            return None;
+        }
+
+        self.set_time(hours, minutes, day_part.or(next_day_part))?;
+        if next_day_part.is_some() || next_token.map(is_hour_word).unwrap_or(false) {
+            return Some(2);
+        }
+        Some(1)
+    }
+
+    fn resolve(self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        if let Some(offset) = self.offset {
+            let has_other_parts = self.date.is_some()
+                || self.day_month.is_some()
+                || self.weekday.is_some()
+                || self.relative_days.is_some()
+                || self.time.is_some();
+            // amount is typed by user, so it may be out of datetime range
+            return now.checked_add_signed(offset).filter(|_| !has_other_parts);
+        }
+
+        let today = now.date();
+        let date = if let Some(date) = self.date {
+            date
+        } else if let Some((day, month)) = self.day_month {
+            match self.year {
+                Some(year) => NaiveDate::from_ymd_opt(year, month, day)?,
+                None => {
+                    // date without year is the nearest such date in future
+                    let date = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+                    if date < today {
+                        NaiveDate::from_ymd_opt(today.year() + 1, month, day)?
+                    } else {
+                        date
+                    }
+                }
+            }
+        } else if let Some((weekday, is_next)) = self.weekday {
+            let mut days_ahead = (weekday.num_days_from_monday() as i64
+                - today.weekday().num_days_from_monday() as i64)
+                .rem_euclid(7);
+            let is_passed_today = self.time.map(|time| time <= now.time()).unwrap_or(false);
+            if days_ahead == 0 && (is_next || is_passed_today) {
+                days_ahead = 7;
+            }
+            today + Duration::days(days_ahead)
+        } else if let Some(days) = self.relative_days {
+            today.checked_add_signed(Duration::days(days))?
+        } else {
+            // only time is given: today, or tomorrow if time has passed
+            let time = self.time?;
+            if time <= now.time() {
+                today + Duration::days(1)
+            } else {
+                today
+            }
+        };
+
+        Some(date.and_time(self.time.unwrap_or(NaiveTime::MIN)))
+    }
+}
+
+/// Parses datetime written in strict format ("25.12.2023 19:00")
+/// or as russian/english phrase ("завтра в 19:00", "next friday 7pm", "25 декабря").
+/// Relative phrases are resolved against `now`, which is expected to be wall-clock time
+/// of event timezone. Date without time is resolved to the start of the day
+pub fn parse_human_datetime(input: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let input = input.trim();
+    if let Some(dt) = STRICT_DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+    {
+        return Some(dt);
+    }
+
+    let tokens = tokenize(input);
+    if tokens.is_empty() {
+        return None;
+    }
+
+    let mut phrase = ParsedPhrase::default();
+    let mut token_i = 0;
+    let mut filler = None;
+    while token_i < tokens.len() {
+        if FILLER_WORDS.contains(&tokens[token_i].as_str()) {
+            filler = Some(tokens[token_i].as_str());
+            token_i += 1;
+            continue;
+        }
+
+        token_i += phrase.consume(&tokens[token_i..], filler)?;
+        filler = None;
+    }
+
+    phrase.resolve(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dt(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .and_then(|date| date.and_hms_opt(h, min, 0))
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_human_datetime() {
+        // wednesday
+        let now = dt(2023, 12, 13, 12, 0);
+
+        let cases = [
+            ("13.12.2023 19:00", Some(dt(2023, 12, 13, 19, 0))),
+            ("13/12/2023 19:00", Some(dt(2023, 12, 13, 19, 0))),
+            ("завтра в 19:00", Some(dt(2023, 12, 14, 19, 0))),
+            ("Завтра, 19:00", Some(dt(2023, 12, 14, 19, 0))),
+            ("послезавтра 10:00", Some(dt(2023, 12, 15, 10, 0))),
+            ("сегодня в 7 вечера", Some(dt(2023, 12, 13, 19, 0))),
+            ("в 3 дня", Some(dt(2023, 12, 13, 15, 0))),
+            ("в 19 часов", Some(dt(2023, 12, 13, 19, 0))),
+            ("в 10:00", Some(dt(2023, 12, 14, 10, 0))),
+            ("в 19.00", Some(dt(2023, 12, 13, 19, 0))),
+            ("завтра в 9.30", Some(dt(2023, 12, 14, 9, 30))),
+            ("в 25.12", Some(dt(2023, 12, 25, 0, 0))),
+            ("в субботу 15:30", Some(dt(2023, 12, 16, 15, 30))),
+            ("в среду 18:00", Some(dt(2023, 12, 13, 18, 0))),
+            ("в среду 10:00", Some(dt(2023, 12, 20, 10, 0))),
+            ("в следующую среду 18:00", Some(dt(2023, 12, 20, 18, 0))),
+            ("пт 20:00", Some(dt(2023, 12, 15, 20, 0))),
+            ("25 декабря", Some(dt(2023, 12, 25, 0, 0))),
+            ("25 декабря в 18:00", Some(dt(2023, 12, 25, 18, 0))),
+            ("25 декабря 2024 18:00", Some(dt(2024, 12, 25, 18, 0))),
+            ("25 декабря 2024г 18:00", Some(dt(2024, 12, 25, 18, 0))),
+            ("5 января", Some(dt(2024, 1, 5, 0, 0))),
+            ("1 мая 12:00", Some(dt(2024, 5, 1, 12, 0))),
+            ("31.12 20:00", Some(dt(2023, 12, 31, 20, 0))),
+            ("31.12.2024 20:00", Some(dt(2024, 12, 31, 20, 0))),
+            ("через 2 часа", Some(dt(2023, 12, 13, 14, 0))),
+            ("через 3 дня в 18:00", Some(dt(2023, 12, 16, 18, 0))),
+            ("в полдень", Some(dt(2023, 12, 14, 12, 0))),
+            ("next friday 7pm", Some(dt(2023, 12, 15, 19, 0))),
+            ("next wednesday 7pm", Some(dt(2023, 12, 20, 19, 0))),
+            ("tomorrow at 9:30am", Some(dt(2023, 12, 14, 9, 30))),
+            ("today 7 pm", Some(dt(2023, 12, 13, 19, 0))),
+            ("dec 31 23:59", Some(dt(2023, 12, 31, 23, 59))),
+            ("December 31st 11pm", Some(dt(2023, 12, 31, 23, 0))),
+            ("on saturday at noon", Some(dt(2023, 12, 16, 12, 0))),
+            ("in 3 days 18:00", Some(dt(2023, 12, 16, 18, 0))),
+            ("in 30 minutes", Some(dt(2023, 12, 13, 12, 30))),
+            ("", None),
+            ("когда-нибудь", None),
+            ("завтра вечером", None),
+            ("32 декабря", None),
+            ("в 25:00", None),
+            ("31.02 10:00", None),
+            ("через 2 часа в 19:00", None),
+            ("через -2 часа", None),
+            ("через 0 дней", None),
+            ("завтра в 19:00 и в 20:00", None),
+            ("через 100000000 дней", None),
+            ("in 999999999 weeks", None),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                parse_human_datetime(input, now),
+                expected,
+                "input: {:?}",
+                input
+            );
+        }
+    }
+}
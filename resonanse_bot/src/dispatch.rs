@@ -2,14 +2,18 @@ use teloxide::dispatching::dialogue::InMemStorage;
 use teloxide::dispatching::{dialogue, UpdateHandler};
 use teloxide::prelude::*;
 
-use crate::commands::Command;
+use crate::commands::{Command, GroupCommand};
+use crate::errors::report_errors;
 use crate::handlers::*;
+use crate::locales::user_locale_scope;
+use crate::metrics::{count_commands, count_updates, measure_callback};
 use crate::states::*;
 
 pub fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>> {
     use dptree::case;
 
     let command_handler = teloxide::filter_command::<Command, _>()
+        .chain(count_commands("resonanse", Command::metric_label))
         // .branch(case![Command::Help].endpoint(help))
         .branch(case![Command::Start].endpoint(start_command))
         .branch(case![Command::About].endpoint(about_command))
@@ -17,18 +21,34 @@ pub fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'stat
         .branch(case![Command::GetEvents].endpoint(get_events_command))
         .branch(case![Command::RunWebApp].endpoint(run_web_app_command))
         .branch(case![Command::SendFeedback].endpoint(send_feedback_command))
-        .branch(case![Command::SendDonation].endpoint(send_donation_command));
+        .branch(case![Command::SendDonation].endpoint(send_donation_command))
+        .branch(case![Command::ImportEvents].endpoint(import_events_command))
+        .branch(case![Command::Drafts].endpoint(drafts_command))
+        .branch(case![Command::Communities].endpoint(communities_command))
+        .branch(case![Command::Following].endpoint(following_command))
+        .branch(case![Command::Profile].endpoint(profile_command))
+        .branch(case![Command::Language].endpoint(language_command));
 
     let message_handler = Update::filter_message()
         .map_async(log_msg_handler)
         .branch(command_handler)
+        .branch(
+            // import replaces dialogue state, so it is not accepted in the middle of another action
+            dptree::filter(ics_document_handler)
+                .branch(case![BaseState::Start].endpoint(handle_ics_document))
+                .branch(case![BaseState::Idle].endpoint(handle_ics_document))
+                .branch(dptree::endpoint(handle_ics_document_in_dialogue)),
+        )
         .branch(case![BaseState::Start].endpoint(handle_start_state))
         .branch(case![BaseState::SendFeedback].endpoint(handle_send_feedback))
+        .branch(case![BaseState::EditProfile { field }].endpoint(handle_edit_profile_state_message))
         .branch(
             case![BaseState::GetEventList {
                 page_size,
                 page_num,
                 events_filter,
+                city_filter,
+                format_filter,
             }]
             .endpoint(handle_get_events),
         )
@@ -37,6 +57,7 @@ pub fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'stat
                 state,
                 filling_event,
                 last_edit_msg_id,
+                pending_drafts,
             }]
             .endpoint(handle_create_event_state_message),
         )
@@ -44,26 +65,118 @@ pub fn schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'stat
 
     let callback_query_handler = Update::filter_callback_query()
         .map_async(log_callback_handler)
-        .branch(dptree::filter(score_event_handler).endpoint(handle_score_event_callback))
-        .branch(
+        .branch(measure_callback(
+            "score_event",
+            dptree::filter(score_event_handler).endpoint(handle_score_event_callback),
+        ))
+        .branch(measure_callback(
+            "cancel_occurrence",
+            dptree::filter(cancel_occurrence_handler).endpoint(handle_cancel_occurrence_callback),
+        ))
+        .branch(measure_callback(
+            "rsvp_event",
+            dptree::filter(rsvp_event_handler).endpoint(handle_rsvp_event_callback),
+        ))
+        .branch(measure_callback(
+            "resume_draft",
+            dptree::filter(resume_draft_handler).endpoint(handle_resume_draft_callback),
+        ))
+        .branch(measure_callback(
+            "group_feed_subject",
+            dptree::filter(group_feed_subject_handler).endpoint(handle_group_feed_subject_callback),
+        ))
+        .branch(measure_callback(
+            "group_feed_city",
+            dptree::filter(group_feed_city_handler).endpoint(handle_group_feed_city_callback),
+        ))
+        .branch(measure_callback(
+            "community_page",
+            dptree::filter(community_page_handler).endpoint(handle_community_page_callback),
+        ))
+        .branch(measure_callback(
+            "community_membership",
+            dptree::filter(community_membership_handler)
+                .endpoint(handle_community_membership_callback),
+        ))
+        .branch(measure_callback(
+            "community_members",
+            dptree::filter(community_members_handler).endpoint(handle_community_members_callback),
+        ))
+        .branch(measure_callback(
+            "remove_community_member",
+            dptree::filter(remove_community_member_handler)
+                .endpoint(handle_remove_community_member_callback),
+        ))
+        .branch(measure_callback(
+            "follow_organizer",
+            dptree::filter(follow_organizer_handler).endpoint(handle_follow_organizer_callback),
+        ))
+        .branch(measure_callback(
+            "unfollow_organizer",
+            dptree::filter(unfollow_organizer_handler).endpoint(handle_unfollow_organizer_callback),
+        ))
+        .branch(measure_callback(
+            "profile_field",
+            dptree::filter(profile_field_handler).endpoint(handle_profile_field_callback),
+        ))
+        .branch(measure_callback(
+            "home_city",
+            dptree::filter(home_city_handler).endpoint(handle_home_city_callback),
+        ))
+        .branch(measure_callback(
+            "choose_language",
+            dptree::filter(choose_language_handler).endpoint(handle_choose_language_callback),
+        ))
+        .branch(measure_callback(
+            "create_event",
             case![BaseState::CreateEvent {
                 state,
                 filling_event,
-                last_edit_msg_id
+                last_edit_msg_id,
+                pending_drafts,
             }]
             .endpoint(handle_create_event_state_callback),
-        )
-        .branch(
+        ))
+        .branch(measure_callback(
+            "get_events",
             case![BaseState::GetEventList {
                 page_size,
                 page_num,
                 events_filter,
+                city_filter,
+                format_filter,
             }]
             .endpoint(handle_get_events_callback),
+        ))
+        .branch(measure_callback(
+            "invalid_state",
+            dptree::endpoint(invalid_state_callback),
+        ));
+
+    // inline queries have no chat, so they are handled outside of dialogue
+    let inline_query_handler = Update::filter_inline_query().endpoint(handle_inline_query);
+
+    // in groups bot only manages event feed, other messages are not addressed to it
+    let group_handler = Update::filter_message()
+        .filter(group_message_handler)
+        .branch(
+            teloxide::filter_command::<GroupCommand, _>()
+                .chain(count_commands("resonanse", GroupCommand::metric_label))
+                .branch(case![GroupCommand::Feed].endpoint(group_feed_command)),
         )
-        .branch(dptree::endpoint(invalid_state_callback));
+        .branch(dptree::endpoint(ignore_group_message));
 
-    dialogue::enter::<Update, InMemStorage<BaseState>, BaseState, _>()
-        .branch(message_handler)
-        .branch(callback_query_handler)
+    let my_chat_member_handler = Update::filter_my_chat_member().endpoint(handle_my_chat_member);
+
+    count_updates("resonanse")
+        .chain(user_locale_scope())
+        .chain(report_errors("resonanse"))
+        .branch(inline_query_handler)
+        .branch(group_handler)
+        .branch(my_chat_member_handler)
+        .branch(
+            dialogue::enter::<Update, InMemStorage<BaseState>, BaseState, _>()
+                .branch(message_handler)
+                .branch(callback_query_handler),
+        )
 }
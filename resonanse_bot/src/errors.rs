@@ -1,21 +1,301 @@
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+use log::{error, warn};
+use teloxide::dispatching::{DpHandlerDescription, UpdateHandler};
+use teloxide::dptree::di::{DependencyMap, DependencySupplier};
+use teloxide::dptree::HandlerDescription;
+use teloxide::prelude::*;
+use teloxide::RequestError;
+
+use crate::config::config;
+use crate::locales::{with_locale, DEFAULT_LOCALE};
+use crate::MANAGER_BOT;
+
+type HandlerError = Box<dyn Error + Send + Sync + 'static>;
+
+/// Telegram message is limited to 4096 characters, the rest of notification is short
+const MAX_NOTIFIED_ERROR_LEN: usize = 3500;
 
 #[derive(Debug)]
 pub enum BotHandlerError {
     UnknownHandler,
     UserInputRejected,
     UnfilledEvent,
+    /// Input is not valid, holds explanation already rendered in user locale
+    Validation(String),
+    /// Requested entity does not exist or is not visible to user
+    NotFound(&'static str),
+    PermissionDenied,
+    Storage(sqlx::Error),
+    Telegram(RequestError),
+}
+
+impl BotHandlerError {
+    /// Variant name, used as metric label
+    pub fn label(&self) -> &'static str {
+        match self {
+            BotHandlerError::UnknownHandler => "UnknownHandler",
+            BotHandlerError::UserInputRejected => "UserInputRejected",
+            BotHandlerError::UnfilledEvent => "UnfilledEvent",
+            BotHandlerError::Validation(_) => "Validation",
+            BotHandlerError::NotFound(_) => "NotFound",
+            BotHandlerError::PermissionDenied => "PermissionDenied",
+            BotHandlerError::Storage(_) => "Storage",
+            BotHandlerError::Telegram(_) => "Telegram",
+        }
+    }
+
+    /// Maps error of entity lookup, missing entity is reported as `NotFound`
+    pub fn lookup(entity: &'static str) -> impl FnOnce(sqlx::Error) -> BotHandlerError {
+        move |err| match err {
+            sqlx::Error::RowNotFound => BotHandlerError::NotFound(entity),
+            err => BotHandlerError::Storage(err),
+        }
+    }
 }
 
 impl Display for BotHandlerError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            BotHandlerError::UnknownHandler => write!(f, "UnknownHandler"),
-            BotHandlerError::UserInputRejected => write!(f, "UserInputRejected"),
-            BotHandlerError::UnfilledEvent => write!(f, "UnfilledEvent"),
+            BotHandlerError::Validation(reason) => write!(f, "Validation: {}", reason),
+            BotHandlerError::NotFound(entity) => write!(f, "NotFound: {}", entity),
+            BotHandlerError::Storage(err) => write!(f, "Storage: {}", err),
+            BotHandlerError::Telegram(err) => write!(f, "Telegram: {}", err),
+            _ => write!(f, "{}", self.label()),
+        }
+    }
+}
+
+impl Error for BotHandlerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BotHandlerError::Storage(err) => Some(err),
+            BotHandlerError::Telegram(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<sqlx::Error> for BotHandlerError {
+    fn from(err: sqlx::Error) -> Self {
+        match err {
+            sqlx::Error::RowNotFound => BotHandlerError::NotFound("row"),
+            err => BotHandlerError::Storage(err),
+        }
+    }
+}
+
+impl From<RequestError> for BotHandlerError {
+    fn from(err: RequestError) -> Self {
+        BotHandlerError::Telegram(err)
+    }
+}
+
+/// Handler errors are mostly boxed by `?` without conversion to `BotHandlerError`,
+/// so storage and telegram errors are recognized in both forms
+enum Cause<'a> {
+    Handler(&'a BotHandlerError),
+    Storage(&'a sqlx::Error),
+    Telegram,
+    Other,
+}
+
+fn cause<'a>(error: &'a (dyn Error + 'static)) -> Cause<'a> {
+    if let Some(error) = error.downcast_ref::<BotHandlerError>() {
+        return match error {
+            BotHandlerError::Storage(err) => Cause::Storage(err),
+            BotHandlerError::Telegram(_) => Cause::Telegram,
+            error => Cause::Handler(error),
+        };
+    }
+    if let Some(err) = error.downcast_ref::<sqlx::Error>() {
+        return Cause::Storage(err);
+    }
+    if error.is::<RequestError>() {
+        return Cause::Telegram;
+    }
+    Cause::Other
+}
+
+/// Message which explains error to user, rendered in current locale.
+/// `None` when handler has already replied to user, or when telegram request has failed,
+/// since reply would likely fail as well
+pub fn user_message(error: &(dyn Error + 'static)) -> Option<String> {
+    let message = match cause(error) {
+        Cause::Handler(BotHandlerError::UnknownHandler | BotHandlerError::UserInputRejected)
+        | Cause::Telegram => return None,
+        Cause::Handler(BotHandlerError::Validation(reason)) => reason.clone(),
+        Cause::Handler(BotHandlerError::UnfilledEvent) => t!("errors.unfilled_event"),
+        Cause::Handler(BotHandlerError::NotFound(_)) | Cause::Storage(sqlx::Error::RowNotFound) => {
+            t!("errors.not_found")
         }
+        Cause::Handler(BotHandlerError::PermissionDenied) => t!("errors.permission"),
+        Cause::Storage(_) => t!("errors.storage"),
+        Cause::Handler(_) | Cause::Other => t!("errors.unexpected"),
+    };
+    Some(message)
+}
+
+/// Error is caused by a bug or an outage of database rather than by user,
+/// telegram errors are not included since notification would likely fail as well
+pub fn is_unexpected(error: &(dyn Error + 'static)) -> bool {
+    match cause(error) {
+        Cause::Storage(sqlx::Error::RowNotFound) => false,
+        Cause::Storage(_) | Cause::Other => true,
+        Cause::Handler(_) | Cause::Telegram => false,
     }
 }
 
-impl Error for BotHandlerError {}
+/// Reports errors of the rest of update handling: logs them with update context,
+/// explains them to user and notifies managers of unexpected ones.
+/// Error is returned further to dispatcher error handler
+pub fn report_errors(bot_name: &'static str) -> UpdateHandler<HandlerError> {
+    dptree::from_fn_with_description(
+        DpHandlerDescription::entry(),
+        move |deps: DependencyMap, cont| async move {
+            let update: Arc<Update> = deps.get();
+            let bot: Arc<Bot> = deps.get();
+            let result: ControlFlow<Result<(), HandlerError>, DependencyMap> = cont(deps).await;
+            if let ControlFlow::Break(Err(error)) = &result {
+                report_error(bot_name, &bot, &update, error.as_ref()).await;
+            }
+            result
+        },
+    )
+}
+
+async fn report_error(
+    bot_name: &'static str,
+    bot: &Bot,
+    update: &Update,
+    error: &(dyn Error + Send + Sync + 'static),
+) {
+    let user_id = update.user().map(|user| user.id.0);
+    let chat_id = update.chat().map(|chat| chat.id);
+    error!(
+        "{} bot has failed to handle update {} from user {:?} in chat {:?}: {:?}",
+        bot_name, update.id, user_id, chat_id, error
+    );
+
+    if let (Some(chat_id), Some(message)) = (chat_id, user_message(error)) {
+        if let Err(err) = bot.send_message(chat_id, message).await {
+            warn!("cannot send error message to chat {}: {:?}", chat_id, err);
+        }
+    }
+
+    if is_unexpected(error) {
+        notify_managers(bot_name, update, error).await;
+    }
+}
+
+async fn notify_managers(
+    bot_name: &'static str,
+    update: &Update,
+    error: &(dyn Error + Send + Sync + 'static),
+) {
+    let Some(manager_bot) = MANAGER_BOT.get() else {
+        warn!("cannot notify managers of error: manager bot is not set");
+        return;
+    };
+
+    let error_text: String = format!("{:?}", error)
+        .chars()
+        .take(MAX_NOTIFIED_ERROR_LEN)
+        .collect();
+    let notification = with_locale(DEFAULT_LOCALE.to_string(), async {
+        t!(
+            "errors.manager_notification",
+            bot = bot_name,
+            update_id = update.id,
+            user = format!("{:?}", update.user().map(|user| user.id.0)),
+            chat = format!("{:?}", update.chat().map(|chat| chat.id.0)),
+            error = error_text
+        )
+    })
+    .await;
+
+    for manager_id in &config().manager_tg_ids {
+        if let Err(err) = manager_bot
+            .send_message(ChatId(*manager_id), notification.clone())
+            .await
+        {
+            warn!("cannot notify manager {} of error: {:?}", manager_id, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_handler_errors() {
+        let error: HandlerError = Box::new(BotHandlerError::UnfilledEvent);
+        assert!(!is_unexpected(error.as_ref()));
+        let error: HandlerError = Box::new(sqlx::Error::RowNotFound);
+        assert!(!is_unexpected(error.as_ref()));
+        let error: HandlerError = Box::new(BotHandlerError::from(sqlx::Error::PoolTimedOut));
+        assert!(is_unexpected(error.as_ref()));
+        let error: HandlerError = Box::new(sqlx::Error::PoolTimedOut);
+        assert!(is_unexpected(error.as_ref()));
+        let error: HandlerError = "Cannot get manager bot".into();
+        assert!(is_unexpected(error.as_ref()));
+
+        assert!(matches!(
+            BotHandlerError::lookup("event")(sqlx::Error::RowNotFound),
+            BotHandlerError::NotFound("event")
+        ));
+        assert!(matches!(
+            BotHandlerError::lookup("event")(sqlx::Error::PoolTimedOut),
+            BotHandlerError::Storage(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn explains_handler_errors_to_user() {
+        let message = |error: HandlerError| {
+            with_locale(
+                "en".to_string(),
+                async move { user_message(error.as_ref()) },
+            )
+        };
+        assert_eq!(
+            message(Box::new(BotHandlerError::Validation(
+                "Too long".to_string()
+            )))
+            .await
+            .as_deref(),
+            Some("Too long")
+        );
+        // handler has already replied to user
+        assert_eq!(
+            message(Box::new(BotHandlerError::UserInputRejected)).await,
+            None
+        );
+        assert_eq!(
+            message(Box::new(BotHandlerError::UnknownHandler)).await,
+            None
+        );
+        assert_eq!(
+            message(Box::new(RequestError::RetryAfter(Default::default()))).await,
+            None
+        );
+        assert_eq!(
+            message(Box::new(sqlx::Error::RowNotFound)).await,
+            message(Box::new(BotHandlerError::NotFound("event"))).await
+        );
+        assert_eq!(
+            message(Box::new(BotHandlerError::PermissionDenied))
+                .await
+                .as_deref(),
+            Some("You do not have permission for this action")
+        );
+        assert_eq!(
+            message("Cannot get manager bot".into()).await.as_deref(),
+            Some("Something went wrong. We have been notified and will fix it")
+        );
+    }
+}
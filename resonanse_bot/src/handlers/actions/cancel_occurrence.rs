@@ -0,0 +1,129 @@
+use std::sync::Arc;
+
+use chrono::{NaiveDateTime, Timelike};
+use log::debug;
+use teloxide::prelude::*;
+use teloxide::Bot;
+use uuid::Uuid;
+
+use resonanse_common::repository::{AccountsRepository, EventsRepository};
+
+use crate::config::DEFAULT_DATETIME_FORMAT;
+use crate::errors::BotHandlerError;
+use crate::handlers::HandlerResult;
+use crate::high_logics::is_event_creator;
+use crate::keyboards;
+
+pub fn cancel_occurrence_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::CANCEL_OCCURRENCE_BTN_PREFIX)
+}
+
+fn parse_cancel_occurrence_data(data: &str) -> Option<(Uuid, NaiveDateTime)> {
+    let (event_id, occurrence_datetime) = data
+        .strip_prefix(keyboards::CANCEL_OCCURRENCE_BTN_PREFIX)?
+        .split_once(':')?;
+
+    Some((
+        Uuid::parse_str(event_id).ok()?,
+        NaiveDateTime::parse_from_str(
+            occurrence_datetime,
+            keyboards::CANCEL_OCCURRENCE_DATETIME_FORMAT,
+        )
+        .ok()?,
+    ))
+}
+
+fn truncate_to_minute(datetime: NaiveDateTime) -> NaiveDateTime {
+    datetime
+        .with_second(0)
+        .and_then(|datetime| datetime.with_nanosecond(0))
+        .unwrap_or(datetime)
+}
+
+pub async fn handle_cancel_occurrence_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
+) -> HandlerResult {
+    debug!("got handle_cancel_occurrence_callback callback");
+
+    bot.answer_callback_query(q.id).await?;
+    let msg = match q.message {
+        None => {
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                .await?;
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+
+    let (event_id, occurrence_datetime) =
+        match q.data.as_deref().and_then(parse_cancel_occurrence_data) {
+            None => {
+                bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                    .await?;
+                return Ok(());
+            }
+            Some(v) => v,
+        };
+
+    let base_event = events_repository
+        .get_event_by_uuid(event_id)
+        .await
+        .map_err(BotHandlerError::lookup("event"))?;
+
+    if !is_event_creator(
+        accounts_repository.as_ref(),
+        q.from.id.0 as i64,
+        &base_event,
+    )
+    .await
+    {
+        bot.send_message(q.from.id, t!("actions.cancel_occurrence.not_allowed"))
+            .await?;
+        return Ok(());
+    }
+
+    // callback data has no room for seconds, so occurrence is matched up to a minute
+    let occurrence = base_event.recurrence.as_ref().and_then(|recurrence| {
+        recurrence
+            .occurrences(base_event.datetime_from)
+            .find(|occurrence| truncate_to_minute(*occurrence) == occurrence_datetime)
+    });
+    let occurrence_datetime = match occurrence {
+        None => {
+            bot.send_message(q.from.id, t!("actions.cancel_occurrence.not_found"))
+                .await?;
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+
+    events_repository
+        .cancel_event_occurrence(event_id, occurrence_datetime)
+        .await?;
+
+    // cancel button is the last row of event keyboard
+    if let Some(inline_kb) = msg.reply_markup() {
+        let mut inline_kb = inline_kb.clone();
+        inline_kb.inline_keyboard.pop();
+        let mut edit_msg = bot.edit_message_reply_markup(msg.chat.id, msg.id);
+        edit_msg.reply_markup = Some(inline_kb);
+        edit_msg.await?;
+    }
+
+    bot.send_message(
+        q.from.id,
+        t!(
+            "actions.cancel_occurrence.done",
+            event_title = base_event.title,
+            occurrence_datetime = occurrence_datetime.format(DEFAULT_DATETIME_FORMAT),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,276 @@
+use std::sync::Arc;
+
+use log::debug;
+use teloxide::prelude::*;
+use teloxide::types::ReplyMarkup;
+use teloxide::Bot;
+
+use resonanse_common::repository::{AccountsRepository, CommunitiesRepository, EventsRepository};
+
+use crate::data_translators::fill_base_account_from_teloxide_user;
+use crate::errors::BotHandlerError;
+use crate::handlers::{log_request, HandlerResult};
+use crate::high_logics::send_community_page;
+use crate::keyboards;
+
+const COMMUNITIES_PAGE_SIZE: i64 = 20;
+
+fn parse_callback_id(q: &CallbackQuery, prefix: &str) -> Option<i64> {
+    q.data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(prefix))
+        .and_then(|id| id.parse::<i64>().ok())
+}
+
+/// Communities of user go first, then public ones
+pub async fn communities_command(
+    bot: Bot,
+    msg: Message,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    communities_repository: Arc<dyn CommunitiesRepository>,
+) -> HandlerResult {
+    log_request("got communities command", &msg);
+    let Some(user) = msg.from() else {
+        return Ok(());
+    };
+
+    let account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(user))
+        .await?;
+    let mut communities = communities_repository
+        .get_account_communities(account.id)
+        .await?;
+    let public_communities = communities_repository
+        .get_public_communities(0, COMMUNITIES_PAGE_SIZE)
+        .await?;
+    for public_community in public_communities {
+        if communities.iter().all(|c| c.id != public_community.id) {
+            communities.push(public_community);
+        }
+    }
+
+    if communities.is_empty() {
+        bot.send_message(msg.chat.id, t!("actions.communities.empty"))
+            .await?;
+        return Ok(());
+    }
+
+    let mut message = bot.send_message(msg.chat.id, t!("actions.communities.choose"));
+    message.reply_markup = Some(ReplyMarkup::InlineKeyboard(
+        keyboards::get_inline_kb_communities(&communities),
+    ));
+    message.await?;
+
+    Ok(())
+}
+
+pub fn community_page_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::COMMUNITY_PAGE_BTN_PREFIX)
+}
+
+pub async fn handle_community_page_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    communities_repository: Arc<dyn CommunitiesRepository>,
+) -> HandlerResult {
+    debug!("got handle_community_page_callback callback");
+
+    bot.answer_callback_query(q.id.clone()).await?;
+    let Some(community_id) = parse_callback_id(&q, keyboards::COMMUNITY_PAGE_BTN_PREFIX) else {
+        bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+            .await?;
+        return Ok(());
+    };
+
+    let community = communities_repository
+        .get_community(community_id)
+        .await
+        .map_err(BotHandlerError::lookup("community"))?;
+    send_community_page(
+        &bot,
+        events_repository.as_ref(),
+        accounts_repository.as_ref(),
+        communities_repository.as_ref(),
+        q.from.id.into(),
+        &q.from,
+        &community,
+    )
+    .await
+}
+
+pub fn community_membership_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::COMMUNITY_MEMBERSHIP_BTN_PREFIX)
+}
+
+/// Joins public community or leaves community user is member of
+pub async fn handle_community_membership_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    communities_repository: Arc<dyn CommunitiesRepository>,
+) -> HandlerResult {
+    debug!("got handle_community_membership_callback callback");
+
+    bot.answer_callback_query(q.id.clone()).await?;
+    let Some(community_id) = parse_callback_id(&q, keyboards::COMMUNITY_MEMBERSHIP_BTN_PREFIX)
+    else {
+        bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+            .await?;
+        return Ok(());
+    };
+
+    let account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(&q.from))
+        .await?;
+    let community = communities_repository
+        .get_community(community_id)
+        .await
+        .map_err(BotHandlerError::lookup("community"))?;
+
+    let msg_text = if communities_repository
+        .is_member(community.id, account.id)
+        .await?
+    {
+        communities_repository
+            .remove_member(community.id, account.id)
+            .await?;
+        t!("actions.communities.left", name = community.name)
+    } else if community.is_private {
+        t!("actions.communities.no_access")
+    } else {
+        communities_repository
+            .add_member(community.id, account.id)
+            .await?;
+        t!("actions.communities.joined", name = community.name)
+    };
+    bot.send_message(q.from.id, msg_text).await?;
+
+    Ok(())
+}
+
+pub fn community_members_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::COMMUNITY_MEMBERS_BTN_PREFIX)
+}
+
+/// Members list is shown only to community owner
+pub async fn handle_community_members_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    communities_repository: Arc<dyn CommunitiesRepository>,
+) -> HandlerResult {
+    debug!("got handle_community_members_callback callback");
+
+    bot.answer_callback_query(q.id.clone()).await?;
+    let Some(community_id) = parse_callback_id(&q, keyboards::COMMUNITY_MEMBERS_BTN_PREFIX) else {
+        bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+            .await?;
+        return Ok(());
+    };
+
+    let account_id = accounts_repository
+        .get_account_id_by_tg_user_id(q.from.id.0 as i64)
+        .await?;
+    let community = communities_repository
+        .get_community(community_id)
+        .await
+        .map_err(BotHandlerError::lookup("community"))?;
+    if community.owner_id != account_id {
+        bot.send_message(q.from.id, t!("actions.communities.owner_only"))
+            .await?;
+        return Ok(());
+    }
+
+    let members = communities_repository.get_members(community.id).await?;
+    let mut message = bot.send_message(
+        q.from.id,
+        t!(
+            "actions.communities.members",
+            name = community.name,
+            members_count = members.len()
+        ),
+    );
+    message.reply_markup = Some(ReplyMarkup::InlineKeyboard(
+        keyboards::get_inline_kb_community_members(&community, &members),
+    ));
+    message.await?;
+
+    Ok(())
+}
+
+pub fn remove_community_member_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::REMOVE_COMMUNITY_MEMBER_BTN_PREFIX)
+}
+
+pub async fn handle_remove_community_member_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    communities_repository: Arc<dyn CommunitiesRepository>,
+) -> HandlerResult {
+    debug!("got handle_remove_community_member_callback callback");
+
+    bot.answer_callback_query(q.id.clone()).await?;
+    let Some((community_id, member_id)) = q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(keyboards::REMOVE_COMMUNITY_MEMBER_BTN_PREFIX))
+        .and_then(|ids| ids.split_once(':'))
+        .and_then(|(community_id, member_id)| {
+            Some((
+                community_id.parse::<i64>().ok()?,
+                member_id.parse::<i64>().ok()?,
+            ))
+        })
+    else {
+        bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+            .await?;
+        return Ok(());
+    };
+
+    let account_id = accounts_repository
+        .get_account_id_by_tg_user_id(q.from.id.0 as i64)
+        .await?;
+    let community = communities_repository
+        .get_community(community_id)
+        .await
+        .map_err(BotHandlerError::lookup("community"))?;
+    if community.owner_id != account_id {
+        bot.send_message(q.from.id, t!("actions.communities.owner_only"))
+            .await?;
+        return Ok(());
+    }
+
+    communities_repository
+        .remove_member(community.id, member_id)
+        .await?;
+
+    if let Some(msg) = q.message {
+        let members = communities_repository.get_members(community.id).await?;
+        bot.edit_message_text(
+            msg.chat.id,
+            msg.id,
+            t!(
+                "actions.communities.members",
+                name = community.name,
+                members_count = members.len()
+            ),
+        )
+        .reply_markup(keyboards::get_inline_kb_community_members(
+            &community, &members,
+        ))
+        .await?;
+    }
+
+    Ok(())
+}
@@ -1,42 +1,56 @@
+use std::error::Error;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
+use std::sync::Arc;
 
-use chrono::NaiveDateTime;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Weekday};
+use chrono_tz::Tz;
 use log::{debug, warn};
 use teloxide::prelude::*;
 use teloxide::types::MessageKind::Common;
 use teloxide::types::ParseMode::MarkdownV2;
 use teloxide::types::{
-    MediaKind, MediaLocation, MediaVenue, MessageCommon, MessageId, ParseMode, ReplyMarkup,
+    InlineKeyboardMarkup, MediaKind, MediaLocation, MediaVenue, MessageCommon, MessageId,
+    ParseMode, ReplyMarkup,
 };
 use teloxide::utils::markdown;
 use teloxide::Bot;
 use uuid::Uuid;
 
 use resonanse_common::file_storage::get_event_image_path_by_uuid;
-use resonanse_common::models::{BaseEvent, EventSubject, Location, ResonanseEventKind};
+use resonanse_common::models::{
+    BaseEvent, City, Community, EventFormat, EventRecurrence, EventSubject, Location,
+    RecurrenceFrequency, ResonanseEventKind, MAX_RECURRENCE_OCCURRENCES,
+};
+use resonanse_common::repository::{
+    AccountsRepository, CommunitiesRepository, EventDraftsRepository, EventsRepository,
+};
 
-use crate::config::DEFAULT_DATETIME_FORMAT;
+use crate::config::{config, DEFAULT_DATETIME_FORMAT};
 use crate::data_structs::{
     prepare_event_msg_with_base_event, EventPostMessageRequest, FillingEvent,
 };
-use crate::errors::BotHandlerError;
+use crate::date_parser::parse_human_datetime;
+use crate::errors::{is_unexpected, user_message, BotHandlerError};
 use crate::handlers::utils::download_file_by_id;
 use crate::handlers::{HandlerResult, MyDialogue};
-use crate::high_logics::publish_event;
+use crate::high_logics::{
+    publish_event, save_event_draft, set_user_timezone, AnnouncementRepositories,
+};
 use crate::keyboards;
 use crate::keyboards::{get_inline_kb_choose_event_kind, get_make_event_keyboard};
 use crate::states::{BaseState, CreateEventState};
-use crate::utils::build_event_deep_link;
-
-const DATETIME_FORMAT_1: &str = "%d/%m/%Y %H:%M";
-const DATETIME_FORMAT_2: &str = "%d.%m.%Y %H.%M";
-const DATETIME_FORMAT_3: &str = "%d-%m-%Y %H:%M";
+use crate::utils::{
+    build_event_deep_link, build_event_invite_link, parse_event_price, parse_timezone,
+};
 
-const TITLE_LIMIT: RangeInclusive<usize> = 5..=100;
-const DESCRIPTION_LIMIT: RangeInclusive<usize> = 15..=764;
-const PLACE_TITLE_LIMIT: RangeInclusive<usize> = 0..=40;
+pub(crate) const TITLE_LIMIT: RangeInclusive<usize> = 5..=100;
+pub(crate) const DESCRIPTION_LIMIT: RangeInclusive<usize> = 15..=764;
+pub(crate) const PLACE_TITLE_LIMIT: RangeInclusive<usize> = 0..=40;
 const CONTACT_LIMIT: RangeInclusive<usize> = 3..=40;
+const MEETING_URL_LIMIT: RangeInclusive<usize> = 10..=1023;
+const CAPACITY_LIMIT: RangeInclusive<i32> = 1..=100_000;
+const RECURRENCE_COUNT_LIMIT: RangeInclusive<i32> = 2..=MAX_RECURRENCE_OCCURRENCES as i32;
 
 macro_rules! reject_user_answer {
     ($bot: ident, $chat_id: expr, $text:expr) => {
@@ -52,25 +66,33 @@ macro_rules! check_msg_size {
         } else {
             $bot.send_message(
                 $chat_id,
-                format!(
-                    "Количество символов ожидается от {} до {}. В вашем сообщении {}",
-                    $limit_range.start(),
-                    $limit_range.end(),
-                    $value_to_check.chars().count()
+                t!(
+                    "actions.invalid_text_length",
+                    min_chars = $limit_range.start(),
+                    max_chars = $limit_range.end(),
+                    chars_count = $value_to_check.chars().count()
                 ),
-            ).await?;
+            )
+            .await?;
 
             return Err(Box::new(BotHandlerError::UserInputRejected));
         }
     };
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_fill_event_field_callback(
     bot: Bot,
     dialogue: MyDialogue,
-    filling_event: FillingEvent,
+    mut filling_event: FillingEvent,
     last_edit_msg_id: MessageId,
+    pending_drafts: Vec<FillingEvent>,
     q: CallbackQuery,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    event_drafts_repository: Arc<dyn EventDraftsRepository>,
+    communities_repository: Arc<dyn CommunitiesRepository>,
+    announcement_repositories: AnnouncementRepositories,
 ) -> HandlerResult {
     bot.answer_callback_query(q.id.clone()).await?;
 
@@ -92,8 +114,8 @@ pub async fn handle_fill_event_field_callback(
         ),
 
         Some(keyboards::FILL_EVENT_DATETIME_FROM_BTN_ID) => {
-            let cur_dt = chrono::offset::Local::now()
-                .naive_local()
+            let cur_dt = chrono::Utc::now()
+                .with_timezone(&filling_event.timezone)
                 .format(&markdown::escape(DEFAULT_DATETIME_FORMAT))
                 .to_string();
 
@@ -101,27 +123,46 @@ pub async fn handle_fill_event_field_callback(
                 CreateEventState::DatetimeFrom,
                 t!(
                     "actions.create_event.fill_event.datetime_from",
-                    dt_example = cur_dt
+                    dt_example = cur_dt,
+                    timezone = filling_event.timezone.name(),
                 ),
-                None,
+                Some(get_datetime_picker_keyboard(
+                    &filling_event,
+                    filling_event.datetime_from,
+                )),
             )
         }
         Some(keyboards::FILL_EVENT_DATETIME_TO_BTN_ID) => {
-            let cur_dt = chrono::offset::Local::now()
-                .naive_local()
+            let cur_dt = chrono::Utc::now()
+                .with_timezone(&filling_event.timezone)
                 .format(&markdown::escape(DEFAULT_DATETIME_FORMAT))
                 .to_string();
 
             (
                 CreateEventState::DatetimeTo,
                 t!(
-                    "actions.create_event.fill_event.datetime_from",
-                    dt_example = cur_dt
+                    "actions.create_event.fill_event.datetime_to",
+                    dt_example = cur_dt,
+                    timezone = filling_event.timezone.name(),
                 ),
-                None,
+                Some(get_datetime_picker_keyboard(
+                    &filling_event,
+                    filling_event.datetime_to,
+                )),
             )
         }
 
+        Some(keyboards::FILL_EVENT_RECURRENCE_BTN_ID) => (
+            CreateEventState::Recurrence,
+            t!("actions.create_event.fill_event.recurrence"),
+            Some(keyboards::get_inline_kb_choose_recurrence()),
+        ),
+
+        Some(keyboards::FILL_EVENT_CITY_BTN_ID) => (
+            CreateEventState::City,
+            t!("actions.create_event.fill_event.city"),
+            Some(keyboards::get_inline_kb_choose_city()),
+        ),
         Some(keyboards::FILL_EVENT_LOCATION_GEO_BTN_ID) => (
             CreateEventState::Geo,
             t!("actions.create_event.fill_event.geo"),
@@ -132,6 +173,50 @@ pub async fn handle_fill_event_field_callback(
             t!("actions.create_event.fill_event.location_title"),
             None,
         ),
+        Some(keyboards::FILL_EVENT_MEETING_URL_BTN_ID) => (
+            CreateEventState::MeetingUrl,
+            t!("actions.create_event.fill_event.meeting_url"),
+            None,
+        ),
+        Some(keyboards::FILL_EVENT_FORMAT_BTN_ID) => (
+            CreateEventState::EventFormat,
+            t!("actions.create_event.fill_event.event_format"),
+            Some(keyboards::get_inline_kb_choose_event_format()),
+        ),
+        Some(keyboards::FILL_EVENT_PRICE_BTN_ID) => (
+            CreateEventState::Price,
+            t!("actions.create_event.fill_event.price"),
+            Some(keyboards::get_inline_kb_choose_price()),
+        ),
+        Some(keyboards::FILL_EVENT_CAPACITY_BTN_ID) => (
+            CreateEventState::Capacity,
+            t!("actions.create_event.fill_event.capacity"),
+            Some(keyboards::get_inline_kb_choose_capacity()),
+        ),
+        Some(keyboards::FILL_EVENT_COMMUNITY_BTN_ID) => {
+            let owned_communities = get_owned_communities(
+                accounts_repository.as_ref(),
+                communities_repository.as_ref(),
+                q.from.id,
+            )
+            .await?;
+            if owned_communities.is_empty() {
+                bot.send_message(
+                    q.from.id,
+                    t!("actions.create_event.fill_event.community_none_owned"),
+                )
+                .await?;
+                return Ok(());
+            }
+
+            (
+                CreateEventState::Community,
+                t!("actions.create_event.fill_event.community"),
+                Some(keyboards::get_inline_kb_choose_community(
+                    &owned_communities,
+                )),
+            )
+        }
         Some(keyboards::FILL_EVENT_PICTURE_BTN_ID) => (
             CreateEventState::Picture,
             t!("actions.create_event.fill_event.picture"),
@@ -147,8 +232,39 @@ pub async fn handle_fill_event_field_callback(
             t!("actions.create_event.fill_event.event_kind"),
             Some(get_inline_kb_choose_event_kind()),
         ),
+        Some(keyboards::FILL_EVENT_PRIVACY_BTN_ID) => {
+            filling_event.is_private = !filling_event.is_private;
+            let privacy_hint = match filling_event.is_private {
+                true => t!("actions.create_event.fill_event.privacy_private"),
+                false => t!("actions.create_event.fill_event.privacy_public"),
+            };
+            bot.send_message(q.from.id, privacy_hint).await?;
+
+            return update_filling_message(
+                &bot,
+                accounts_repository.as_ref(),
+                event_drafts_repository.as_ref(),
+                dialogue,
+                filling_event,
+                pending_drafts,
+                q.from.id.into(),
+                last_edit_msg_id,
+            )
+            .await;
+        }
         Some(keyboards::FILL_EVENT_FINALIZE_BTN_ID) => {
-            return handle_event_finalisation_callback(bot, dialogue, filling_event, q).await
+            return handle_event_finalisation_callback(
+                bot,
+                dialogue,
+                filling_event,
+                pending_drafts,
+                q,
+                events_repository,
+                accounts_repository,
+                event_drafts_repository,
+                announcement_repositories,
+            )
+            .await
         }
         // CreateEventState::Finalisation,
         // t!("actions.create_event.fill_event.finalize"),
@@ -166,13 +282,28 @@ pub async fn handle_fill_event_field_callback(
 
     dialogue
         .update(BaseState::CreateEvent {
-            state: fill_field_state,
+            state: fill_field_state.clone(),
             filling_event,
             last_edit_msg_id,
+            pending_drafts,
         })
         .await?;
 
-    if reply_markup.is_some() {
+    if matches!(
+        fill_field_state,
+        CreateEventState::DatetimeFrom
+            | CreateEventState::DatetimeTo
+            | CreateEventState::Recurrence
+            | CreateEventState::Price
+            | CreateEventState::Capacity
+            | CreateEventState::Community
+    ) {
+        // keyboard is sent with hint, so user can either type value or pick it
+        let mut message = bot.send_message(q.from.id, msg_text);
+        message.parse_mode = Some(MarkdownV2);
+        message.reply_markup = reply_markup.map(ReplyMarkup::InlineKeyboard);
+        message.await?;
+    } else if reply_markup.is_some() {
         if let Some(msg) = q.message {
             let mut edit_message = bot.edit_message_reply_markup(q.from.id, msg.id);
             edit_message.reply_markup = reply_markup;
@@ -188,21 +319,42 @@ pub async fn handle_fill_event_field_callback(
     Ok(())
 }
 
+/// Calendar opens on month of already filled datetime,
+/// end of event defaults to month of its start
+fn get_datetime_picker_keyboard(
+    filling_event: &FillingEvent,
+    filling_dt: Option<NaiveDateTime>,
+) -> InlineKeyboardMarkup {
+    let today = now_in_timezone(filling_event.timezone).date();
+    let month = filling_dt
+        .or(filling_event.datetime_from)
+        .map(|dt| dt.date())
+        .unwrap_or(today);
+
+    keyboards::get_inline_kb_calendar(month, today)
+}
+
 pub async fn handle_create_event_state_message(
     bot: Bot,
     dialogue: MyDialogue,
-    (create_event_state, mut filling_event, last_edit_msg_id): (
+    (create_event_state, mut filling_event, last_edit_msg_id, pending_drafts): (
         CreateEventState,
         FillingEvent,
         MessageId,
+        Vec<FillingEvent>,
     ),
     msg: Message,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    event_drafts_repository: Arc<dyn EventDraftsRepository>,
 ) -> HandlerResult {
     let chat_id = msg.chat.id;
     match create_event_state {
         CreateEventState::Idle => {
-            bot.send_message(msg.chat.id, "Вы не нажали, что именно хотите заполнить")
-                .await?;
+            bot.send_message(
+                msg.chat.id,
+                t!("actions.create_event.fill_event.field_not_chosen"),
+            )
+            .await?;
             return Ok(());
         }
         CreateEventState::EventTitle => handle_event_name(&bot, msg, &mut filling_event).await?,
@@ -210,16 +362,28 @@ pub async fn handle_create_event_state_message(
         CreateEventState::Description => {
             handle_event_description(&bot, msg, &mut filling_event).await?
         }
-        CreateEventState::DatetimeFrom => {
-            handle_event_datetime(&bot, msg, &mut filling_event.datetime_from).await?
+        CreateEventState::DatetimeFrom | CreateEventState::DatetimeTo => {
+            handle_event_datetime(
+                &bot,
+                accounts_repository.as_ref(),
+                msg,
+                &create_event_state,
+                &mut filling_event,
+            )
+            .await?
         }
-        CreateEventState::DatetimeTo => {
-            handle_event_datetime(&bot, msg, &mut filling_event.datetime_to).await?
+        CreateEventState::Recurrence => {
+            handle_event_recurrence_end(&bot, msg, &mut filling_event).await?
         }
         CreateEventState::Geo => handle_event_geo(&bot, msg, &mut filling_event).await?,
         CreateEventState::PlaceTitle => {
             handle_event_place_title(&bot, msg, &mut filling_event).await?
         }
+        CreateEventState::MeetingUrl => {
+            handle_event_meeting_url(&bot, msg, &mut filling_event).await?
+        }
+        CreateEventState::Price => handle_event_price(&bot, msg, &mut filling_event).await?,
+        CreateEventState::Capacity => handle_event_capacity(&bot, msg, &mut filling_event).await?,
         // CreateEventState::Subject => handle_event_subject,
         CreateEventState::Picture => handle_event_picture(&bot, msg, &mut filling_event).await?,
         CreateEventState::ContactInfo => {
@@ -231,28 +395,45 @@ pub async fn handle_create_event_state_message(
                 "Unhandled handle_create_event_state: {:?}",
                 create_event_state
             );
-            bot.send_message(msg.chat.id, "unknown create event handler")
+            bot.send_message(msg.chat.id, t!("actions.unrecognized_action"))
                 .await?;
             return Err(Box::new(BotHandlerError::UnknownHandler));
         }
     }
 
-    update_filling_message(&bot, dialogue, filling_event, chat_id, last_edit_msg_id).await?;
+    update_filling_message(
+        &bot,
+        accounts_repository.as_ref(),
+        event_drafts_repository.as_ref(),
+        dialogue,
+        filling_event,
+        pending_drafts,
+        chat_id,
+        last_edit_msg_id,
+    )
+    .await?;
 
     Ok(())
 
     // handler(bot, dialogue, msg, filling_event)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_create_event_state_callback(
     bot: Bot,
     dialogue: MyDialogue,
-    (create_event_state, mut filling_event, last_edit_msg_id): (
+    (create_event_state, mut filling_event, last_edit_msg_id, pending_drafts): (
         CreateEventState,
         FillingEvent,
         MessageId,
+        Vec<FillingEvent>,
     ),
     q: CallbackQuery,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    event_drafts_repository: Arc<dyn EventDraftsRepository>,
+    communities_repository: Arc<dyn CommunitiesRepository>,
+    announcement_repositories: AnnouncementRepositories,
 ) -> HandlerResult {
     let msg = match &q.message {
         None => {
@@ -263,19 +444,53 @@ pub async fn handle_create_event_state_callback(
     };
     let chat_id = msg.chat.id;
 
+    let is_calendar_callback = q
+        .data
+        .as_deref()
+        .map(|data| data.starts_with(keyboards::CALENDAR_BTN_PREFIX))
+        .unwrap_or(false);
+    if is_calendar_callback {
+        let is_datetime_chosen =
+            handle_event_calendar_callback(&bot, &q, &create_event_state, &mut filling_event)
+                .await?;
+        if is_datetime_chosen {
+            update_filling_message(
+                &bot,
+                accounts_repository.as_ref(),
+                event_drafts_repository.as_ref(),
+                dialogue,
+                filling_event,
+                pending_drafts,
+                chat_id,
+                last_edit_msg_id,
+            )
+            .await?;
+        }
+        return Ok(());
+    }
+
     match handle_fill_event_field_callback(
         bot.clone(),
         dialogue.clone(),
         filling_event.clone(),
         last_edit_msg_id,
+        pending_drafts.clone(),
         q.clone(),
+        events_repository.clone(),
+        accounts_repository.clone(),
+        event_drafts_repository.clone(),
+        communities_repository.clone(),
+        announcement_repositories,
     )
     .await
     {
-        Ok(v) => return Ok(v),
-        Err(err) => {
-            warn!("handle_fill_event_field_callback error: {:?}", err);
-        }
+        // callback is not a field button, it is an answer to current field
+        Err(err)
+            if matches!(
+                err.downcast_ref::<BotHandlerError>(),
+                Some(BotHandlerError::UnknownHandler)
+            ) => {}
+        result => return result,
     }
 
     match create_event_state {
@@ -293,6 +508,38 @@ pub async fn handle_create_event_state_callback(
         //     handle_event_finalisation_callback(bot, dialogue, filling_event, q).await
         // }
         CreateEventState::EventKind => handle_event_kind(&bot, &mut filling_event, q).await?,
+        CreateEventState::EventFormat => handle_event_format(&bot, &mut filling_event, q).await?,
+        CreateEventState::City => handle_event_city(&bot, &mut filling_event, q).await?,
+        CreateEventState::Price => handle_event_price_callback(&bot, &mut filling_event, q).await?,
+        CreateEventState::Capacity => {
+            handle_event_capacity_callback(&bot, &mut filling_event, q).await?
+        }
+        CreateEventState::Community => {
+            handle_event_community_callback(
+                &bot,
+                accounts_repository.as_ref(),
+                communities_repository.as_ref(),
+                &mut filling_event,
+                q,
+            )
+            .await?
+        }
+        CreateEventState::Recurrence => {
+            let is_recurrence_filled =
+                handle_event_recurrence_callback(&bot, &mut filling_event, q).await?;
+            if !is_recurrence_filled {
+                // wait for recurrence end in the same state
+                dialogue
+                    .update(BaseState::CreateEvent {
+                        state: CreateEventState::Recurrence,
+                        filling_event,
+                        last_edit_msg_id,
+                        pending_drafts,
+                    })
+                    .await?;
+                return Ok(());
+            }
+        }
         _ => {
             // return handle_fill_event_field_callback(
             //     bot, dialogue, filling_event,
@@ -303,13 +550,23 @@ pub async fn handle_create_event_state_callback(
                 "Unhandled handle_create_event_state_callback: {:?}",
                 create_event_state
             );
-            bot.send_message(msg.chat.id, "unknown create event handler")
+            bot.send_message(msg.chat.id, t!("actions.unrecognized_action"))
                 .await?;
             return Err(Box::new(BotHandlerError::UnknownHandler));
         }
     }
 
-    update_filling_message(&bot, dialogue, filling_event, chat_id, last_edit_msg_id).await?;
+    update_filling_message(
+        &bot,
+        accounts_repository.as_ref(),
+        event_drafts_repository.as_ref(),
+        dialogue,
+        filling_event,
+        pending_drafts,
+        chat_id,
+        last_edit_msg_id,
+    )
+    .await?;
 
     // dialogue
     //     .update(BaseState::CreateEvent {
@@ -321,13 +578,56 @@ pub async fn handle_create_event_state_callback(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn update_filling_message(
     bot: &Bot,
+    accounts_repository: &dyn AccountsRepository,
+    event_drafts_repository: &dyn EventDraftsRepository,
     dialogue: MyDialogue,
     filling_event: FillingEvent,
+    pending_drafts: Vec<FillingEvent>,
     chat_id: ChatId,
     last_edit_msg_id: MessageId,
 ) -> HandlerResult {
+    let sent_event_message = send_filling_message(bot, &filling_event, chat_id).await?;
+    // wizard is in private chat, so chat id is id of user
+    save_event_draft(
+        accounts_repository,
+        event_drafts_repository,
+        chat_id.0,
+        &filling_event,
+    )
+    .await;
+
+    match bot.delete_message(chat_id, last_edit_msg_id).await {
+        Ok(_) => {}
+        Err(err) => {
+            debug!(
+                "error on deleting message {} from chat {}: {:?}",
+                last_edit_msg_id, chat_id, err
+            );
+        }
+    };
+
+    dialogue
+        .update(BaseState::CreateEvent {
+            state: CreateEventState::Idle,
+            filling_event,
+            last_edit_msg_id: sent_event_message.id,
+            pending_drafts,
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Sends event preview if event is ready, otherwise hint with missed data.
+/// Both messages have make event keyboard
+pub async fn send_filling_message(
+    bot: &Bot,
+    filling_event: &FillingEvent,
+    chat_id: ChatId,
+) -> Result<Message, Box<dyn std::error::Error + Send + Sync>> {
     let sent_event_message: Message = match BaseEvent::try_from(filling_event.clone()) {
         Ok(base_event) if filling_event.is_ready() => {
             let event_message = prepare_event_msg_with_base_event(
@@ -337,6 +637,7 @@ pub async fn update_filling_message(
                 Some(ReplyMarkup::InlineKeyboard(
                     keyboards::get_make_event_keyboard(),
                 )),
+                filling_event.timezone,
             );
 
             match event_message {
@@ -353,25 +654,7 @@ pub async fn update_filling_message(
         }
     };
 
-    match bot.delete_message(chat_id, last_edit_msg_id).await {
-        Ok(_) => {}
-        Err(err) => {
-            debug!(
-                "error on deleting message {} from chat {}: {:?}",
-                last_edit_msg_id, chat_id, err
-            );
-        }
-    };
-
-    dialogue
-        .update(BaseState::CreateEvent {
-            state: CreateEventState::Idle,
-            filling_event,
-            last_edit_msg_id: sent_event_message.id,
-        })
-        .await?;
-
-    Ok(())
+    Ok(sent_event_message)
 }
 
 pub async fn handle_event_name(
@@ -382,7 +665,11 @@ pub async fn handle_event_name(
 ) -> HandlerResult {
     let event_name = match msg.text() {
         None => {
-            reject_user_answer!(bot, msg.chat.id, "No name provided");
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!("actions.create_event.fill_event.text_expected")
+            );
         }
         Some(v) => check_msg_size!(bot, msg.chat.id, TITLE_LIMIT, v).replace('\n', " "),
     };
@@ -406,7 +693,11 @@ pub async fn handle_event_description(
 ) -> HandlerResult {
     let event_description = match msg.text() {
         None => {
-            reject_user_answer!(bot, msg.chat.id, "No description provided");
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!("actions.create_event.fill_event.text_expected")
+            );
         }
         Some(v) => check_msg_size!(bot, msg.chat.id, DESCRIPTION_LIMIT, v),
     };
@@ -438,38 +729,297 @@ pub async fn handle_event_description(
 
 pub async fn handle_event_datetime(
     bot: &Bot,
+    accounts_repository: &dyn AccountsRepository,
     // dialogue: MyDialogue,
     msg: Message,
-    // mut filling_event: FillingEvent,
-    filling_dt: &mut Option<NaiveDateTime>,
+    create_event_state: &CreateEventState,
+    filling_event: &mut FillingEvent,
 ) -> HandlerResult {
     let event_dt = match msg.text() {
         None => {
-            reject_user_answer!(bot, msg.chat.id, "No datetime provided");
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!("actions.create_event.fill_event.text_expected")
+            );
             // bot.send_message(
             //     msg.chat.id,
-            //     "No datetime provided",
+            //     t!("actions.create_event.fill_event.text_expected"),
             // ).await?;
             // return Ok(());
         }
         Some(v) => v,
     };
 
-    let event_dt = match NaiveDateTime::parse_from_str(event_dt, DEFAULT_DATETIME_FORMAT)
-        .or(NaiveDateTime::parse_from_str(event_dt, DATETIME_FORMAT_1))
-        .or(NaiveDateTime::parse_from_str(event_dt, DATETIME_FORMAT_2))
-        .or(NaiveDateTime::parse_from_str(event_dt, DATETIME_FORMAT_3))
+    // timezone may be specified after datetime, e.g. "завтра в 19:00 Europe/Samara"
+    let (event_dt, event_tz) =
+        match parse_human_datetime(event_dt, now_in_timezone(filling_event.timezone)) {
+            Some(v) => (Some(v), None),
+            None => match event_dt
+                .trim()
+                .rsplit_once(' ')
+                .and_then(|(dt_part, tz_part)| Some((dt_part, parse_timezone(tz_part)?)))
+            {
+                Some((dt_part, tz)) => {
+                    (parse_human_datetime(dt_part, now_in_timezone(tz)), Some(tz))
+                }
+                None => (None, None),
+            },
+        };
+
+    let event_dt = match event_dt {
+        Some(v) => v,
+        None => {
+            warn!("handle_event_datetime: cannot parse date {:?}", msg.text());
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!("actions.create_event.fill_event.datetime_invalid")
+            );
+        }
+    };
+
+    set_event_datetime(
+        bot,
+        msg.chat.id,
+        create_event_state,
+        filling_event,
+        event_dt,
+    )
+    .await?;
+
+    if let Some(event_tz) = event_tz {
+        filling_event.timezone = event_tz;
+        if let Some(user) = msg.from() {
+            if let Err(err) =
+                set_user_timezone(accounts_repository, user.id.0 as i64, event_tz).await
+            {
+                warn!("cannot save user timezone: {:?}", err);
+            }
+        }
+    }
+
+    // phrases like "в субботу" may be understood differently, so show what was recognized
+    bot.send_message(
+        msg.chat.id,
+        t!(
+            "actions.create_event.fill_event.datetime_recognized",
+            datetime = event_dt.format(DEFAULT_DATETIME_FORMAT),
+            weekday = weekday_name(event_dt.weekday()),
+            timezone = filling_event.timezone.name(),
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Sets start or end of event depending on `create_event_state`,
+/// rejects value if event would end before it starts
+async fn set_event_datetime(
+    bot: &Bot,
+    chat_id: ChatId,
+    create_event_state: &CreateEventState,
+    filling_event: &mut FillingEvent,
+    event_dt: NaiveDateTime,
+) -> HandlerResult {
+    let (datetime_from, datetime_to) = match create_event_state {
+        CreateEventState::DatetimeFrom => (Some(event_dt), filling_event.datetime_to),
+        CreateEventState::DatetimeTo => (filling_event.datetime_from, Some(event_dt)),
+        _ => return Err(Box::new(BotHandlerError::UnknownHandler)),
+    };
+
+    if let (Some(datetime_from), Some(datetime_to)) = (datetime_from, datetime_to) {
+        if datetime_to <= datetime_from {
+            reject_user_answer!(
+                bot,
+                chat_id,
+                t!("actions.create_event.fill_event.datetime_range_invalid")
+            );
+        }
+    }
+
+    filling_event.datetime_from = datetime_from;
+    filling_event.datetime_to = datetime_to;
+
+    Ok(())
+}
+
+/// Handles navigation in calendar picker.
+/// Returns true when datetime is chosen and saved to `filling_event`
+async fn handle_event_calendar_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    create_event_state: &CreateEventState,
+    filling_event: &mut FillingEvent,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    // calendar of already filled field may still be pressed after dialogue has moved on
+    if !matches!(
+        create_event_state,
+        CreateEventState::DatetimeFrom | CreateEventState::DatetimeTo
+    ) {
+        bot.answer_callback_query(q.id.clone())
+            .text(t!("actions.create_event.fill_event.calendar_outdated"))
+            .await?;
+        return Ok(false);
+    }
+
+    bot.answer_callback_query(q.id.clone()).await?;
+    let (msg, data) = match (&q.message, q.data.as_deref()) {
+        (Some(msg), Some(data)) => (msg, data),
+        _ => return Ok(false),
+    };
+
+    let reply_markup = if let Some(month) = data.strip_prefix(keyboards::CALENDAR_MONTH_BTN_PREFIX)
     {
-        Ok(v) => v,
-        Err(err) => {
-            warn!("handle_event_datetime: parse date error {}", err);
-            bot.send_message(msg.chat.id, "Дата и время не распознаны")
-                .await?;
-            return Err(Box::new(err));
+        let month = NaiveDate::parse_from_str(month, keyboards::CALENDAR_DATE_FORMAT)?;
+        let today = now_in_timezone(filling_event.timezone).date();
+        keyboards::get_inline_kb_calendar(month, today)
+    } else if let Some(day) = data.strip_prefix(keyboards::CALENDAR_DAY_BTN_PREFIX) {
+        let day = NaiveDate::parse_from_str(day, keyboards::CALENDAR_DATE_FORMAT)?;
+        keyboards::get_inline_kb_time_slots(day)
+    } else if let Some(dt) = data.strip_prefix(keyboards::CALENDAR_TIME_BTN_PREFIX) {
+        let event_dt = NaiveDateTime::parse_from_str(dt, keyboards::CALENDAR_DATETIME_FORMAT)?;
+        set_event_datetime(
+            bot,
+            msg.chat.id,
+            create_event_state,
+            filling_event,
+            event_dt,
+        )
+        .await?;
+        bot.delete_message(msg.chat.id, msg.id).await?;
+        return Ok(true);
+    } else {
+        // header buttons and empty cells
+        return Ok(false);
+    };
+
+    let mut edit_message = bot.edit_message_reply_markup(msg.chat.id, msg.id);
+    edit_message.reply_markup = Some(reply_markup);
+    edit_message.await?;
+
+    Ok(false)
+}
+
+fn now_in_timezone(tz: Tz) -> NaiveDateTime {
+    chrono::Utc::now().with_timezone(&tz).naive_local()
+}
+
+fn weekday_name(weekday: Weekday) -> String {
+    match weekday {
+        Weekday::Mon => t!("weekday.mon"),
+        Weekday::Tue => t!("weekday.tue"),
+        Weekday::Wed => t!("weekday.wed"),
+        Weekday::Thu => t!("weekday.thu"),
+        Weekday::Fri => t!("weekday.fri"),
+        Weekday::Sat => t!("weekday.sat"),
+        Weekday::Sun => t!("weekday.sun"),
+    }
+}
+
+/// Handles recurrence frequency and "no end" buttons.
+/// Returns false when recurrence end is still expected from user
+pub async fn handle_event_recurrence_callback(
+    bot: &Bot,
+    filling_event: &mut FillingEvent,
+    q: CallbackQuery,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    bot.answer_callback_query(q.id).await?;
+    let msg = match q.message {
+        None => return Ok(true),
+        Some(v) => v,
+    };
+
+    match q.data.as_deref() {
+        None => {
+            reject_user_answer!(
+                bot,
+                q.from.id,
+                t!("actions.create_event.fill_event.option_not_chosen")
+            );
         }
+        Some(keyboards::RECURRENCE_NONE_BTN) => filling_event.recurrence = None,
+        Some(keyboards::RECURRENCE_NO_END_BTN) => {}
+        Some(v) => {
+            let frequency = RecurrenceFrequency::from_str(v)?;
+            filling_event.recurrence = Some(EventRecurrence::new(frequency));
+
+            let mut edit_message = bot.edit_message_text(
+                msg.chat.id,
+                msg.id,
+                t!("actions.create_event.fill_event.recurrence_end"),
+            );
+            edit_message.parse_mode = Some(MarkdownV2);
+            edit_message.reply_markup = Some(keyboards::get_inline_kb_recurrence_end());
+            edit_message.await?;
+            return Ok(false);
+        }
+    }
+
+    bot.delete_message(msg.chat.id, msg.id).await?;
+    Ok(true)
+}
+
+/// Recurrence ends either at date of last occurrence or after number of occurrences
+pub async fn handle_event_recurrence_end(
+    bot: &Bot,
+    msg: Message,
+    filling_event: &mut FillingEvent,
+) -> HandlerResult {
+    let now = now_in_timezone(filling_event.timezone);
+    let datetime_from = filling_event.datetime_from;
+    let recurrence = match filling_event.recurrence.as_mut() {
+        None => {
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!("actions.create_event.fill_event.recurrence_not_chosen")
+            );
+        }
+        Some(v) => v,
     };
+    let recurrence_end = msg.text().unwrap_or_default().trim();
+
+    if let Ok(occurrences_count) = recurrence_end.parse::<i32>() {
+        if !RECURRENCE_COUNT_LIMIT.contains(&occurrences_count) {
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!(
+                    "actions.create_event.fill_event.recurrence_end_invalid",
+                    max_count = MAX_RECURRENCE_OCCURRENCES
+                )
+            );
+        }
+        recurrence.occurrences_count = Some(occurrences_count);
+        recurrence.until_date = None;
+        return Ok(());
+    }
 
-    *filling_dt = Some(event_dt);
+    let until_date = parse_human_datetime(recurrence_end, now)
+        .map(|until_dt| until_dt.date())
+        .filter(|until_date| {
+            datetime_from
+                .map(|datetime_from| *until_date >= datetime_from.date())
+                .unwrap_or(true)
+        });
+    match until_date {
+        None => {
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!(
+                    "actions.create_event.fill_event.recurrence_end_invalid",
+                    max_count = MAX_RECURRENCE_OCCURRENCES
+                )
+            );
+        }
+        Some(until_date) => {
+            recurrence.until_date = Some(until_date);
+            recurrence.occurrences_count = None;
+        }
+    }
 
     Ok(())
 }
@@ -501,15 +1051,27 @@ pub async fn handle_event_geo(
             match Location::parse_from_yandex_map_link(&plain_text) {
                 Some(loc) => loc,
                 None => {
-                    reject_user_answer!(bot, msg.chat.id, "Место не распознано");
+                    reject_user_answer!(
+                        bot,
+                        msg.chat.id,
+                        t!("actions.create_event.fill_event.geo_invalid")
+                    );
                 }
             }
         }
         _ => {
-            reject_user_answer!(bot, msg.chat.id, "No location provided");
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!("actions.create_event.fill_event.geo_invalid")
+            );
         }
     };
 
+    // venue outside of known cities keeps city chosen by user
+    if let Some(city) = City::from_location(&location) {
+        filling_event.city = Some(city);
+    }
     filling_event.geo_position = Some(location);
 
     Ok(())
@@ -523,7 +1085,11 @@ pub async fn handle_event_place_title(
     let place_title = match msg.text() {
         Some(place_title) => check_msg_size!(bot, msg.chat.id, PLACE_TITLE_LIMIT, place_title),
         _ => {
-            reject_user_answer!(bot, msg.chat.id, "No location provided");
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!("actions.create_event.fill_event.text_expected")
+            );
         }
     };
 
@@ -555,7 +1121,7 @@ pub async fn handle_event_place_title(
 //             }
 //         }
 //         _ => {
-//             reject_user_answer!(bot, msg.chat.id, "No location provided");
+//             reject_user_answer!(bot, msg.chat.id, t!("actions.create_event.fill_event.text_expected"));
 //         }
 //     };
 //
@@ -584,7 +1150,11 @@ pub async fn handle_event_subject(
     bot.answer_callback_query(q.id).await?;
     let event_subject = match q.data.as_ref() {
         None => {
-            reject_user_answer!(bot, q.from.id, "No subject provided");
+            reject_user_answer!(
+                bot,
+                q.from.id,
+                t!("actions.create_event.fill_event.option_not_chosen")
+            );
         }
         Some(v) => EventSubject::from_str(v.as_ref())?,
     };
@@ -620,7 +1190,11 @@ pub async fn handle_event_kind(
     bot.answer_callback_query(q.id).await?;
     let event_kind = match q.data.as_ref() {
         None => {
-            reject_user_answer!(bot, q.from.id, "No subject provided");
+            reject_user_answer!(
+                bot,
+                q.from.id,
+                t!("actions.create_event.fill_event.option_not_chosen")
+            );
         }
         Some(v) => ResonanseEventKind::from_str(v.as_ref())?,
     };
@@ -634,6 +1208,257 @@ pub async fn handle_event_kind(
     Ok(())
 }
 
+pub async fn handle_event_format(
+    bot: &Bot,
+    filling_event: &mut FillingEvent,
+    q: CallbackQuery,
+) -> HandlerResult {
+    bot.answer_callback_query(q.id).await?;
+    let event_format = match q.data.as_ref() {
+        None => {
+            reject_user_answer!(
+                bot,
+                q.from.id,
+                t!("actions.create_event.fill_event.option_not_chosen")
+            );
+        }
+        Some(v) => EventFormat::from_str(v.as_ref())?,
+    };
+
+    filling_event.event_format = event_format;
+
+    if let Some(msg) = q.message {
+        bot.delete_message(q.from.id, msg.id).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn handle_event_city(
+    bot: &Bot,
+    filling_event: &mut FillingEvent,
+    q: CallbackQuery,
+) -> HandlerResult {
+    bot.answer_callback_query(q.id).await?;
+    let city = match q.data.as_ref() {
+        None => {
+            reject_user_answer!(
+                bot,
+                q.from.id,
+                t!("actions.create_event.fill_event.option_not_chosen")
+            );
+        }
+        Some(v) => City::from_str(v.as_ref())?,
+    };
+
+    filling_event.city = Some(city);
+
+    if let Some(msg) = q.message {
+        bot.delete_message(q.from.id, msg.id).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn handle_event_price(
+    bot: &Bot,
+    msg: Message,
+    filling_event: &mut FillingEvent,
+) -> HandlerResult {
+    let price = match msg.text().and_then(parse_event_price) {
+        None => {
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!("actions.create_event.fill_event.price_invalid")
+            );
+        }
+        Some(v) => v,
+    };
+
+    filling_event.price = price;
+
+    Ok(())
+}
+
+/// "Free" button under price hint
+pub async fn handle_event_price_callback(
+    bot: &Bot,
+    filling_event: &mut FillingEvent,
+    q: CallbackQuery,
+) -> HandlerResult {
+    bot.answer_callback_query(q.id).await?;
+    if q.data.as_deref() != Some(keyboards::PRICE_FREE_BTN) {
+        reject_user_answer!(
+            bot,
+            q.from.id,
+            t!("actions.create_event.fill_event.option_not_chosen")
+        );
+    }
+
+    filling_event.price = None;
+
+    if let Some(msg) = q.message {
+        bot.delete_message(q.from.id, msg.id).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn handle_event_capacity(
+    bot: &Bot,
+    msg: Message,
+    filling_event: &mut FillingEvent,
+) -> HandlerResult {
+    let capacity = match msg.text().and_then(|v| v.trim().parse::<i32>().ok()) {
+        Some(v) if CAPACITY_LIMIT.contains(&v) => v,
+        _ => {
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!(
+                    "actions.create_event.fill_event.capacity_invalid",
+                    max_capacity = CAPACITY_LIMIT.end()
+                )
+            );
+        }
+    };
+
+    filling_event.capacity = Some(capacity);
+
+    Ok(())
+}
+
+/// "Unlimited" button under capacity hint
+pub async fn handle_event_capacity_callback(
+    bot: &Bot,
+    filling_event: &mut FillingEvent,
+    q: CallbackQuery,
+) -> HandlerResult {
+    bot.answer_callback_query(q.id).await?;
+    if q.data.as_deref() != Some(keyboards::CAPACITY_UNLIMITED_BTN) {
+        reject_user_answer!(
+            bot,
+            q.from.id,
+            t!("actions.create_event.fill_event.option_not_chosen")
+        );
+    }
+
+    filling_event.capacity = None;
+
+    if let Some(msg) = q.message {
+        bot.delete_message(q.from.id, msg.id).await?;
+    }
+
+    Ok(())
+}
+
+async fn get_owned_communities(
+    accounts_repository: &dyn AccountsRepository,
+    communities_repository: &dyn CommunitiesRepository,
+    tg_user_id: UserId,
+) -> Result<Vec<Community>, Box<dyn Error + Send + Sync>> {
+    let account_id = match accounts_repository
+        .get_account_id_by_tg_user_id(tg_user_id.0 as i64)
+        .await
+    {
+        Ok(v) => v,
+        Err(err) => {
+            debug!("cannot get account of tg user {}: {:?}", tg_user_id, err);
+            return Ok(Vec::new());
+        }
+    };
+
+    Ok(communities_repository
+        .get_owned_communities(account_id)
+        .await?)
+}
+
+/// Event can be organized only by community owned by its creator
+pub async fn handle_event_community_callback(
+    bot: &Bot,
+    accounts_repository: &dyn AccountsRepository,
+    communities_repository: &dyn CommunitiesRepository,
+    filling_event: &mut FillingEvent,
+    q: CallbackQuery,
+) -> HandlerResult {
+    bot.answer_callback_query(q.id).await?;
+    let community_id = match q.data.as_deref() {
+        Some(keyboards::EVENT_COMMUNITY_NONE_BTN) => None,
+        Some(data) => match data
+            .strip_prefix(keyboards::EVENT_COMMUNITY_BTN_PREFIX)
+            .and_then(|community_id| community_id.parse::<i64>().ok())
+        {
+            None => {
+                reject_user_answer!(
+                    bot,
+                    q.from.id,
+                    t!("actions.create_event.fill_event.option_not_chosen")
+                );
+            }
+            Some(v) => Some(v),
+        },
+        None => {
+            reject_user_answer!(
+                bot,
+                q.from.id,
+                t!("actions.create_event.fill_event.option_not_chosen")
+            );
+        }
+    };
+
+    if let Some(community_id) = community_id {
+        let is_owned =
+            get_owned_communities(accounts_repository, communities_repository, q.from.id)
+                .await?
+                .iter()
+                .any(|community| community.id == community_id);
+        if !is_owned {
+            reject_user_answer!(bot, q.from.id, t!("actions.communities.owner_only"));
+        }
+    }
+
+    filling_event.community_id = community_id;
+
+    if let Some(msg) = q.message {
+        bot.delete_message(q.from.id, msg.id).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn handle_event_meeting_url(
+    bot: &Bot,
+    msg: Message,
+    filling_event: &mut FillingEvent,
+) -> HandlerResult {
+    let meeting_url = match msg.text() {
+        None => {
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!("actions.create_event.fill_event.text_expected")
+            );
+        }
+        Some(v) => check_msg_size!(bot, msg.chat.id, MEETING_URL_LIMIT, v).trim(),
+    };
+
+    let is_web_url = url::Url::parse(meeting_url)
+        .map(|url| matches!(url.scheme(), "http" | "https") && url.host().is_some())
+        .unwrap_or(false);
+    if !is_web_url {
+        reject_user_answer!(
+            bot,
+            msg.chat.id,
+            t!("actions.create_event.fill_event.meeting_url_invalid")
+        );
+    }
+
+    filling_event.meeting_url = Some(meeting_url.to_string());
+
+    Ok(())
+}
+
 pub async fn handle_event_picture(
     bot: &Bot,
     // dialogue: MyDialogue,
@@ -642,13 +1467,18 @@ pub async fn handle_event_picture(
 ) -> HandlerResult {
     let event_photo_file_id = match msg.photo().and_then(|p| p.last()) {
         None => {
-            reject_user_answer!(bot, msg.chat.id, "No photo provided");
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!("actions.create_event.fill_event.picture_expected")
+            );
         }
         Some(v) => v.file.id.clone(),
     };
 
     let local_file_uuid = Uuid::new_v4();
-    let local_file_path = get_event_image_path_by_uuid(local_file_uuid);
+    let local_file_path =
+        get_event_image_path_by_uuid(&config().common.storage_dir, local_file_uuid);
 
     download_file_by_id(bot, &event_photo_file_id, &local_file_path).await?;
 
@@ -678,7 +1508,11 @@ pub async fn handle_event_contact(
 ) -> HandlerResult {
     let contact_info = match msg.text() {
         None => {
-            reject_user_answer!(bot, msg.chat.id, "No subject provided");
+            reject_user_answer!(
+                bot,
+                msg.chat.id,
+                t!("actions.create_event.fill_event.text_expected")
+            );
         }
         Some(v) => check_msg_size!(bot, msg.chat.id, CONTACT_LIMIT, v).to_string(),
     };
@@ -713,11 +1547,17 @@ pub async fn handle_event_contact(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_event_finalisation_callback(
     bot: Bot,
     dialogue: MyDialogue,
     filling_event: FillingEvent,
+    mut pending_drafts: Vec<FillingEvent>,
     q: CallbackQuery,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    event_drafts_repository: Arc<dyn EventDraftsRepository>,
+    announcement_repositories: AnnouncementRepositories,
 ) -> HandlerResult {
     bot.answer_callback_query(q.id.clone()).await?;
     let msg = match q.message {
@@ -730,29 +1570,52 @@ pub async fn handle_event_finalisation_callback(
 
     let tg_user = q.from;
 
-    let created_event = match publish_event(filling_event.clone(), &tg_user).await {
+    let created_event = match publish_event(
+        &bot,
+        events_repository.as_ref(),
+        &accounts_repository,
+        &announcement_repositories,
+        filling_event.clone(),
+        &tg_user,
+    )
+    .await
+    {
         Ok(v) => v,
+        // unexpected errors are reported by dispatcher and notified to managers
+        Err(err) if is_unexpected(err.as_ref()) => return Err(err),
         Err(err) => {
-            bot.send_message(msg.chat.id, format!("Событие не создано. Ошибка: {}", err))
-                .await?;
+            bot.send_message(
+                msg.chat.id,
+                t!(
+                    "actions.create_event.fill_event.publish_failed",
+                    error = user_message(err.as_ref()).unwrap_or_else(|| t!("errors.telegram"))
+                ),
+            )
+            .await?;
 
             return Ok(());
         }
     };
     bot.delete_message(msg.chat.id, msg.id).await?;
-    dialogue.update(BaseState::Idle).await?;
 
-    let tg_event_deep_link = build_event_deep_link(created_event.id);
-    if filling_event.is_private {
+    if let Err(err) = event_drafts_repository
+        .delete_draft(filling_event.draft_id)
+        .await
+    {
+        warn!("cannot delete draft of published event: {:?}", err);
+    }
+
+    if let Some(invite_token) = created_event.invite_token.as_deref() {
         bot.send_message(
             msg.chat.id,
-            format!(
-                "Событие создано. Оно будет доступно только по вашей ссылке: {}",
-                tg_event_deep_link
+            t!(
+                "actions.create_event.fill_event.finalize_private",
+                event_link = build_event_invite_link(invite_token)
             ),
         )
         .await?;
     } else {
+        let tg_event_deep_link = build_event_deep_link(created_event.id);
         bot.send_message(
             msg.chat.id,
             t!(
@@ -763,5 +1626,115 @@ pub async fn handle_event_finalisation_callback(
         .await?;
     }
 
+    if pending_drafts.is_empty() {
+        dialogue.update(BaseState::Idle).await?;
+        return Ok(());
+    }
+
+    // continue with next draft in queue
+    let next_draft = pending_drafts.remove(0);
+    bot.send_message(
+        msg.chat.id,
+        t!(
+            "actions.import_events.next_draft",
+            drafts_left = pending_drafts.len() + 1
+        ),
+    )
+    .await?;
+    let sent_msg = send_filling_message(&bot, &next_draft, msg.chat.id).await?;
+    dialogue
+        .update(BaseState::CreateEvent {
+            state: CreateEventState::Idle,
+            filling_event: next_draft,
+            last_edit_msg_id: sent_msg.id,
+            pending_drafts,
+        })
+        .await?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::time::Duration;
+
+    use axum::{Json, Router};
+    use resonanse_common::repository::in_memory::{
+        InMemoryAccountFollowsRepository, InMemoryAccountsRepository,
+        InMemoryCommunitiesRepository, InMemoryEventDraftsRepository, InMemoryEventsRepository,
+        InMemoryGroupFeedsRepository,
+    };
+    use resonanse_common::repository::PgAccountsRepository;
+    use sqlx::postgres::PgPoolOptions;
+    use teloxide::dispatching::dialogue::InMemStorage;
+
+    use super::*;
+
+    /// Telegram API which accepts every request
+    async fn serve_telegram_api() -> reqwest::Url {
+        let server = axum::Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(
+            Router::new()
+                .fallback(|| async { Json(serde_json::json!({"ok": true, "result": true})) })
+                .into_make_service(),
+        );
+        let url = format!("http://{}", server.local_addr()).parse().unwrap();
+        tokio::spawn(server);
+        url
+    }
+
+    #[tokio::test]
+    async fn returns_storage_errors_of_finalisation_to_dispatcher() {
+        let bot = Bot::new("token").set_api_url(serve_telegram_api().await);
+        let dialogue = MyDialogue::new(InMemStorage::new(), ChatId(42));
+        let q: CallbackQuery = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "from": {"id": 42, "is_bot": false, "first_name": "Ivan"},
+            "chat_instance": "1",
+            "data": keyboards::FILL_EVENT_FINALIZE_BTN_ID,
+            "message": {"message_id": 1, "date": 0,
+                "chat": {"id": 42, "type": "private"}, "text": "event"},
+        }))
+        .unwrap();
+        // database is unreachable, so account of creator cannot be saved
+        let failing_accounts_repository = PgAccountsRepository::new(
+            PgPoolOptions::new()
+                .acquire_timeout(Duration::from_millis(100))
+                .connect_lazy("postgres://postgres@127.0.0.1:1/resonanse")
+                .unwrap(),
+        );
+        let in_memory_accounts_repository = Arc::new(InMemoryAccountsRepository::new());
+
+        let err = handle_create_event_state_callback(
+            bot,
+            dialogue,
+            (
+                CreateEventState::Idle,
+                FillingEvent::new(),
+                MessageId(1),
+                vec![],
+            ),
+            q,
+            Arc::new(InMemoryEventsRepository::new()),
+            Arc::new(failing_accounts_repository),
+            Arc::new(InMemoryEventDraftsRepository::new(chrono::Duration::days(
+                1,
+            ))),
+            Arc::new(InMemoryCommunitiesRepository::new(
+                in_memory_accounts_repository.clone(),
+            )),
+            AnnouncementRepositories {
+                account_follows_repository: Arc::new(InMemoryAccountFollowsRepository::new(
+                    in_memory_accounts_repository,
+                )),
+                group_feeds_repository: Arc::new(InMemoryGroupFeedsRepository::new()),
+            },
+        )
+        .await
+        .unwrap_err();
+
+        // dispatcher reports such errors to user and notifies managers of them
+        assert!(is_unexpected(err.as_ref()));
+        assert!(user_message(err.as_ref()).is_some());
+    }
+}
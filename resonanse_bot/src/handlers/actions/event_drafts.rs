@@ -0,0 +1,121 @@
+use std::sync::Arc;
+
+use log::debug;
+use teloxide::prelude::*;
+use teloxide::types::ReplyMarkup;
+use teloxide::Bot;
+use uuid::Uuid;
+
+use resonanse_common::repository::{AccountsRepository, EventDraftsRepository};
+
+use crate::data_structs::FillingEvent;
+use crate::handlers::actions::create_event::update_filling_message;
+use crate::handlers::{log_request, HandlerResult, MyDialogue};
+use crate::keyboards::{get_inline_kb_event_drafts, RESUME_DRAFT_BTN_PREFIX};
+
+pub async fn drafts_command(
+    bot: Bot,
+    msg: Message,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    event_drafts_repository: Arc<dyn EventDraftsRepository>,
+) -> HandlerResult {
+    log_request("got drafts command", &msg);
+
+    let account_id = accounts_repository
+        .get_account_id_by_tg_user_id(msg.chat.id.0)
+        .await;
+    let drafts = match account_id {
+        Ok(account_id) => {
+            event_drafts_repository
+                .get_account_drafts::<FillingEvent>(account_id)
+                .await?
+        }
+        Err(err) => {
+            debug!("cannot get account of tg user {}: {:?}", msg.chat.id, err);
+            Vec::new()
+        }
+    };
+
+    if drafts.is_empty() {
+        bot.send_message(msg.chat.id, t!("actions.event_drafts.empty"))
+            .await?;
+        return Ok(());
+    }
+
+    let mut message = bot.send_message(msg.chat.id, t!("actions.event_drafts.choose"));
+    message.reply_markup = Some(ReplyMarkup::InlineKeyboard(get_inline_kb_event_drafts(
+        &drafts,
+    )));
+    message.await?;
+
+    Ok(())
+}
+
+pub fn resume_draft_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(RESUME_DRAFT_BTN_PREFIX)
+}
+
+/// Continues filling of chosen draft, current wizard state is replaced
+pub async fn handle_resume_draft_callback(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    event_drafts_repository: Arc<dyn EventDraftsRepository>,
+) -> HandlerResult {
+    debug!("got handle_resume_draft_callback callback");
+
+    bot.answer_callback_query(q.id).await?;
+    let msg = match q.message {
+        None => {
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                .await?;
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+    let draft_id = match q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(RESUME_DRAFT_BTN_PREFIX))
+        .and_then(|draft_id| Uuid::parse_str(draft_id).ok())
+    {
+        None => {
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                .await?;
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+
+    let account_id = accounts_repository
+        .get_account_id_by_tg_user_id(q.from.id.0 as i64)
+        .await?;
+    let filling_event = match event_drafts_repository
+        .get_draft::<FillingEvent>(draft_id, account_id)
+        .await
+    {
+        Ok(v) => v,
+        Err(err) => {
+            debug!("cannot get event draft {}: {:?}", draft_id, err);
+            bot.send_message(q.from.id, t!("actions.event_drafts.not_found"))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // drafts list message is replaced with filling message
+    update_filling_message(
+        &bot,
+        accounts_repository.as_ref(),
+        event_drafts_repository.as_ref(),
+        dialogue,
+        filling_event,
+        Vec::new(),
+        msg.chat.id,
+        msg.id,
+    )
+    .await
+}
@@ -0,0 +1,158 @@
+use std::sync::Arc;
+
+use log::debug;
+use teloxide::prelude::*;
+use teloxide::types::ReplyMarkup;
+use teloxide::Bot;
+
+use resonanse_common::repository::{AccountFollowsRepository, AccountsRepository};
+
+use crate::data_translators::fill_base_account_from_teloxide_user;
+use crate::handlers::{log_request, HandlerResult};
+use crate::keyboards;
+
+pub async fn following_command(
+    bot: Bot,
+    msg: Message,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    account_follows_repository: Arc<dyn AccountFollowsRepository>,
+) -> HandlerResult {
+    log_request("got following command", &msg);
+    let Some(user) = msg.from() else {
+        return Ok(());
+    };
+
+    let account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(user))
+        .await?;
+    let organizers = account_follows_repository
+        .get_followed_organizers(account.id)
+        .await?;
+
+    if organizers.is_empty() {
+        bot.send_message(msg.chat.id, t!("actions.follow_organizer.empty"))
+            .await?;
+        return Ok(());
+    }
+
+    let mut message = bot.send_message(msg.chat.id, t!("actions.follow_organizer.list"));
+    message.reply_markup = Some(ReplyMarkup::InlineKeyboard(
+        keyboards::get_inline_kb_followed_organizers(&organizers),
+    ));
+    message.await?;
+
+    Ok(())
+}
+
+pub fn follow_organizer_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::FOLLOW_ORGANIZER_BTN_PREFIX)
+}
+
+/// Follows organizer of event or unfollows if user already follows them
+pub async fn handle_follow_organizer_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    account_follows_repository: Arc<dyn AccountFollowsRepository>,
+) -> HandlerResult {
+    debug!("got handle_follow_organizer_callback callback");
+
+    bot.answer_callback_query(q.id).await?;
+    let organizer_id = match q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(keyboards::FOLLOW_ORGANIZER_BTN_PREFIX))
+        .and_then(|organizer_id| organizer_id.parse::<i64>().ok())
+    {
+        None => {
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                .await?;
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+
+    // event post may be opened in channel by user who never started the bot
+    let account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(&q.from))
+        .await?;
+    if account.id == organizer_id {
+        bot.send_message(q.from.id, t!("actions.follow_organizer.self_follow"))
+            .await?;
+        return Ok(());
+    }
+
+    let msg_text = if account_follows_repository
+        .is_following(account.id, organizer_id)
+        .await?
+    {
+        account_follows_repository
+            .unfollow(account.id, organizer_id)
+            .await?;
+        t!("actions.follow_organizer.unfollowed")
+    } else {
+        account_follows_repository
+            .follow(account.id, organizer_id)
+            .await?;
+        t!("actions.follow_organizer.followed")
+    };
+    bot.send_message(q.from.id, msg_text).await?;
+
+    Ok(())
+}
+
+pub fn unfollow_organizer_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::UNFOLLOW_ORGANIZER_BTN_PREFIX)
+}
+
+/// Unfollows organizer from list of followed ones and updates the list
+pub async fn handle_unfollow_organizer_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    account_follows_repository: Arc<dyn AccountFollowsRepository>,
+) -> HandlerResult {
+    debug!("got handle_unfollow_organizer_callback callback");
+
+    bot.answer_callback_query(q.id).await?;
+    let organizer_id = match q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(keyboards::UNFOLLOW_ORGANIZER_BTN_PREFIX))
+        .and_then(|organizer_id| organizer_id.parse::<i64>().ok())
+    {
+        None => {
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                .await?;
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+
+    let account_id = accounts_repository
+        .get_account_id_by_tg_user_id(q.from.id.0 as i64)
+        .await?;
+    account_follows_repository
+        .unfollow(account_id, organizer_id)
+        .await?;
+
+    if let Some(msg) = q.message {
+        let organizers = account_follows_repository
+            .get_followed_organizers(account_id)
+            .await?;
+        if organizers.is_empty() {
+            bot.edit_message_text(msg.chat.id, msg.id, t!("actions.follow_organizer.empty"))
+                .await?;
+        } else {
+            bot.edit_message_text(msg.chat.id, msg.id, t!("actions.follow_organizer.list"))
+                .reply_markup(keyboards::get_inline_kb_followed_organizers(&organizers))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
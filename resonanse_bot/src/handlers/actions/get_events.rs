@@ -1,5 +1,8 @@
 use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
 
+use chrono_tz::Tz;
 use log::debug;
 
 use teloxide::prelude::*;
@@ -7,20 +10,31 @@ use teloxide::types::{Message, ParseMode, ReplyMarkup};
 use teloxide::utils::markdown;
 use teloxide::Bot;
 
-use resonanse_common::models::EventSubject;
-use resonanse_common::EventSubjectFilter;
+use resonanse_common::models::{City, EventFormat, EventSubject};
+use resonanse_common::repository::{AccountsRepository, EventsRepository};
+use resonanse_common::{EventFormatFilter, EventSubjectFilter};
 
+use crate::data_structs::format_event_datetime;
 use crate::handlers::{HandlerResult, MyDialogue};
-use crate::high_logics::send_event_post;
+use crate::high_logics::{get_user_timezone, send_base_event_post};
+use crate::keyboards;
 use crate::keyboards::{get_inline_kb_events_page, get_inline_kb_set_subject_filter};
 use crate::states::BaseState;
-use crate::{keyboards, EVENTS_REPOSITORY};
+use crate::utils::format_event_price;
 
 pub async fn handle_get_events(
     bot: Bot,
     _dialogue: MyDialogue,
-    (page_size, page_num, events_filter): (i64, i64, EventSubjectFilter),
+    (page_size, page_num, events_filter, city_filter, format_filter): (
+        i64,
+        i64,
+        EventSubjectFilter,
+        Option<City>,
+        EventFormatFilter,
+    ),
     msg: Message,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
 ) -> HandlerResult {
     // handle event command start
     if let Some(msg_text) = msg.text() {
@@ -28,14 +42,24 @@ pub async fn handle_get_events(
             if let Some(event_num) = rest_msg.split(' ').next() {
                 if let Ok(event_num) = event_num.parse::<i64>() {
                     // let event_global_num = event_num;
-                    let events = EVENTS_REPOSITORY
-                        .get()
-                        .ok_or("Cannot get events repository")?
-                        .get_public_events(page_num, page_size, &events_filter)
+                    let events = events_repository
+                        .get_public_events(
+                            page_num,
+                            page_size,
+                            &events_filter,
+                            city_filter,
+                            &format_filter,
+                        )
                         .await?;
 
                     if let Some(choosed_event) = events.get(event_num as usize - 1) {
-                        send_event_post(&bot, msg.chat.id, choosed_event.id).await?;
+                        send_base_event_post(
+                            &bot,
+                            accounts_repository.as_ref(),
+                            msg.chat.id,
+                            choosed_event.clone(),
+                        )
+                        .await?;
                         return Ok(());
                     }
                 }
@@ -44,7 +68,7 @@ pub async fn handle_get_events(
     }
     // handle event command end
 
-    bot.send_message(msg.chat.id, "Выбранное событие не найдено")
+    bot.send_message(msg.chat.id, t!("actions.get_events.event_not_found"))
         .await?;
 
     Ok(())
@@ -53,24 +77,59 @@ pub async fn handle_get_events(
 pub async fn handle_get_events_callback(
     bot: Bot,
     dialogue: MyDialogue,
-    (page_size, page_num, events_filter): (i64, i64, EventSubjectFilter),
+    (page_size, page_num, events_filter, city_filter, format_filter): (
+        i64,
+        i64,
+        EventSubjectFilter,
+        Option<City>,
+        EventFormatFilter,
+    ),
     q: CallbackQuery,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
 ) -> HandlerResult {
     debug!("got handle_get_events__callback callback");
     bot.answer_callback_query(q.id.clone()).await?;
 
     match q.data.as_deref() {
         None => {
-            bot.send_message(q.from.id, "Действие не распознано")
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
                 .await?;
             Ok(())
         }
         Some(keyboards::EVENTS_PAGE_LEFT | keyboards::EVENTS_PAGE_RIGHT) => {
-            handle_page_callback(bot, dialogue, (page_size, page_num, events_filter), q).await
+            handle_page_callback(
+                bot,
+                dialogue,
+                (
+                    page_size,
+                    page_num,
+                    events_filter,
+                    city_filter,
+                    format_filter,
+                ),
+                q,
+                events_repository,
+                accounts_repository,
+            )
+            .await
         }
         _ => {
-            handle_events_filter_callback(bot, dialogue, (page_size, page_num, events_filter), q)
-                .await
+            handle_events_filter_callback(
+                bot,
+                dialogue,
+                (
+                    page_size,
+                    page_num,
+                    events_filter,
+                    city_filter,
+                    format_filter,
+                ),
+                q,
+                events_repository,
+                accounts_repository,
+            )
+            .await
         }
     }
 }
@@ -78,14 +137,23 @@ pub async fn handle_get_events_callback(
 pub async fn handle_events_filter_callback(
     bot: Bot,
     dialogue: MyDialogue,
-    (page_size, page_num, mut events_filter): (i64, i64, EventSubjectFilter),
+    (page_size, page_num, mut events_filter, mut city_filter, mut format_filter): (
+        i64,
+        i64,
+        EventSubjectFilter,
+        Option<City>,
+        EventFormatFilter,
+    ),
     q: CallbackQuery,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
 ) -> HandlerResult {
     debug!("got handle_events_filter_callback callback");
 
     let msg = match q.message {
         None => {
-            bot.send_message(q.from.id, "Unknown message").await?;
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                .await?;
             return Ok(());
         }
         Some(v) => v,
@@ -96,25 +164,50 @@ pub async fn handle_events_filter_callback(
         Some(keyboards::APPLY_EVENT_FILTER_BTN) => {
             bot.delete_message(msg.chat.id, msg.id).await?;
 
-            let msg_text = get_choose_event_text(page_num, page_size, &events_filter).await?;
+            let viewer_tz =
+                get_user_timezone(accounts_repository.as_ref(), q.from.id.0 as i64).await;
+            let msg_text = get_choose_event_text(
+                events_repository.as_ref(),
+                page_num,
+                page_size,
+                &events_filter,
+                city_filter,
+                &format_filter,
+                viewer_tz,
+            )
+            .await?;
             let mut message = bot.send_message(q.from.id, msg_text);
             message.reply_markup = Some(ReplyMarkup::InlineKeyboard(get_inline_kb_events_page()));
             message.parse_mode = Some(ParseMode::MarkdownV2);
             message.await?;
             return Ok(());
         }
-        Some(text) => match EventSubject::try_from(text) {
-            Ok(event_subject) => {
+        Some(text) => {
+            if let Ok(event_subject) = EventSubject::try_from(text) {
                 events_filter.switch(event_subject);
-                let mut edit_msg = bot.edit_message_reply_markup(msg.chat.id, msg.id);
-                edit_msg.reply_markup = Some(get_inline_kb_set_subject_filter(&events_filter));
-                edit_msg.await?;
-            }
-            Err(_e) => {
-                bot.send_message(q.from.id, "Не распознанное действие")
+            } else if let Ok(city) = City::from_str(text) {
+                city_filter = match city_filter {
+                    Some(chosen_city) if chosen_city == city => None,
+                    _ => Some(city),
+                };
+            } else if let Ok(event_format) = EventFormat::from_str(text) {
+                format_filter.switch(event_format);
+            } else if text == keyboards::ONLY_FREE_EVENT_FILTER_BTN {
+                format_filter.switch_only_free();
+            } else {
+                bot.send_message(q.from.id, t!("actions.unrecognized_action"))
                     .await?;
+                return Ok(());
             }
-        },
+
+            let mut edit_msg = bot.edit_message_reply_markup(msg.chat.id, msg.id);
+            edit_msg.reply_markup = Some(get_inline_kb_set_subject_filter(
+                &events_filter,
+                city_filter,
+                &format_filter,
+            ));
+            edit_msg.await?;
+        }
     };
 
     dialogue
@@ -122,6 +215,8 @@ pub async fn handle_events_filter_callback(
             page_size,
             page_num,
             events_filter,
+            city_filter,
+            format_filter,
         })
         .await?;
 
@@ -131,15 +226,24 @@ pub async fn handle_events_filter_callback(
 pub async fn handle_page_callback(
     bot: Bot,
     dialogue: MyDialogue,
-    (page_size, page_num, events_filter): (i64, i64, EventSubjectFilter),
+    (page_size, page_num, events_filter, city_filter, format_filter): (
+        i64,
+        i64,
+        EventSubjectFilter,
+        Option<City>,
+        EventFormatFilter,
+    ),
     q: CallbackQuery,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
 ) -> HandlerResult {
     debug!("got handle_page_callback callback");
     let page_num = page_num as u32;
 
     let msg = match q.message {
         None => {
-            bot.send_message(q.from.id, "Unknown message").await?;
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                .await?;
             return Ok(());
         }
         Some(v) => v,
@@ -160,10 +264,22 @@ pub async fn handle_page_callback(
             page_size,
             page_num,
             events_filter: events_filter.clone(),
+            city_filter,
+            format_filter: format_filter.clone(),
         })
         .await?;
 
-    let msg_text = get_choose_event_text(page_num, page_size, &events_filter).await?;
+    let viewer_tz = get_user_timezone(accounts_repository.as_ref(), q.from.id.0 as i64).await;
+    let msg_text = get_choose_event_text(
+        events_repository.as_ref(),
+        page_num,
+        page_size,
+        &events_filter,
+        city_filter,
+        &format_filter,
+        viewer_tz,
+    )
+    .await?;
     let mut message = bot.edit_message_text(msg.chat.id, msg.id, msg_text);
     message.reply_markup = Some(get_inline_kb_events_page());
     message.parse_mode = Some(ParseMode::MarkdownV2);
@@ -173,14 +289,22 @@ pub async fn handle_page_callback(
 }
 
 pub async fn get_choose_event_text(
+    events_repository: &dyn EventsRepository,
     page_num: i64,
     page_size: i64,
     events_filter: &EventSubjectFilter,
+    city_filter: Option<City>,
+    format_filter: &EventFormatFilter,
+    viewer_tz: Tz,
 ) -> Result<String, Box<dyn Error + Send + Sync>> {
-    let events = EVENTS_REPOSITORY
-        .get()
-        .ok_or("Cannot get events repository")?
-        .get_public_events(page_num, page_size, events_filter)
+    let events = events_repository
+        .get_public_events(
+            page_num,
+            page_size,
+            events_filter,
+            city_filter,
+            format_filter,
+        )
         .await?;
 
     let mut event_i = 0;
@@ -199,13 +323,29 @@ pub async fn get_choose_event_text(
                     None => String::new(),
                 };
 
+                let recurrence_mark = match event.recurrence {
+                    Some(_) => " 🔁",
+                    None => "",
+                };
+
+                // online events have no place, so show format instead
+                let event_place_text = match event.location_title.as_deref() {
+                    Some(location_title) => format!("📍 {}", location_title),
+                    None => format!("🌐 {}", t!(&event.event_format.to_string())),
+                };
+                let event_price_text = match event.price.as_ref() {
+                    Some(price) => format!(" · 💳 {}", format_event_price(price)),
+                    None => String::new(),
+                };
+
                 format!(
-                    "/event\\_{}\t*{}*{}\n⏰ {}\n📍 {}",
+                    "/event\\_{}\t*{}*{}\n⏰ {}{}\n{}",
                     event_i,
                     markdown::escape(&event.title),
                     markdown::escape(&event_brief_description_text),
-                    markdown::escape(&event.datetime_from.to_string()),
-                    markdown::escape(&event.location_title),
+                    markdown::escape(&format_event_datetime(event, viewer_tz)),
+                    recurrence_mark,
+                    markdown::escape(&format!("{}{}", event_place_text, event_price_text)),
                 )
             })
             .collect::<Vec<String>>()
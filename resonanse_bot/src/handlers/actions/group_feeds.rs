@@ -0,0 +1,192 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use log::{debug, warn};
+use teloxide::prelude::*;
+use teloxide::types::{Chat, ChatMemberUpdated};
+use teloxide::Bot;
+
+use resonanse_common::models::{City, EventSubject};
+use resonanse_common::repository::GroupFeedsRepository;
+
+use crate::handlers::HandlerResult;
+use crate::keyboards;
+
+fn is_group_chat(chat: &Chat) -> bool {
+    chat.is_group() || chat.is_supergroup()
+}
+
+pub fn group_message_handler(msg: Message) -> bool {
+    is_group_chat(&msg.chat)
+}
+
+/// Feed settings are changed only by group administrators
+async fn is_group_admin(bot: &Bot, chat_id: ChatId, user_id: UserId) -> bool {
+    match bot.get_chat_member(chat_id, user_id).await {
+        Ok(chat_member) => chat_member.is_privileged(),
+        Err(err) => {
+            warn!(
+                "cannot get member {} of chat {}: {:?}",
+                user_id, chat_id, err
+            );
+            false
+        }
+    }
+}
+
+/// Registers group feed when bot is added to group and removes it when bot leaves
+pub async fn handle_my_chat_member(
+    bot: Bot,
+    upd: ChatMemberUpdated,
+    group_feeds_repository: Arc<dyn GroupFeedsRepository>,
+) -> HandlerResult {
+    debug!("got my_chat_member update {:?}", upd);
+    if !is_group_chat(&upd.chat) {
+        return Ok(());
+    }
+
+    let was_present = upd.old_chat_member.is_present();
+    let is_present = upd.new_chat_member.is_present();
+
+    if !was_present && is_present {
+        group_feeds_repository
+            .add_group_feed(upd.chat.id.0, upd.chat.title())
+            .await?;
+        bot.send_message(upd.chat.id, t!("actions.group_feed.greeting"))
+            .await?;
+    } else if was_present && !is_present {
+        group_feeds_repository
+            .delete_group_feed(upd.chat.id.0)
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn ignore_group_message() -> HandlerResult {
+    Ok(())
+}
+
+pub async fn group_feed_command(
+    bot: Bot,
+    msg: Message,
+    group_feeds_repository: Arc<dyn GroupFeedsRepository>,
+) -> HandlerResult {
+    let Some(user) = msg.from() else {
+        return Ok(());
+    };
+    if !is_group_admin(&bot, msg.chat.id, user.id).await {
+        bot.send_message(msg.chat.id, t!("actions.group_feed.admins_only"))
+            .await?;
+        return Ok(());
+    }
+
+    // bot may be added to group before feeds were introduced
+    let group_feed = group_feeds_repository
+        .add_group_feed(msg.chat.id.0, msg.chat.title())
+        .await?;
+
+    bot.send_message(msg.chat.id, t!("actions.group_feed.settings"))
+        .reply_markup(keyboards::get_inline_kb_group_feed(&group_feed))
+        .await?;
+
+    Ok(())
+}
+
+pub fn group_feed_subject_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::GROUP_FEED_SUBJECT_BTN_PREFIX)
+}
+
+pub async fn handle_group_feed_subject_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    group_feeds_repository: Arc<dyn GroupFeedsRepository>,
+) -> HandlerResult {
+    debug!("got handle_group_feed_subject_callback callback");
+
+    let Some(msg) = q.message.as_ref() else {
+        bot.answer_callback_query(q.id).await?;
+        return Ok(());
+    };
+    if !is_group_admin(&bot, msg.chat.id, q.from.id).await {
+        bot.answer_callback_query(q.id)
+            .text(t!("actions.group_feed.admins_only"))
+            .await?;
+        return Ok(());
+    }
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    let event_subject = match q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(keyboards::GROUP_FEED_SUBJECT_BTN_PREFIX))
+        .and_then(|subject| EventSubject::from_str(subject).ok())
+    {
+        None => {
+            warn!("unknown group feed subject callback {:?}", q.data);
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+
+    let group_feed = group_feeds_repository
+        .switch_group_feed_subject(msg.chat.id.0, event_subject)
+        .await?;
+
+    bot.edit_message_reply_markup(msg.chat.id, msg.id)
+        .reply_markup(keyboards::get_inline_kb_group_feed(&group_feed))
+        .await?;
+
+    Ok(())
+}
+
+pub fn group_feed_city_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::GROUP_FEED_CITY_BTN_PREFIX)
+}
+
+pub async fn handle_group_feed_city_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    group_feeds_repository: Arc<dyn GroupFeedsRepository>,
+) -> HandlerResult {
+    debug!("got handle_group_feed_city_callback callback");
+
+    let Some(msg) = q.message.as_ref() else {
+        bot.answer_callback_query(q.id).await?;
+        return Ok(());
+    };
+    if !is_group_admin(&bot, msg.chat.id, q.from.id).await {
+        bot.answer_callback_query(q.id)
+            .text(t!("actions.group_feed.admins_only"))
+            .await?;
+        return Ok(());
+    }
+    bot.answer_callback_query(q.id.clone()).await?;
+
+    let city = match q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(keyboards::GROUP_FEED_CITY_BTN_PREFIX))
+        .and_then(|city| City::from_str(city).ok())
+    {
+        None => {
+            warn!("unknown group feed city callback {:?}", q.data);
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+
+    let group_feed = group_feeds_repository
+        .switch_group_feed_city(msg.chat.id.0, city)
+        .await?;
+
+    bot.edit_message_reply_markup(msg.chat.id, msg.id)
+        .reply_markup(keyboards::get_inline_kb_group_feed(&group_feed))
+        .await?;
+
+    Ok(())
+}
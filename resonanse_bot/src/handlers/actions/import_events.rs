@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use log::{debug, warn};
+use teloxide::net::Download;
+use teloxide::prelude::*;
+use teloxide::types::{Document, ParseMode};
+use teloxide::Bot;
+
+use resonanse_common::repository::AccountsRepository;
+
+use crate::data_structs::FillingEvent;
+use crate::data_translators::parse_ics_to_filling_events;
+use crate::errors::BotHandlerError;
+use crate::handlers::actions::create_event::{
+    send_filling_message, DESCRIPTION_LIMIT, PLACE_TITLE_LIMIT, TITLE_LIMIT,
+};
+use crate::handlers::{log_request, HandlerResult, MyDialogue};
+use crate::high_logics::get_user_timezone;
+use crate::states::{BaseState, CreateEventState};
+
+const ICS_FILE_EXTENSION: &str = ".ics";
+const ICS_MIME_TYPE: &str = "text/calendar";
+// calendars with thousands of events are not expected from organizers
+const ICS_MAX_FILE_SIZE: u32 = 512 * 1024;
+const ICS_MAX_DRAFTS: usize = 20;
+
+fn is_ics_document(document: &Document) -> bool {
+    let is_ics_file_name = document
+        .file_name
+        .as_deref()
+        .map(|file_name| file_name.to_lowercase().ends_with(ICS_FILE_EXTENSION))
+        .unwrap_or(false);
+    let is_ics_mime = document
+        .mime_type
+        .as_ref()
+        .map(|mime| mime.essence_str() == ICS_MIME_TYPE)
+        .unwrap_or(false);
+
+    is_ics_file_name || is_ics_mime
+}
+
+pub fn ics_document_handler(msg: Message) -> bool {
+    msg.document().map(is_ics_document).unwrap_or(false)
+}
+
+pub async fn import_events_command(bot: Bot, msg: Message) -> HandlerResult {
+    log_request("got import_events command", &msg);
+
+    let mut message = bot.send_message(msg.chat.id, t!("actions.import_events.instructions"));
+    message.parse_mode = Some(ParseMode::MarkdownV2);
+    message.await?;
+
+    Ok(())
+}
+
+pub async fn handle_ics_document_in_dialogue(bot: Bot, msg: Message) -> HandlerResult {
+    log_request("got ics document during another action", &msg);
+
+    bot.send_message(msg.chat.id, t!("actions.import_events.busy"))
+        .await?;
+
+    Ok(())
+}
+
+/// Cuts imported text to wizard limits, too short values are dropped
+fn fit_to_limit(value: Option<String>, max_chars: usize, min_chars: usize) -> Option<String> {
+    let value: String = value?.chars().take(max_chars).collect();
+    if value.chars().count() < min_chars {
+        return None;
+    }
+    Some(value)
+}
+
+fn prepare_imported_draft(mut filling_event: FillingEvent) -> FillingEvent {
+    filling_event.title = fit_to_limit(
+        filling_event.title.map(|title| title.replace('\n', " ")),
+        *TITLE_LIMIT.end(),
+        *TITLE_LIMIT.start(),
+    );
+    filling_event.description = fit_to_limit(
+        filling_event.description,
+        *DESCRIPTION_LIMIT.end(),
+        *DESCRIPTION_LIMIT.start(),
+    );
+    filling_event.location_title = fit_to_limit(
+        filling_event.location_title,
+        *PLACE_TITLE_LIMIT.end(),
+        *PLACE_TITLE_LIMIT.start(),
+    );
+
+    filling_event
+}
+
+pub async fn handle_ics_document(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    accounts_repository: Arc<dyn AccountsRepository>,
+) -> HandlerResult {
+    log_request("got ics document", &msg);
+
+    let document = match msg.document() {
+        None => return Ok(()),
+        Some(v) => v,
+    };
+
+    if document.file.size > ICS_MAX_FILE_SIZE {
+        return Err(Box::new(BotHandlerError::Validation(t!(
+            "actions.import_events.too_large"
+        ))));
+    }
+
+    let tg_file = bot.get_file(&document.file.id).await?;
+    let mut ics_content: Vec<u8> = Vec::new();
+    bot.download_file(&tg_file.path, &mut ics_content).await?;
+
+    let user_tz = get_user_timezone(accounts_repository.as_ref(), msg.chat.id.0).await;
+    let mut drafts = parse_ics_to_filling_events(&String::from_utf8_lossy(&ics_content), user_tz)
+        .into_iter()
+        .map(prepare_imported_draft)
+        .collect::<Vec<_>>();
+    debug!("imported {} drafts from ics", drafts.len());
+
+    if drafts.is_empty() {
+        return Err(Box::new(BotHandlerError::Validation(t!(
+            "actions.import_events.not_found"
+        ))));
+    }
+    let found_msg_text = if drafts.len() > ICS_MAX_DRAFTS {
+        warn!(
+            "ics document contains {} events, only first {} will be imported",
+            drafts.len(),
+            ICS_MAX_DRAFTS
+        );
+        let found_msg_text = t!(
+            "actions.import_events.found_limited",
+            drafts_count = drafts.len(),
+            imported_count = ICS_MAX_DRAFTS
+        );
+        drafts.truncate(ICS_MAX_DRAFTS);
+        found_msg_text
+    } else {
+        t!("actions.import_events.found", drafts_count = drafts.len())
+    };
+
+    bot.send_message(msg.chat.id, found_msg_text).await?;
+
+    let filling_event = drafts.remove(0);
+    let sent_msg = send_filling_message(&bot, &filling_event, msg.chat.id).await?;
+
+    dialogue
+        .update(BaseState::CreateEvent {
+            state: CreateEventState::Idle,
+            filling_event,
+            last_edit_msg_id: sent_msg.id,
+            pending_drafts: drafts,
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use log::debug;
+use teloxide::prelude::*;
+use teloxide::types::{
+    InlineQueryResult, InlineQueryResultArticle, InputMessageContent, InputMessageContentText,
+    ParseMode,
+};
+use teloxide::Bot;
+
+use resonanse_common::models::BaseEvent;
+use resonanse_common::repository::EventsRepository;
+
+use crate::data_structs::{format_event_datetime, format_event_post_text};
+use crate::handlers::HandlerResult;
+use crate::keyboards;
+use crate::utils::build_event_deep_link;
+
+const INLINE_RESULTS_LIMIT: usize = 20;
+const INLINE_RESULTS_CACHE_TIME_SECS: u32 = 60;
+
+/// Posters are stored as local files and have no telegram file id,
+/// so events are shared as text articles
+fn build_event_inline_result(base_event: &BaseEvent) -> InlineQueryResult {
+    let event_tz = base_event.timezone;
+    let msg_text = format_event_post_text(base_event, event_tz);
+    let reply_markup = keyboards::get_inline_kb_shared_event_message(
+        base_event.id,
+        base_event.creator_id,
+        base_event
+            .location
+            .as_ref()
+            .map(|loc| loc.get_yandex_map_link_to()),
+        build_event_deep_link(base_event.id),
+    );
+
+    let mut description = format_event_datetime(base_event, event_tz);
+    if let Some(location_title) = base_event.location_title.as_deref() {
+        description.push('\n');
+        description.push_str(location_title);
+    }
+
+    // recurring event is shown once, by its nearest occurrence
+    InlineQueryResult::Article(
+        InlineQueryResultArticle::new(
+            base_event.id.simple().to_string(),
+            base_event.title.clone(),
+            InputMessageContent::Text(
+                InputMessageContentText::new(msg_text).parse_mode(ParseMode::MarkdownV2),
+            ),
+        )
+        .description(description)
+        .reply_markup(reply_markup),
+    )
+}
+
+/// Searches upcoming public events for `@bot <query>`, so they can be shared in any chat
+pub async fn handle_inline_query(
+    bot: Bot,
+    q: InlineQuery,
+    events_repository: Arc<dyn EventsRepository>,
+) -> HandlerResult {
+    debug!("got inline query {:?} from {}", q.query, q.from.id);
+
+    let events = events_repository
+        .search_upcoming_public_events(q.query.trim(), INLINE_RESULTS_LIMIT)
+        .await?;
+
+    let results = events
+        .iter()
+        .map(build_event_inline_result)
+        .collect::<Vec<_>>();
+    bot.answer_inline_query(q.id, results)
+        .cache_time(INLINE_RESULTS_CACHE_TIME_SECS)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use std::sync::Arc;
+
+use log::debug;
+use teloxide::prelude::*;
+use teloxide::Bot;
+
+use resonanse_common::repository::AccountsRepository;
+
+use crate::data_translators::fill_base_account_from_teloxide_user;
+use crate::handlers::{log_request, HandlerResult};
+use crate::keyboards;
+use crate::locales::SUPPORTED_LOCALES;
+
+pub async fn language_command(bot: Bot, msg: Message) -> HandlerResult {
+    log_request("got language command", &msg);
+
+    bot.send_message(msg.chat.id, t!("actions.language.choose"))
+        .reply_markup(keyboards::get_inline_kb_choose_language())
+        .await?;
+
+    Ok(())
+}
+
+pub fn choose_language_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::CHOOSE_LANGUAGE_BTN_PREFIX)
+}
+
+pub async fn handle_choose_language_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    accounts_repository: Arc<dyn AccountsRepository>,
+) -> HandlerResult {
+    debug!("got handle_choose_language_callback callback");
+
+    bot.answer_callback_query(q.id).await?;
+    let locale = match q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(keyboards::CHOOSE_LANGUAGE_BTN_PREFIX))
+        .filter(|locale| SUPPORTED_LOCALES.contains(locale))
+    {
+        None => {
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                .await?;
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+
+    let account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(&q.from))
+        .await?;
+    accounts_repository
+        .set_user_locale(account.id, locale)
+        .await?;
+
+    // update is handled in previous locale, so new one is passed explicitly
+    bot.send_message(q.from.id, t!("actions.language.changed", locale = locale))
+        .await?;
+
+    Ok(())
+}
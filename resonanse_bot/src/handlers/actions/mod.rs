@@ -1,9 +1,29 @@
+pub use cancel_occurrence::*;
+pub use communities::*;
 pub use create_event::*;
+pub use event_drafts::*;
+pub use follow_organizer::*;
 pub use get_events::*;
+pub use group_feeds::*;
+pub use import_events::*;
+pub use inline_events::*;
+pub use language::*;
+pub use profile::*;
+pub use rsvp_event::*;
 pub use score_event::*;
 pub use send_feedback::*;
 
+mod cancel_occurrence;
+mod communities;
 mod create_event;
+mod event_drafts;
+mod follow_organizer;
 mod get_events;
+mod group_feeds;
+mod import_events;
+mod inline_events;
+mod language;
+mod profile;
+mod rsvp_event;
 mod score_event;
 mod send_feedback;
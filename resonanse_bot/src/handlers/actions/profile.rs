@@ -0,0 +1,218 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use log::debug;
+use teloxide::prelude::*;
+use teloxide::Bot;
+
+use resonanse_common::models::{City, UserData};
+use resonanse_common::repository::AccountsRepository;
+
+use crate::data_structs::{ProfileField, ProfileFieldError, AGE_LIMIT, PROFILE_FIELDS};
+use crate::data_translators::fill_base_account_from_teloxide_user;
+use crate::handlers::{log_request, HandlerResult, MyDialogue};
+use crate::keyboards;
+use crate::states::BaseState;
+
+fn format_profile(user_data: &UserData) -> String {
+    let profile_fields = PROFILE_FIELDS
+        .iter()
+        .map(|field| {
+            format!(
+                "{}: {}",
+                t!(field.translation_key()),
+                field.get_value(user_data).unwrap_or("—".to_string())
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "{} {}\n\n{}\n\n{}",
+        user_data.first_name,
+        user_data.last_name,
+        profile_fields,
+        t!("actions.profile.choose_field")
+    )
+}
+
+async fn send_profile(bot: &Bot, chat_id: ChatId, user_data: &UserData) -> HandlerResult {
+    bot.send_message(chat_id, format_profile(user_data))
+        .reply_markup(keyboards::get_inline_kb_edit_profile())
+        .await?;
+
+    Ok(())
+}
+
+pub async fn profile_command(
+    bot: Bot,
+    msg: Message,
+    accounts_repository: Arc<dyn AccountsRepository>,
+) -> HandlerResult {
+    log_request("got profile command", &msg);
+    let Some(user) = msg.from() else {
+        return Ok(());
+    };
+
+    let account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(user))
+        .await?;
+    send_profile(&bot, msg.chat.id, &account.user_data).await
+}
+
+pub fn profile_field_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::PROFILE_FIELD_BTN_PREFIX)
+}
+
+pub async fn handle_profile_field_callback(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+) -> HandlerResult {
+    debug!("got handle_profile_field_callback callback");
+
+    bot.answer_callback_query(q.id).await?;
+    let field = match q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(keyboards::PROFILE_FIELD_BTN_PREFIX))
+        .and_then(|field| ProfileField::from_str(field).ok())
+    {
+        None => {
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                .await?;
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+
+    let field_name = t!(field.translation_key());
+    let mut msg_text = match field {
+        ProfileField::Age => t!(
+            "actions.profile.enter_age",
+            min_age = AGE_LIMIT.start(),
+            max_age = AGE_LIMIT.end()
+        ),
+        _ => t!(
+            "actions.profile.enter_value",
+            field_name = field_name,
+            max_chars = field.text_limit().end()
+        ),
+    };
+    if !field.is_required() {
+        msg_text = format!("{}\n{}", msg_text, t!("actions.profile.clear_hint"));
+    }
+    let mut message = bot.send_message(q.from.id, msg_text);
+    if field == ProfileField::City {
+        // city can be either typed or picked
+        message = message.reply_markup(keyboards::get_inline_kb_choose_home_city());
+    }
+    message.await?;
+    dialogue.update(BaseState::EditProfile { field }).await?;
+
+    Ok(())
+}
+
+pub async fn handle_edit_profile_state_message(
+    bot: Bot,
+    dialogue: MyDialogue,
+    field: ProfileField,
+    msg: Message,
+    accounts_repository: Arc<dyn AccountsRepository>,
+) -> HandlerResult {
+    let Some(user) = msg.from() else {
+        return Ok(());
+    };
+    let Some(msg_text) = msg.text() else {
+        bot.send_message(msg.chat.id, t!("actions.profile.text_expected"))
+            .await?;
+        return Ok(());
+    };
+
+    let mut account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(user))
+        .await?;
+
+    // user stays in edit state until value is accepted or another command is sent
+    if let Err(err) = field.set_value(&mut account.user_data, msg_text) {
+        let err_text = match err {
+            ProfileFieldError::Required => t!("actions.profile.required"),
+            ProfileFieldError::InvalidLength(text_limit) => t!(
+                "actions.invalid_text_length",
+                min_chars = text_limit.start(),
+                max_chars = text_limit.end(),
+                chars_count = msg_text.trim().chars().count()
+            ),
+            ProfileFieldError::InvalidAge => t!(
+                "actions.profile.enter_age",
+                min_age = AGE_LIMIT.start(),
+                max_age = AGE_LIMIT.end()
+            ),
+            ProfileFieldError::UnknownCity => t!("actions.home_city.unknown"),
+        };
+        bot.send_message(msg.chat.id, err_text).await?;
+        return Ok(());
+    }
+
+    accounts_repository
+        .update_user_data(account.id, &account.user_data)
+        .await?;
+    dialogue.update(BaseState::Idle).await?;
+
+    bot.send_message(msg.chat.id, t!("actions.profile.saved"))
+        .await?;
+    send_profile(&bot, msg.chat.id, &account.user_data).await
+}
+
+pub fn home_city_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::HOME_CITY_BTN_PREFIX)
+}
+
+pub async fn handle_home_city_callback(
+    bot: Bot,
+    dialogue: MyDialogue,
+    q: CallbackQuery,
+    accounts_repository: Arc<dyn AccountsRepository>,
+) -> HandlerResult {
+    debug!("got handle_home_city_callback callback");
+
+    bot.answer_callback_query(q.id).await?;
+    let city = match q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(keyboards::HOME_CITY_BTN_PREFIX))
+        .and_then(|city| City::from_str(city).ok())
+    {
+        None => {
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                .await?;
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+
+    let account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(&q.from))
+        .await?;
+    accounts_repository.set_user_city(account.id, city).await?;
+
+    // city picked instead of typed in profile editing
+    if let Some(BaseState::EditProfile {
+        field: ProfileField::City,
+    }) = dialogue.get().await?
+    {
+        dialogue.update(BaseState::Idle).await?;
+    }
+
+    bot.send_message(
+        q.from.id,
+        t!("actions.home_city.changed", city = t!(&city.to_string())),
+    )
+    .await?;
+
+    Ok(())
+}
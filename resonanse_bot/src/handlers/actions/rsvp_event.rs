@@ -0,0 +1,125 @@
+use std::sync::Arc;
+
+use log::{debug, warn};
+use teloxide::prelude::*;
+use teloxide::Bot;
+use uuid::Uuid;
+
+use resonanse_common::models::ParticipationStatus;
+use resonanse_common::repository::{
+    AccountsRepository, EventParticipantsRepository, EventsRepository,
+};
+
+use crate::data_translators::fill_base_account_from_teloxide_user;
+use crate::errors::BotHandlerError;
+use crate::handlers::HandlerResult;
+use crate::high_logics::can_view_event;
+use crate::keyboards;
+use crate::locales::get_user_locale;
+
+pub fn rsvp_event_handler(q: CallbackQuery) -> bool {
+    q.data
+        .unwrap_or_default()
+        .starts_with(keyboards::RSVP_EVENT_BTN_PREFIX)
+}
+
+/// Registers user to event or cancels registration if user is already registered
+pub async fn handle_rsvp_event_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    participants_repository: Arc<dyn EventParticipantsRepository>,
+) -> HandlerResult {
+    debug!("got handle_rsvp_event_callback callback");
+
+    bot.answer_callback_query(q.id).await?;
+    let event_id = match q
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(keyboards::RSVP_EVENT_BTN_PREFIX))
+        .and_then(|event_id| Uuid::parse_str(event_id).ok())
+    {
+        None => {
+            bot.send_message(q.from.id, t!("actions.unrecognized_action"))
+                .await?;
+            return Ok(());
+        }
+        Some(v) => v,
+    };
+
+    // event post may be opened in channel by user who never started the bot
+    let account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(&q.from))
+        .await?;
+    let base_event = events_repository
+        .get_event_by_uuid(event_id)
+        .await
+        .map_err(BotHandlerError::lookup("event"))?;
+    if !can_view_event(
+        events_repository.as_ref(),
+        accounts_repository.as_ref(),
+        q.from.id.0 as i64,
+        &base_event,
+    )
+    .await
+    {
+        bot.send_message(q.from.id, t!("actions.private_event.no_access"))
+            .await?;
+        return Ok(());
+    }
+    let participation_status = participants_repository
+        .get_participation_status(event_id, account.id)
+        .await?;
+    if participation_status.is_some() {
+        let promoted_tg_user_id = participants_repository
+            .leave_event(event_id, account.id)
+            .await?;
+        bot.send_message(
+            q.from.id,
+            t!("actions.rsvp_event.left", event_title = base_event.title),
+        )
+        .await?;
+
+        if let Some(promoted_tg_user_id) = promoted_tg_user_id {
+            let promoted_user_locale =
+                get_user_locale(accounts_repository.as_ref(), promoted_tg_user_id, None).await;
+            let notify_result = bot
+                .send_message(
+                    UserId(promoted_tg_user_id as u64),
+                    t!(
+                        "actions.rsvp_event.promoted",
+                        locale = &promoted_user_locale,
+                        event_title = base_event.title
+                    ),
+                )
+                .await;
+            // user may have never started the bot or blocked it
+            if let Err(err) = notify_result {
+                warn!(
+                    "cannot notify user {} promoted from waitlist: {:?}",
+                    promoted_tg_user_id, err
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    let msg_text = match participants_repository
+        .join_event(event_id, account.id)
+        .await?
+    {
+        ParticipationStatus::Going => {
+            t!("actions.rsvp_event.going", event_title = base_event.title)
+        }
+        ParticipationStatus::Waitlisted => {
+            t!(
+                "actions.rsvp_event.waitlisted",
+                event_title = base_event.title
+            )
+        }
+    };
+    bot.send_message(q.from.id, msg_text).await?;
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use log::debug;
 use teloxide::prelude::*;
 use teloxide::Bot;
@@ -6,7 +8,7 @@ use uuid::Uuid;
 use resonanse_common::models::EventScoreType;
 use resonanse_common::repository::{AccountsRepository, EventScoresRepository};
 
-use crate::config::POSTGRES_DB_URL;
+use crate::data_translators::fill_base_account_from_teloxide_user;
 use crate::handlers::HandlerResult;
 use crate::keyboards;
 
@@ -16,25 +18,19 @@ pub fn score_event_handler(q: CallbackQuery) -> bool {
         || q_data.starts_with(keyboards::INLINE_DISLIKE_EVENT_BTN)
 }
 
-pub async fn handle_score_event_callback(bot: Bot, q: CallbackQuery) -> HandlerResult {
+pub async fn handle_score_event_callback(
+    bot: Bot,
+    q: CallbackQuery,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    events_score_repository: Arc<dyn EventScoresRepository>,
+) -> HandlerResult {
     debug!("got handle_score_event_callback callback");
 
     bot.answer_callback_query(q.id).await?;
-    let msg = match q.message {
-        None => {
-            bot.send_message(q.from.id, "Unknown message").await?;
-            return Ok(());
-        }
-        Some(v) => v,
-    };
-
-    let conn_url = std::env::var(POSTGRES_DB_URL).unwrap();
-    let pool = resonanse_common::PgPool::connect(&conn_url).await?;
-    let events_score_repository = EventScoresRepository::new(pool.clone());
-    let accounts_repository = AccountsRepository::new(pool.clone());
 
+    // event shared via inline mode may be scored by user who never started the bot
     let user_account = accounts_repository
-        .get_user_by_tg_id(q.from.id.0 as i64)
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(&q.from))
         .await?;
 
     match q.data.as_deref() {
@@ -47,6 +43,10 @@ pub async fn handle_score_event_callback(bot: Bot, q: CallbackQuery) -> HandlerR
                 .set_event_score_by_user(event_id, user_account.id, EventScoreType::Like)
                 .await?;
 
+            // message shared via inline mode is common for all users, so it is not marked
+            let Some(msg) = q.message.as_ref() else {
+                return Ok(());
+            };
             debug!("inline kb {:?}", msg.reply_markup());
             if let Some(inline_kb) = msg.reply_markup() {
                 let mut inline_kb = inline_kb.clone();
@@ -79,6 +79,10 @@ pub async fn handle_score_event_callback(bot: Bot, q: CallbackQuery) -> HandlerR
                 .set_event_score_by_user(event_id, user_account.id, EventScoreType::Dislike)
                 .await?;
 
+            // message shared via inline mode is common for all users, so it is not marked
+            let Some(msg) = q.message.as_ref() else {
+                return Ok(());
+            };
             debug!("inline kb {:?}", msg.reply_markup());
             if let Some(inline_kb) = msg.reply_markup() {
                 let mut inline_kb = inline_kb.clone();
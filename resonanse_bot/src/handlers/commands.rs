@@ -1,22 +1,26 @@
-use std::env;
 use std::str::FromStr;
+use std::sync::Arc;
 
-use teloxide::Bot;
 use teloxide::prelude::*;
 use teloxide::types::{ParseMode, ReplyMarkup};
 use teloxide::utils::command::parse_command;
+use teloxide::Bot;
 use uuid::Uuid;
 
-use resonanse_common::EventSubjectFilter;
+use resonanse_common::repository::{AccountsRepository, CommunitiesRepository, EventsRepository};
+use resonanse_common::{EventFormatFilter, EventSubjectFilter};
 
-use crate::{ACCOUNTS_REPOSITORY, keyboards};
-use crate::config::DONATION_URL;
+use crate::config::config;
 use crate::data_structs::FillingEvent;
 use crate::data_translators::fill_base_account_from_teloxide_user;
-use crate::handlers::{HandlerResult, log_request, MyDialogue};
-use crate::high_logics::send_event_post;
+use crate::handlers::{log_request, HandlerResult, MyDialogue};
+use crate::high_logics::{
+    get_user_city, get_user_timezone, open_community_by_invite, open_event_by_invite,
+    send_event_post,
+};
 use crate::keyboards::{get_inline_kb_run_web_app, get_inline_kb_set_subject_filter};
 use crate::states::{BaseState, CreateEventState};
+use crate::keyboards;
 
 // const CREATE_EVENT_TEXT_MD: &str = r#"
 //
@@ -37,7 +41,13 @@ use crate::states::{BaseState, CreateEventState};
 //
 // "#;
 
-pub async fn start_command(bot: Bot, msg: Message) -> HandlerResult {
+pub async fn start_command(
+    bot: Bot,
+    msg: Message,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    communities_repository: Arc<dyn CommunitiesRepository>,
+) -> HandlerResult {
     log_request("got start_command", &msg);
 
     if let Some(command_text) = msg.text() {
@@ -45,7 +55,43 @@ pub async fn start_command(bot: Bot, msg: Message) -> HandlerResult {
             if let Some(first_param) = params.first() {
                 if let Some(event_uuid) = first_param.strip_prefix("event_") {
                     if let Ok(event_uuid) = Uuid::from_str(event_uuid) {
-                        send_event_post(&bot, msg.chat.id, event_uuid).await?;
+                        send_event_post(
+                            &bot,
+                            events_repository.as_ref(),
+                            accounts_repository.as_ref(),
+                            msg.chat.id,
+                            event_uuid,
+                        )
+                        .await?;
+                        return Ok(());
+                    }
+                }
+                if let Some(invite_token) = first_param.strip_prefix("community_") {
+                    if let Some(user) = msg.from() {
+                        open_community_by_invite(
+                            &bot,
+                            events_repository.as_ref(),
+                            accounts_repository.as_ref(),
+                            communities_repository.as_ref(),
+                            msg.chat.id,
+                            user,
+                            invite_token,
+                        )
+                        .await?;
+                        return Ok(());
+                    }
+                }
+                if let Some(invite_token) = first_param.strip_prefix("invite_") {
+                    if let Some(user) = msg.from() {
+                        open_event_by_invite(
+                            &bot,
+                            events_repository.as_ref(),
+                            accounts_repository.as_ref(),
+                            msg.chat.id,
+                            user,
+                            invite_token,
+                        )
+                        .await?;
                         return Ok(());
                     }
                 }
@@ -59,11 +105,15 @@ pub async fn start_command(bot: Bot, msg: Message) -> HandlerResult {
 
     if let Some(user) = msg.from() {
         let new_user_account = fill_base_account_from_teloxide_user(user);
-        ACCOUNTS_REPOSITORY
-            .get()
-            .ok_or("Cannot get accounts repository")?
+        let account = accounts_repository
             .create_user_by_tg_user_id(new_user_account)
             .await?;
+
+        if account.user_data.city.is_none() {
+            bot.send_message(msg.chat.id, t!("actions.home_city.choose"))
+                .reply_markup(keyboards::get_inline_kb_choose_home_city())
+                .await?;
+        }
     }
 
     Ok(())
@@ -79,14 +129,21 @@ pub async fn about_command(bot: Bot, msg: Message) -> HandlerResult {
     Ok(())
 }
 
-pub async fn create_event_command(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
+pub async fn create_event_command(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    accounts_repository: Arc<dyn AccountsRepository>,
+) -> HandlerResult {
     log_request("got create_event command", &msg);
 
     let mut message = bot.send_message(msg.chat.id, t!("actions.create_event.new_event"));
     message.parse_mode = Some(ParseMode::MarkdownV2);
     message.await?;
 
-    let filling_event = FillingEvent::new();
+    let mut filling_event = FillingEvent::new();
+    filling_event.timezone = get_user_timezone(accounts_repository.as_ref(), msg.chat.id.0).await;
+    filling_event.city = get_user_city(accounts_repository.as_ref(), msg.chat.id.0).await;
 
     let mut message = bot.send_message(msg.chat.id, filling_event.get_missed_data_hint());
     message.parse_mode = Some(ParseMode::MarkdownV2);
@@ -100,13 +157,19 @@ pub async fn create_event_command(bot: Bot, dialogue: MyDialogue, msg: Message)
             state: CreateEventState::Idle,
             filling_event,
             last_edit_msg_id: sent_msg.id,
+            pending_drafts: Vec::new(),
         })
         .await?;
 
     Ok(())
 }
 
-pub async fn get_events_command(bot: Bot, dialogue: MyDialogue, msg: Message) -> HandlerResult {
+pub async fn get_events_command(
+    bot: Bot,
+    dialogue: MyDialogue,
+    msg: Message,
+    accounts_repository: Arc<dyn AccountsRepository>,
+) -> HandlerResult {
     log_request("got get_events command", &msg);
 
     const DEFAULT_PAGE_SIZE: i64 = 10;
@@ -114,19 +177,23 @@ pub async fn get_events_command(bot: Bot, dialogue: MyDialogue, msg: Message) ->
     let (page, page_size) = (0i64, DEFAULT_PAGE_SIZE);
 
     let events_filter = EventSubjectFilter::new();
+    let city_filter = get_user_city(accounts_repository.as_ref(), msg.chat.id.0).await;
+    let format_filter = EventFormatFilter::new();
 
     dialogue
         .update(BaseState::GetEventList {
             page_size,
             page_num: page,
             events_filter: events_filter.clone(),
+            city_filter,
+            format_filter: format_filter.clone(),
         })
         .await?;
 
     let mut message = bot.send_message(msg.chat.id, t!("choose_category_msg"));
     message.parse_mode = Some(ParseMode::MarkdownV2);
     message.reply_markup = Some(ReplyMarkup::InlineKeyboard(
-        get_inline_kb_set_subject_filter(&events_filter),
+        get_inline_kb_set_subject_filter(&events_filter, city_filter, &format_filter),
     ));
     message.await?;
 
@@ -158,11 +225,11 @@ pub async fn send_feedback_command(bot: Bot, dialogue: MyDialogue, msg: Message)
 pub async fn send_donation_command(bot: Bot, msg: Message) -> HandlerResult {
     log_request("got send_donation_command command", &msg);
 
-    let donation_url = env::var(DONATION_URL)?;
-    let donation_msg = t!(
-        "donation_msg",
-        donation_link = &donation_url,
-    );
+    let donation_url = config()
+        .donation_url
+        .as_ref()
+        .ok_or("Donation url is not configured")?;
+    let donation_msg = t!("donation_msg", donation_link = donation_url.as_str(),);
 
     let message = bot.send_message(msg.chat.id, donation_msg);
     message.await?;
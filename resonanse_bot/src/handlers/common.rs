@@ -48,11 +48,8 @@ pub async fn invalid_state(bot: Bot, msg: Message) -> HandlerResult {
         // msg
     );
 
-    bot.send_message(
-        msg.chat.id,
-        "Если ты застрял, можешь вернуться и почитать мини-гайд /start",
-    )
-    .await?;
+    bot.send_message(msg.chat.id, t!("actions.invalid_state"))
+        .await?;
 
     Ok(())
 }
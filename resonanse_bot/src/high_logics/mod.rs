@@ -1,23 +1,49 @@
-use std::env;
 use std::error::Error;
+use std::sync::Arc;
 
-use log::{debug};
+use chrono::Utc;
+use chrono_tz::Tz;
+use log::{debug, warn};
 
 use teloxide::prelude::*;
 use teloxide::types::ReplyMarkup;
+use teloxide::RequestError;
 use uuid::Uuid;
 
-use resonanse_common::models::BaseEvent;
+use resonanse_common::models::{BaseEvent, City, Community, DEFAULT_TIMEZONE};
+use resonanse_common::repository::{
+    AccountFollowsRepository, AccountsRepository, CommunitiesRepository, EventDraftsRepository,
+    EventsRepository, GroupFeedsRepository,
+};
 // use resonanse_common::repository::CreateBaseEvent;
 
-use crate::config::POSTS_CHANNEL_ID;
-use crate::data_structs::{prepare_event_msg_with_base_event, EventPostMessageRequest};
+use crate::config::{config, DEFAULT_DATETIME_FORMAT};
+use crate::data_structs::{
+    prepare_event_msg_with_base_event, EventPostMessageRequest, FillingEvent,
+};
 use crate::data_translators::fill_base_account_from_teloxide_user;
 use crate::errors::BotHandlerError;
-use crate::keyboards::get_inline_kb_event_message;
-use crate::{ACCOUNTS_REPOSITORY, EVENTS_REPOSITORY, MANAGER_BOT};
+use crate::keyboards::{
+    get_inline_btn_cancel_occurrence, get_inline_kb_community_page, get_inline_kb_event_message,
+};
+use crate::locales::{get_user_locale, with_locale, DEFAULT_LOCALE};
+use crate::metrics::EVENT_PUBLICATIONS_TOTAL;
+use crate::utils::{build_community_invite_link, build_event_deep_link};
+use crate::MANAGER_BOT;
+
+/// Repositories used to announce published event to followers and group feeds,
+/// handler gets them together since number of handler dependencies is limited
+#[derive(Clone)]
+pub struct AnnouncementRepositories {
+    pub account_follows_repository: Arc<dyn AccountFollowsRepository>,
+    pub group_feeds_repository: Arc<dyn GroupFeedsRepository>,
+}
 
 pub async fn publish_event<I>(
+    bot: &Bot,
+    events_repository: &dyn EventsRepository,
+    accounts_repository: &Arc<dyn AccountsRepository>,
+    announcement_repositories: &AnnouncementRepositories,
     new_event: I,
     creator_tg_user: &teloxide::types::User,
 ) -> Result<BaseEvent, Box<dyn Error + Send + Sync>>
@@ -26,9 +52,7 @@ where
 {
     // save to db
     let user_account = fill_base_account_from_teloxide_user(creator_tg_user);
-    let account = ACCOUNTS_REPOSITORY
-        .get()
-        .ok_or("Cannot get accounts repository")?
+    let account = accounts_repository
         .create_user_by_tg_user_id(user_account)
         .await?;
 
@@ -78,15 +102,19 @@ where
     // }
 
     create_base_event.creator_id = account.id;
-    let created_event = EVENTS_REPOSITORY
-        .get()
-        .ok_or("Cannot get events repository")?
+    let created_event = events_repository
         .create_event(create_base_event.clone())
         .await?;
+    let visibility = if created_event.is_private {
+        "private"
+    } else {
+        "public"
+    };
+    EVENT_PUBLICATIONS_TOTAL.with_label_values(&[visibility]).inc();
 
-    // post to tg
-    if let Ok(tg_channel_to_post) = env::var(POSTS_CHANNEL_ID) {
-        // if let Ok(tg_channel_to_post) = tg_channel_to_post.parse::<i64>() {
+    // post to tg, private events are shared only by invite link
+    if !created_event.is_private {
+        let tg_channel_to_post = ChatId(config().posts_channel_id);
         debug!(
             "posting event {:?} to channel {}",
             created_event.id, tg_channel_to_post
@@ -101,44 +129,231 @@ where
         //     Some(created_event.location.get_yandex_map_link_to())
         // )));
 
-        if let Ok(tg_channel_to_post) = tg_channel_to_post.parse::<i64>() {
+        // channel is read by everyone, so post is not in creator locale
+        with_locale(DEFAULT_LOCALE.to_string(), async {
             match prepare_event_msg_with_base_event(
                 manager_bot,
-                ChatId(tg_channel_to_post),
+                tg_channel_to_post,
                 created_event.clone(),
                 construct_created_event_kb(&created_event),
+                created_event.timezone,
             ) {
-                EventPostMessageRequest::WithPoster(f) => f.await?,
-                EventPostMessageRequest::Text(f) => f.await?,
-            };
-        }
+                EventPostMessageRequest::WithPoster(f) => f.await,
+                EventPostMessageRequest::Text(f) => f.await,
+            }
+        })
+        .await
+        .map_err(BotHandlerError::Telegram)?;
+    }
+
+    // followers and groups are notified in background, organizer should not wait for it
+    if !created_event.is_private {
+        let follower_bot = bot.clone();
+        let accounts_repository = accounts_repository.clone();
+        let account_follows_repository = announcement_repositories
+            .account_follows_repository
+            .clone();
+        let followed_event = created_event.clone();
+        tokio::spawn(async move {
+            notify_organizer_followers(
+                &follower_bot,
+                accounts_repository.as_ref(),
+                account_follows_repository.as_ref(),
+                &followed_event,
+            )
+            .await
+        });
+
+        let feed_bot = bot.clone();
+        let group_feeds_repository = announcement_repositories.group_feeds_repository.clone();
+        let posted_event = created_event.clone();
+        tokio::spawn(async move {
+            post_event_to_group_feeds(&feed_bot, group_feeds_repository.as_ref(), &posted_event)
+                .await
+        });
     }
 
     debug!("created event {:?}", created_event);
     Ok(created_event)
 }
 
+/// Sends new event of organizer to its followers.
+/// Failed notifications are only logged, follower may block the bot
+async fn notify_organizer_followers(
+    bot: &Bot,
+    accounts_repository: &dyn AccountsRepository,
+    account_follows_repository: &dyn AccountFollowsRepository,
+    base_event: &BaseEvent,
+) {
+    let follower_tg_ids = match account_follows_repository
+        .get_follower_tg_ids(base_event.creator_id)
+        .await
+    {
+        Ok(v) => v,
+        Err(err) => {
+            warn!(
+                "cannot get followers of organizer {}: {:?}",
+                base_event.creator_id, err
+            );
+            return;
+        }
+    };
+
+    for follower_tg_id in follower_tg_ids {
+        debug!(
+            "notifying follower {} about event {:?}",
+            follower_tg_id, base_event.id
+        );
+        let follower_locale = get_user_locale(accounts_repository, follower_tg_id, None).await;
+        let notify_result = with_locale(
+            follower_locale,
+            notify_follower(bot, accounts_repository, follower_tg_id, base_event),
+        )
+        .await;
+        if let Err(err) = notify_result {
+            warn!(
+                "cannot notify follower {} about event {}: {:?}",
+                follower_tg_id, base_event.id, err
+            );
+        }
+    }
+}
+
+async fn notify_follower(
+    bot: &Bot,
+    accounts_repository: &dyn AccountsRepository,
+    follower_tg_id: i64,
+    base_event: &BaseEvent,
+) -> Result<Message, RequestError> {
+    let follower_chat_id = ChatId(follower_tg_id);
+    bot.send_message(follower_chat_id, t!("actions.follow_organizer.new_event"))
+        .await?;
+
+    match prepare_event_msg_with_base_event(
+        bot,
+        follower_chat_id,
+        base_event.clone(),
+        construct_created_event_kb(base_event),
+        get_user_timezone(accounts_repository, follower_tg_id).await,
+    ) {
+        EventPostMessageRequest::WithPoster(f) => f.await,
+        EventPostMessageRequest::Text(f) => f.await,
+    }
+}
+
+/// Posts public event to groups which follow its subject and city.
+/// Failed posts are only logged, bot may be restricted in some groups
+async fn post_event_to_group_feeds(
+    bot: &Bot,
+    group_feeds_repository: &dyn GroupFeedsRepository,
+    base_event: &BaseEvent,
+) {
+    if base_event.is_private {
+        return;
+    }
+    let group_chat_ids = match group_feeds_repository
+        .get_event_group_chats(base_event.subject, base_event.city)
+        .await
+    {
+        Ok(v) => v,
+        Err(err) => {
+            warn!(
+                "cannot get group feeds of event {}: {:?}",
+                base_event.id, err
+            );
+            return;
+        }
+    };
+
+    for group_chat_id in group_chat_ids {
+        debug!(
+            "posting event {:?} to group {}",
+            base_event.id, group_chat_id
+        );
+        let post_result = with_locale(DEFAULT_LOCALE.to_string(), async {
+            match prepare_event_msg_with_base_event(
+                bot,
+                ChatId(group_chat_id),
+                base_event.clone(),
+                construct_created_event_kb(base_event),
+                base_event.timezone,
+            ) {
+                EventPostMessageRequest::WithPoster(f) => f.await,
+                EventPostMessageRequest::Text(f) => f.await,
+            }
+        })
+        .await;
+        if let Err(err) = post_result {
+            warn!(
+                "cannot post event {} to group {}: {:?}",
+                base_event.id, group_chat_id, err
+            );
+        }
+    }
+}
+
 pub async fn send_event_post(
     bot: &Bot,
+    events_repository: &dyn EventsRepository,
+    accounts_repository: &dyn AccountsRepository,
     chat_id: ChatId,
     event_uuid: Uuid,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let created_event = EVENTS_REPOSITORY
-        .get()
-        .ok_or("Cannot get events repository")?
+    let created_event = events_repository
         .get_event_by_uuid(event_uuid)
-        .await?;
+        .await
+        .map_err(BotHandlerError::lookup("event"))?;
+
+    if !can_view_event(
+        events_repository,
+        accounts_repository,
+        chat_id.0,
+        &created_event,
+    )
+    .await
+    {
+        bot.send_message(chat_id, t!("actions.private_event.no_access"))
+            .await?;
+        return Ok(());
+    }
 
+    // recurring event is shown as its nearest occurrence
+    let event_occurrence = created_event
+        .upcoming_occurrences(Utc::now(), 1)
+        .pop()
+        .unwrap_or(created_event);
+
+    send_base_event_post(bot, accounts_repository, chat_id, event_occurrence).await
+}
+
+/// Sends event as is, `base_event` may be single occurrence of recurring event
+pub async fn send_base_event_post(
+    bot: &Bot,
+    accounts_repository: &dyn AccountsRepository,
+    chat_id: ChatId,
+    base_event: BaseEvent,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
     // let event_inline_btns = match created_event.location {
     //     None => None,
     //     Some(location) => Some(),
     // };
-    let event_post_message_request = prepare_event_msg_with_base_event(
-        bot,
-        chat_id,
-        created_event.clone(),
-        construct_created_event_kb(&created_event),
-    );
+    let mut event_reply_markup = construct_created_event_kb(&base_event);
+    if base_event.recurrence.is_some()
+        && is_event_creator(accounts_repository, chat_id.0, &base_event).await
+    {
+        if let Some(ReplyMarkup::InlineKeyboard(inline_kb)) = event_reply_markup.as_mut() {
+            inline_kb
+                .inline_keyboard
+                .push(vec![get_inline_btn_cancel_occurrence(
+                    base_event.id,
+                    base_event.datetime_from,
+                )]);
+        }
+    }
+
+    let viewer_tz = get_user_timezone(accounts_repository, chat_id.0).await;
+    let event_post_message_request =
+        prepare_event_msg_with_base_event(bot, chat_id, base_event, event_reply_markup, viewer_tz);
     match event_post_message_request {
         EventPostMessageRequest::WithPoster(f) => f.await?,
         EventPostMessageRequest::Text(f) => f.await?,
@@ -147,9 +362,301 @@ pub async fn send_event_post(
     Ok(())
 }
 
+/// Adds user to allow-list of private event and shows the event
+pub async fn open_event_by_invite(
+    bot: &Bot,
+    events_repository: &dyn EventsRepository,
+    accounts_repository: &dyn AccountsRepository,
+    chat_id: ChatId,
+    tg_user: &teloxide::types::User,
+    invite_token: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let base_event = match events_repository
+        .get_event_by_invite_token(invite_token)
+        .await
+    {
+        Ok(v) => v,
+        Err(err) => {
+            debug!("cannot get event by invite token: {:?}", err);
+            bot.send_message(chat_id, t!("actions.private_event.invalid_invite"))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(tg_user))
+        .await?;
+    if account.id != base_event.creator_id {
+        events_repository
+            .grant_event_access(base_event.id, account.id)
+            .await?;
+    }
+
+    send_event_post(
+        bot,
+        events_repository,
+        accounts_repository,
+        chat_id,
+        base_event.id,
+    )
+    .await
+}
+
+/// Community description, its upcoming events and membership controls.
+/// Private community is shown only to its members
+pub async fn send_community_page(
+    bot: &Bot,
+    events_repository: &dyn EventsRepository,
+    accounts_repository: &dyn AccountsRepository,
+    communities_repository: &dyn CommunitiesRepository,
+    chat_id: ChatId,
+    tg_user: &teloxide::types::User,
+    community: &Community,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    const COMMUNITY_PAGE_EVENTS_LIMIT: usize = 5;
+
+    let account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(tg_user))
+        .await?;
+    let is_member = communities_repository
+        .is_member(community.id, account.id)
+        .await?;
+    let is_owner = community.owner_id == account.id;
+    if community.is_private && !is_member {
+        bot.send_message(chat_id, t!("actions.communities.no_access"))
+            .await?;
+        return Ok(());
+    }
+
+    let members_count = communities_repository.count_members(community.id).await?;
+    let community_events = events_repository
+        .get_community_events(community.id, COMMUNITY_PAGE_EVENTS_LIMIT)
+        .await?;
+    let viewer_tz = get_user_timezone(accounts_repository, tg_user.id.0 as i64).await;
+
+    let mut community_details = String::new();
+    if let Some(channel_link) = community.telegram_channel_link.as_deref() {
+        community_details.push_str(&t!(
+            "actions.communities.channel",
+            channel_link = channel_link
+        ));
+    }
+    if let (true, Some(invite_token)) = (is_owner, community.invite_token.as_deref()) {
+        community_details.push_str(&t!(
+            "actions.communities.invite_link",
+            invite_link = build_community_invite_link(invite_token)
+        ));
+    }
+    if !community_events.is_empty() {
+        let events_lines = community_events
+            .iter()
+            .map(|event| {
+                format!(
+                    "• {} — {}\n{}",
+                    event
+                        .datetime_from_instant()
+                        .with_timezone(&viewer_tz)
+                        .format(DEFAULT_DATETIME_FORMAT),
+                    event.title,
+                    build_event_deep_link(event.id),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        community_details.push_str(&t!("actions.communities.events", events = events_lines));
+    }
+
+    let mut message = bot.send_message(
+        chat_id,
+        t!(
+            "actions.communities.page",
+            name = community.name,
+            description = community.description,
+            category = community.category,
+            location = community.location,
+            members_count = members_count,
+            details = community_details,
+        ),
+    );
+    message.reply_markup = Some(ReplyMarkup::InlineKeyboard(get_inline_kb_community_page(
+        community, is_member, is_owner,
+    )));
+    message.disable_web_page_preview = Some(true);
+    message.await?;
+
+    Ok(())
+}
+
+/// Invite link makes user member of private community
+pub async fn open_community_by_invite(
+    bot: &Bot,
+    events_repository: &dyn EventsRepository,
+    accounts_repository: &dyn AccountsRepository,
+    communities_repository: &dyn CommunitiesRepository,
+    chat_id: ChatId,
+    tg_user: &teloxide::types::User,
+    invite_token: &str,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let community = match communities_repository
+        .get_community_by_invite_token(invite_token)
+        .await
+    {
+        Ok(v) => v,
+        Err(err) => {
+            debug!("cannot get community by invite token: {:?}", err);
+            bot.send_message(chat_id, t!("actions.communities.invalid_invite"))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    let account = accounts_repository
+        .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(tg_user))
+        .await?;
+    communities_repository
+        .add_member(community.id, account.id)
+        .await?;
+
+    send_community_page(
+        bot,
+        events_repository,
+        accounts_repository,
+        communities_repository,
+        chat_id,
+        tg_user,
+        &community,
+    )
+    .await
+}
+
+/// Public events are visible to everyone,
+/// private ones only to creator and accounts from allow-list
+pub async fn can_view_event(
+    events_repository: &dyn EventsRepository,
+    accounts_repository: &dyn AccountsRepository,
+    tg_user_id: i64,
+    base_event: &BaseEvent,
+) -> bool {
+    if !base_event.is_private {
+        return true;
+    }
+
+    let account_id = match accounts_repository
+        .get_account_id_by_tg_user_id(tg_user_id)
+        .await
+    {
+        Ok(v) => v,
+        Err(err) => {
+            debug!("cannot get account of tg user {}: {:?}", tg_user_id, err);
+            return false;
+        }
+    };
+
+    if account_id == base_event.creator_id {
+        return true;
+    }
+
+    events_repository
+        .is_event_access_granted(base_event.id, account_id)
+        .await
+        .unwrap_or_else(|err| {
+            warn!("cannot check access to event {}: {:?}", base_event.id, err);
+            false
+        })
+}
+
+pub async fn is_event_creator(
+    accounts_repository: &dyn AccountsRepository,
+    tg_user_id: i64,
+    base_event: &BaseEvent,
+) -> bool {
+    match accounts_repository
+        .get_account_id_by_tg_user_id(tg_user_id)
+        .await
+    {
+        Ok(account_id) => account_id == base_event.creator_id,
+        Err(err) => {
+            debug!("cannot get account of tg user {}: {:?}", tg_user_id, err);
+            false
+        }
+    }
+}
+
+/// Saves draft of user's account, failure is not critical for event filling
+pub async fn save_event_draft(
+    accounts_repository: &dyn AccountsRepository,
+    event_drafts_repository: &dyn EventDraftsRepository,
+    tg_user_id: i64,
+    filling_event: &FillingEvent,
+) {
+    let save_result = match accounts_repository
+        .get_account_id_by_tg_user_id(tg_user_id)
+        .await
+    {
+        Ok(account_id) => {
+            event_drafts_repository
+                .save_draft(filling_event.draft_id, account_id, filling_event)
+                .await
+        }
+        Err(err) => Err(err),
+    };
+    if let Err(err) = save_result {
+        warn!(
+            "cannot save event draft of tg user {}: {:?}",
+            tg_user_id, err
+        );
+    }
+}
+
+/// Returns timezone from user's account, or default one if account has no timezone
+pub async fn get_user_timezone(
+    accounts_repository: &dyn AccountsRepository,
+    tg_user_id: i64,
+) -> Tz {
+    match accounts_repository.get_user_by_tg_id(tg_user_id).await {
+        Ok(account) => account.user_data.timezone.unwrap_or(DEFAULT_TIMEZONE),
+        Err(err) => {
+            debug!("cannot get timezone of tg user {}: {:?}", tg_user_id, err);
+            DEFAULT_TIMEZONE
+        }
+    }
+}
+
+/// Remembers timezone explicitly chosen by user as default for next events
+pub async fn set_user_timezone(
+    accounts_repository: &dyn AccountsRepository,
+    tg_user_id: i64,
+    timezone: Tz,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let account_id = accounts_repository
+        .get_account_id_by_tg_user_id(tg_user_id)
+        .await?;
+    accounts_repository
+        .set_user_timezone(account_id, timezone)
+        .await?;
+
+    Ok(())
+}
+
+/// Returns home city from user's account, `None` if user has not chosen it
+pub async fn get_user_city(
+    accounts_repository: &dyn AccountsRepository,
+    tg_user_id: i64,
+) -> Option<City> {
+    match accounts_repository.get_user_by_tg_id(tg_user_id).await {
+        Ok(account) => account.user_data.city,
+        Err(err) => {
+            debug!("cannot get city of tg user {}: {:?}", tg_user_id, err);
+            None
+        }
+    }
+}
+
 pub fn construct_created_event_kb(created_event: &BaseEvent) -> Option<ReplyMarkup> {
     Some(ReplyMarkup::InlineKeyboard(get_inline_kb_event_message(
         created_event.id,
+        created_event.creator_id,
         created_event
             .location
             .as_ref()
@@ -176,3 +683,59 @@ impl SberSummarizatorInstance {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use resonanse_common::repository::in_memory::InMemoryAccountsRepository;
+    use teloxide::types::{User, UserId};
+
+    use super::*;
+
+    fn tg_user(tg_user_id: u64) -> User {
+        User {
+            id: UserId(tg_user_id),
+            is_bot: false,
+            first_name: "Ivan".to_string(),
+            last_name: None,
+            username: None,
+            language_code: Some("en-US".to_string()),
+            is_premium: false,
+            added_to_attachment_menu: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_user_settings_from_account() {
+        let accounts_repository = InMemoryAccountsRepository::new();
+        assert_eq!(
+            get_user_timezone(&accounts_repository, 42).await,
+            DEFAULT_TIMEZONE
+        );
+        assert_eq!(get_user_city(&accounts_repository, 42).await, None);
+
+        let account = accounts_repository
+            .create_user_by_tg_user_id(fill_base_account_from_teloxide_user(&tg_user(42)))
+            .await
+            .unwrap();
+        set_user_timezone(&accounts_repository, 42, Tz::Europe__Samara)
+            .await
+            .unwrap();
+        accounts_repository
+            .set_user_city(account.id, City::Kazan)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_user_timezone(&accounts_repository, 42).await,
+            Tz::Europe__Samara
+        );
+        assert_eq!(
+            get_user_city(&accounts_repository, 42).await,
+            Some(City::Kazan)
+        );
+        assert_eq!(get_user_locale(&accounts_repository, 42, None).await, "en");
+        assert!(set_user_timezone(&accounts_repository, 7, Tz::UTC)
+            .await
+            .is_err());
+    }
+}
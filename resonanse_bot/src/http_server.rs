@@ -0,0 +1,92 @@
+use std::net::SocketAddr;
+
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+use log::{error, info};
+use resonanse_common::metrics;
+
+use crate::supervisor::{Health, ShutdownSignal};
+use crate::webhook::Webhooks;
+
+/// Serves health of supervised tasks, metrics and webhooks if they are set
+#[derive(Clone)]
+pub struct HttpServer {
+    listen_addr: SocketAddr,
+    health: Health,
+    webhooks: Option<Webhooks>,
+}
+
+impl HttpServer {
+    pub fn new(listen_addr: SocketAddr, health: Health, webhooks: Option<Webhooks>) -> Self {
+        Self {
+            listen_addr,
+            health,
+            webhooks,
+        }
+    }
+
+    fn router(&self) -> Router {
+        let router = Router::new()
+            .route(
+                "/health",
+                get(report_health).with_state(self.health.clone()),
+            )
+            .route("/metrics", get(report_metrics));
+        match &self.webhooks {
+            Some(webhooks) => router.merge(webhooks.routes()),
+            None => router,
+        }
+    }
+
+    /// Serves until shutdown, webhooks are deleted after it
+    pub async fn serve(self, mut shutdown: ShutdownSignal) {
+        let server = match axum::Server::try_bind(&self.listen_addr) {
+            Ok(server) => server,
+            Err(err) => {
+                error!("cannot bind http server to {}: {}", self.listen_addr, err);
+                return;
+            }
+        };
+        info!("Http server listens on {}", self.listen_addr);
+
+        let mut server_shutdown = shutdown.clone();
+        let result = server
+            .serve(self.router().into_make_service())
+            .with_graceful_shutdown(async move { server_shutdown.wait().await })
+            .await;
+        if let Err(err) = result {
+            error!("http server error: {:?}", err);
+            return;
+        }
+
+        shutdown.wait().await;
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.delete().await;
+        }
+        info!("Http server is stopped");
+    }
+}
+
+/// 503 while any supervised task is not running
+async fn report_health(State(health): State<Health>) -> (StatusCode, Json<serde_json::Value>) {
+    let status = match health.is_healthy() {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    };
+    let tasks = health
+        .tasks()
+        .into_iter()
+        .map(|(task, state)| (task.to_string(), state.to_string().into()))
+        .collect::<serde_json::Map<_, _>>();
+    (status, Json(tasks.into()))
+}
+
+async fn report_metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, metrics::METRICS_CONTENT_TYPE)],
+        metrics::render(),
+    )
+}
@@ -1,12 +1,17 @@
-use std::env;
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime};
 use teloxide::types::{
     InlineKeyboardButton, InlineKeyboardButtonKind, InlineKeyboardMarkup, WebAppInfo,
 };
 use uuid::Uuid;
 
-use crate::config::WEB_APP_URL;
-use resonanse_common::models::{EventSubject, ResonanseEventKind};
-use resonanse_common::EventSubjectFilter;
+use crate::config::{config, DEFAULT_DATE_FORMAT};
+use crate::data_structs::{FillingEvent, PROFILE_FIELDS};
+use crate::locales::SUPPORTED_LOCALES;
+use resonanse_common::models::{
+    BaseAccount, City, Community, EventDraft, EventFormat, EventSubject, GroupFeed,
+    RecurrenceFrequency, ResonanseEventKind, CITIES,
+};
+use resonanse_common::{EventFormatFilter, EventSubjectFilter};
 
 macro_rules! kb_button_from_enum {
     ($s:expr) => {
@@ -53,6 +58,26 @@ pub fn get_inline_kb_choose_subject() -> InlineKeyboardMarkup {
     // ReplyMarkup::InlineKeyboard(keyboard)
 }
 
+pub const RECURRENCE_NONE_BTN: &str = "keyboards.recurrence_none";
+pub const RECURRENCE_NO_END_BTN: &str = "keyboards.recurrence_no_end";
+
+pub fn get_inline_kb_choose_recurrence() -> InlineKeyboardMarkup {
+    let buttons = [
+        vec![
+            kb_button_from_enum!(RecurrenceFrequency::Daily),
+            kb_button_from_enum!(RecurrenceFrequency::Weekly),
+            kb_button_from_enum!(RecurrenceFrequency::Monthly),
+        ],
+        vec![kb_button_from_str!(RECURRENCE_NONE_BTN)],
+    ];
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+pub fn get_inline_kb_recurrence_end() -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new([[kb_button_from_str!(RECURRENCE_NO_END_BTN)]])
+}
+
 pub fn get_inline_kb_choose_event_kind() -> InlineKeyboardMarkup {
     let buttons = [vec![
         kb_button_from_enum!(ResonanseEventKind::Announcement),
@@ -62,6 +87,68 @@ pub fn get_inline_kb_choose_event_kind() -> InlineKeyboardMarkup {
     InlineKeyboardMarkup::new(buttons)
 }
 
+pub fn get_inline_kb_choose_event_format() -> InlineKeyboardMarkup {
+    let buttons = [vec![
+        kb_button_from_enum!(EventFormat::Offline),
+        kb_button_from_enum!(EventFormat::Online),
+        kb_button_from_enum!(EventFormat::Hybrid),
+    ]];
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+const CITIES_ROW_LEN: usize = 3;
+
+pub fn get_inline_kb_choose_city() -> InlineKeyboardMarkup {
+    let buttons = CITIES
+        .chunks(CITIES_ROW_LEN)
+        .map(|cities_row| {
+            cities_row
+                .iter()
+                .map(|city| kb_button_from_enum!(city))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+pub const HOME_CITY_BTN_PREFIX: &str = "HOME_CITY:";
+
+pub fn get_inline_kb_choose_home_city() -> InlineKeyboardMarkup {
+    let buttons = CITIES
+        .chunks(CITIES_ROW_LEN)
+        .map(|cities_row| {
+            cities_row
+                .iter()
+                .map(|city| {
+                    InlineKeyboardButton::new(
+                        t!(&city.to_string()),
+                        InlineKeyboardButtonKind::CallbackData(format!(
+                            "{}{}",
+                            HOME_CITY_BTN_PREFIX, city
+                        )),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+pub const PRICE_FREE_BTN: &str = "keyboards.price_free";
+
+pub fn get_inline_kb_choose_price() -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new([[kb_button_from_str!(PRICE_FREE_BTN)]])
+}
+
+pub const CAPACITY_UNLIMITED_BTN: &str = "keyboards.capacity_unlimited";
+
+pub fn get_inline_kb_choose_capacity() -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new([[kb_button_from_str!(CAPACITY_UNLIMITED_BTN)]])
+}
+
 // pub fn get_inline_kb_view_event(map_link: String) -> ReplyMarkup {
 //     let map_link_btn = InlineKeyboardButton::new(
 //         "Место на карте",
@@ -128,14 +215,38 @@ pub fn get_inline_kb_choose_event_kind() -> InlineKeyboardMarkup {
 //     InlineKeyboardMarkup::new(buttons)
 // }
 
-#[allow(unused)]
 pub const INLINE_WANT_TO_GO_BTN: &str = "keyboards.want_go_to_event_btn";
+pub const RSVP_EVENT_BTN_PREFIX: &str = "RSVP:";
+pub const INLINE_FOLLOW_ORGANIZER_BTN: &str = "keyboards.follow_organizer_btn";
+pub const FOLLOW_ORGANIZER_BTN_PREFIX: &str = "FOLLOW_ORGANIZER:";
 pub const INLINE_MAP_BTN: &str = "keyboards.event_map_btn";
 pub const INLINE_LIKE_EVENT_BTN: &str = "keyboards.like_event_btn";
 pub const INLINE_DISLIKE_EVENT_BTN: &str = "keyboards.dislike_event_btn";
+pub const INLINE_CANCEL_OCCURRENCE_BTN: &str = "keyboards.cancel_occurrence_btn";
+// callback data is limited by 64 bytes, so prefix is short and uuid is in simple format
+pub const CANCEL_OCCURRENCE_BTN_PREFIX: &str = "CANCEL_OCCURRENCE:";
+// seconds do not fit as well, occurrence is matched by minute of its start
+pub const CANCEL_OCCURRENCE_DATETIME_FORMAT: &str = "%Y%m%d%H%M";
+
+/// Button for organizer of recurring event to cancel single occurrence
+pub fn get_inline_btn_cancel_occurrence(
+    event_id: Uuid,
+    occurrence_datetime: NaiveDateTime,
+) -> InlineKeyboardButton {
+    InlineKeyboardButton::new(
+        t!(INLINE_CANCEL_OCCURRENCE_BTN),
+        InlineKeyboardButtonKind::CallbackData(format!(
+            "{}{}:{}",
+            CANCEL_OCCURRENCE_BTN_PREFIX,
+            event_id.simple(),
+            occurrence_datetime.format(CANCEL_OCCURRENCE_DATETIME_FORMAT)
+        )),
+    )
+}
 
 pub fn get_inline_kb_event_message(
     event_id: Uuid,
+    creator_id: i64,
     map_link: Option<String>,
     // want_go_url: String,
 ) -> InlineKeyboardMarkup {
@@ -169,6 +280,68 @@ pub fn get_inline_kb_event_message(
     //     ));
     buttons.push(buttons_first_row);
 
+    // same button registers and cancels participation, post is shared by all users
+    buttons.push(vec![InlineKeyboardButton::new(
+        t!(INLINE_WANT_TO_GO_BTN),
+        InlineKeyboardButtonKind::CallbackData(format!(
+            "{}{}",
+            RSVP_EVENT_BTN_PREFIX,
+            event_id.simple()
+        )),
+    )]);
+
+    // same as rsvp, one button follows and unfollows organizer
+    buttons.push(vec![InlineKeyboardButton::new(
+        t!(INLINE_FOLLOW_ORGANIZER_BTN),
+        InlineKeyboardButtonKind::CallbackData(format!(
+            "{}{}",
+            FOLLOW_ORGANIZER_BTN_PREFIX, creator_id
+        )),
+    )]);
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+pub const INLINE_OPEN_EVENT_IN_BOT_BTN: &str = "keyboards.open_event_in_bot_btn";
+
+/// Event keyboard for posts shared via inline mode, with deep link to event in bot
+pub fn get_inline_kb_shared_event_message(
+    event_id: Uuid,
+    creator_id: i64,
+    map_link: Option<String>,
+    deep_link: String,
+) -> InlineKeyboardMarkup {
+    get_inline_kb_event_message(event_id, creator_id, map_link).append_row(vec![
+        InlineKeyboardButton::url(t!(INLINE_OPEN_EVENT_IN_BOT_BTN), deep_link.parse().unwrap()),
+    ])
+}
+
+pub const RESUME_DRAFT_BTN_PREFIX: &str = "RESUME_DRAFT:";
+
+/// One button per draft: title and date of last change
+pub fn get_inline_kb_event_drafts(drafts: &[EventDraft<FillingEvent>]) -> InlineKeyboardMarkup {
+    let buttons = drafts
+        .iter()
+        .map(|draft| {
+            vec![InlineKeyboardButton::new(
+                format!(
+                    "{} · {}",
+                    draft
+                        .draft
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| t!("actions.event_drafts.untitled")),
+                    draft.update_time.format(DEFAULT_DATE_FORMAT)
+                ),
+                InlineKeyboardButtonKind::CallbackData(format!(
+                    "{}{}",
+                    RESUME_DRAFT_BTN_PREFIX,
+                    draft.id.simple()
+                )),
+            )]
+        })
+        .collect::<Vec<_>>();
+
     InlineKeyboardMarkup::new(buttons)
 }
 
@@ -192,7 +365,7 @@ pub fn get_inline_kb_events_page() -> InlineKeyboardMarkup {
 }
 
 pub fn get_inline_kb_run_web_app() -> InlineKeyboardMarkup {
-    let web_app_url = url::Url::parse(env::var(WEB_APP_URL).unwrap().as_str()).unwrap();
+    let web_app_url = config().web_app_url.clone();
 
     let web_app_btn = InlineKeyboardButton::new(
         t!("web_app.run_app"),
@@ -205,9 +378,14 @@ pub fn get_inline_kb_run_web_app() -> InlineKeyboardMarkup {
 }
 
 pub const APPLY_EVENT_FILTER_BTN: &str = "APPLY_EVENT_FILTER_BTN";
+pub const APPLY_EVENT_FILTER_LABEL: &str = "keyboards.apply_filter_btn";
+pub const ONLY_FREE_EVENT_FILTER_BTN: &str = "keyboards.only_free_filter";
 
+/// Only one city can be chosen, choosing it again shows events of all cities
 pub fn get_inline_kb_set_subject_filter(
     event_filters: &EventSubjectFilter,
+    city_filter: Option<City>,
+    format_filter: &EventFormatFilter,
 ) -> InlineKeyboardMarkup {
     const FILTER_ON: &str = "✅";
     const FILTER_OFF: &str = "❌";
@@ -239,8 +417,66 @@ pub fn get_inline_kb_set_subject_filter(
         .map(|c| c.to_vec())
         .collect::<Vec<_>>();
 
+    let city_buttons = CITIES
+        .chunks(CITIES_ROW_LEN)
+        .map(|cities_row| {
+            cities_row
+                .iter()
+                .map(|city| {
+                    InlineKeyboardButton::new(
+                        format!(
+                            "{}[{}]",
+                            t!(&city.to_string()),
+                            if city_filter == Some(*city) {
+                                FILTER_ON
+                            } else {
+                                FILTER_OFF
+                            },
+                        ),
+                        InlineKeyboardButtonKind::CallbackData(city.to_string()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    buttons.extend(city_buttons);
+
+    let format_buttons = [
+        EventFormat::Offline,
+        EventFormat::Online,
+        EventFormat::Hybrid,
+    ]
+    .into_iter()
+    .map(|ef| {
+        let on = format_filter.formats.get(&ef).copied().unwrap_or(false);
+        InlineKeyboardButton::new(
+            format!(
+                "{}[{}]",
+                t!(&ef.to_string()),
+                if on { FILTER_ON } else { FILTER_OFF },
+            ),
+            InlineKeyboardButtonKind::CallbackData(ef.to_string()),
+        )
+    })
+    .collect::<Vec<_>>();
+    buttons.push(format_buttons);
+
+    let only_free_button = InlineKeyboardButton::new(
+        format!(
+            "{}[{}]",
+            t!(ONLY_FREE_EVENT_FILTER_BTN),
+            if format_filter.only_free {
+                FILTER_ON
+            } else {
+                FILTER_OFF
+            },
+        ),
+        InlineKeyboardButtonKind::CallbackData(ONLY_FREE_EVENT_FILTER_BTN.to_string()),
+    );
+    buttons.push(vec![only_free_button]);
+
     let apply_button = InlineKeyboardButton::new(
-        "Показать",
+        t!(APPLY_EVENT_FILTER_LABEL),
         InlineKeyboardButtonKind::CallbackData(APPLY_EVENT_FILTER_BTN.to_string()),
     );
     buttons.push(vec![apply_button]);
@@ -248,16 +484,272 @@ pub fn get_inline_kb_set_subject_filter(
     InlineKeyboardMarkup::new(buttons)
 }
 
+pub const GROUP_FEED_SUBJECT_BTN_PREFIX: &str = "GROUP_FEED_SUBJECT:";
+pub const GROUP_FEED_CITY_BTN_PREFIX: &str = "GROUP_FEED_CITY:";
+
+/// Subjects and then cities followed by group, switched by admins
+pub fn get_inline_kb_group_feed(group_feed: &GroupFeed) -> InlineKeyboardMarkup {
+    const FILTER_ON: &str = "✅";
+    const FILTER_OFF: &str = "❌";
+    const ROW_LEN: usize = 2;
+
+    let mut subjects = EventSubjectFilter::new().0.into_keys().collect::<Vec<_>>();
+    subjects.sort_by_key(|es| *es as i32);
+
+    let subject_buttons = subjects.into_iter().map(|es| {
+        InlineKeyboardButton::new(
+            format!(
+                "{}[{}]",
+                t!(&es.to_string()),
+                if group_feed.is_following(es) {
+                    FILTER_ON
+                } else {
+                    FILTER_OFF
+                },
+            ),
+            InlineKeyboardButtonKind::CallbackData(format!(
+                "{}{}",
+                GROUP_FEED_SUBJECT_BTN_PREFIX, es
+            )),
+        )
+    });
+    let city_buttons = CITIES.iter().map(|city| {
+        InlineKeyboardButton::new(
+            format!(
+                "{}[{}]",
+                t!(&city.to_string()),
+                if group_feed.is_following_city(*city) {
+                    FILTER_ON
+                } else {
+                    FILTER_OFF
+                },
+            ),
+            InlineKeyboardButtonKind::CallbackData(format!(
+                "{}{}",
+                GROUP_FEED_CITY_BTN_PREFIX, city
+            )),
+        )
+    });
+
+    let buttons = subject_buttons
+        .collect::<Vec<_>>()
+        .chunks(ROW_LEN)
+        .map(|c| c.to_vec())
+        .chain(
+            city_buttons
+                .collect::<Vec<_>>()
+                .chunks(CITIES_ROW_LEN)
+                .map(|c| c.to_vec()),
+        )
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+pub const COMMUNITY_PAGE_BTN_PREFIX: &str = "COMMUNITY:";
+pub const COMMUNITY_MEMBERSHIP_BTN_PREFIX: &str = "COMMUNITY_MEMBERSHIP:";
+pub const COMMUNITY_MEMBERS_BTN_PREFIX: &str = "COMMUNITY_MEMBERS:";
+pub const REMOVE_COMMUNITY_MEMBER_BTN_PREFIX: &str = "REMOVE_COMMUNITY_MEMBER:";
+pub const JOIN_COMMUNITY_BTN: &str = "keyboards.join_community_btn";
+pub const LEAVE_COMMUNITY_BTN: &str = "keyboards.leave_community_btn";
+pub const COMMUNITY_MEMBERS_BTN: &str = "keyboards.community_members_btn";
+
+/// One button per community, opens community page
+pub fn get_inline_kb_communities(communities: &[Community]) -> InlineKeyboardMarkup {
+    let buttons = communities
+        .iter()
+        .map(|community| {
+            vec![InlineKeyboardButton::new(
+                match community.is_private {
+                    true => format!("🔒 {}", community.name),
+                    false => community.name.clone(),
+                },
+                InlineKeyboardButtonKind::CallbackData(format!(
+                    "{}{}",
+                    COMMUNITY_PAGE_BTN_PREFIX, community.id
+                )),
+            )]
+        })
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+/// Owner cannot leave own community, private community is joined only by invite link
+pub fn get_inline_kb_community_page(
+    community: &Community,
+    is_member: bool,
+    is_owner: bool,
+) -> InlineKeyboardMarkup {
+    let mut buttons = vec![];
+
+    let membership_btn_text = match (is_owner, is_member, community.is_private) {
+        (true, _, _) => None,
+        (false, true, _) => Some(t!(LEAVE_COMMUNITY_BTN)),
+        (false, false, false) => Some(t!(JOIN_COMMUNITY_BTN)),
+        (false, false, true) => None,
+    };
+    if let Some(membership_btn_text) = membership_btn_text {
+        buttons.push(vec![InlineKeyboardButton::new(
+            membership_btn_text,
+            InlineKeyboardButtonKind::CallbackData(format!(
+                "{}{}",
+                COMMUNITY_MEMBERSHIP_BTN_PREFIX, community.id
+            )),
+        )]);
+    }
+    if is_owner {
+        buttons.push(vec![InlineKeyboardButton::new(
+            t!(COMMUNITY_MEMBERS_BTN),
+            InlineKeyboardButtonKind::CallbackData(format!(
+                "{}{}",
+                COMMUNITY_MEMBERS_BTN_PREFIX, community.id
+            )),
+        )]);
+    }
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+/// Button removes member from community, owner is not listed
+/// Telegram username if present, full name otherwise
+fn format_account_name(account: &BaseAccount) -> String {
+    match account.contact_data.telegram.username.as_deref() {
+        Some(tg_username) => format!("@{}", tg_username),
+        None => format!(
+            "{} {}",
+            account.user_data.first_name, account.user_data.last_name
+        )
+        .trim()
+        .to_string(),
+    }
+}
+
+pub fn get_inline_kb_community_members(
+    community: &Community,
+    members: &[BaseAccount],
+) -> InlineKeyboardMarkup {
+    let buttons = members
+        .iter()
+        .filter(|member| member.id != community.owner_id)
+        .map(|member| {
+            vec![InlineKeyboardButton::new(
+                format!("❌ {}", format_account_name(member)),
+                InlineKeyboardButtonKind::CallbackData(format!(
+                    "{}{}:{}",
+                    REMOVE_COMMUNITY_MEMBER_BTN_PREFIX, community.id, member.id
+                )),
+            )]
+        })
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+pub const UNFOLLOW_ORGANIZER_BTN_PREFIX: &str = "UNFOLLOW_ORGANIZER:";
+
+pub fn get_inline_kb_followed_organizers(organizers: &[BaseAccount]) -> InlineKeyboardMarkup {
+    let buttons = organizers
+        .iter()
+        .map(|organizer| {
+            vec![InlineKeyboardButton::new(
+                format!("❌ {}", format_account_name(organizer)),
+                InlineKeyboardButtonKind::CallbackData(format!(
+                    "{}{}",
+                    UNFOLLOW_ORGANIZER_BTN_PREFIX, organizer.id
+                )),
+            )]
+        })
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+pub const PROFILE_FIELD_BTN_PREFIX: &str = "PROFILE_FIELD:";
+
+pub fn get_inline_kb_edit_profile() -> InlineKeyboardMarkup {
+    let buttons = PROFILE_FIELDS
+        .chunks(3)
+        .map(|fields_row| {
+            fields_row
+                .iter()
+                .map(|field| {
+                    InlineKeyboardButton::new(
+                        t!(field.translation_key()),
+                        InlineKeyboardButtonKind::CallbackData(format!(
+                            "{}{}",
+                            PROFILE_FIELD_BTN_PREFIX,
+                            field.as_str()
+                        )),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+pub const CHOOSE_LANGUAGE_BTN_PREFIX: &str = "LANGUAGE:";
+pub const LANGUAGE_NAME: &str = "language_name";
+
+/// Each language is named in itself, so user can find own one
+pub fn get_inline_kb_choose_language() -> InlineKeyboardMarkup {
+    let buttons = SUPPORTED_LOCALES
+        .iter()
+        .map(|locale| {
+            InlineKeyboardButton::new(
+                t!(LANGUAGE_NAME, locale = locale),
+                InlineKeyboardButtonKind::CallbackData(format!(
+                    "{}{}",
+                    CHOOSE_LANGUAGE_BTN_PREFIX, locale
+                )),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    InlineKeyboardMarkup::new([buttons])
+}
+
+pub const EVENT_COMMUNITY_BTN_PREFIX: &str = "EVENT_COMMUNITY:";
+pub const EVENT_COMMUNITY_NONE_BTN: &str = "keyboards.event_community_none";
+
+pub fn get_inline_kb_choose_community(communities: &[Community]) -> InlineKeyboardMarkup {
+    let mut buttons = communities
+        .iter()
+        .map(|community| {
+            vec![InlineKeyboardButton::new(
+                community.name.clone(),
+                InlineKeyboardButtonKind::CallbackData(format!(
+                    "{}{}",
+                    EVENT_COMMUNITY_BTN_PREFIX, community.id
+                )),
+            )]
+        })
+        .collect::<Vec<_>>();
+    buttons.push(vec![kb_button_from_str!(EVENT_COMMUNITY_NONE_BTN)]);
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
 pub const FILL_EVENT_TITLE_BTN_ID: &str = "keyboards.fill_event.title_btn";
 pub const FILL_EVENT_SUBJECT_BTN_ID: &str = "keyboards.fill_event.subject_btn";
 pub const FILL_EVENT_DESCRIPTION_BTN_ID: &str = "keyboards.fill_event.description_btn";
 // const FILL_EVENT_DATETIME_BTN_ID: &str = "fill_event.datetime";
 pub const FILL_EVENT_DATETIME_FROM_BTN_ID: &str = "keyboards.fill_event.datetime_from";
 pub const FILL_EVENT_DATETIME_TO_BTN_ID: &str = "keyboards.fill_event.datetime_to";
+pub const FILL_EVENT_RECURRENCE_BTN_ID: &str = "keyboards.fill_event.recurrence";
 
 // const FILL_EVENT_LOCATION_BTN_ID: &str = "fill_event.location";
+pub const FILL_EVENT_CITY_BTN_ID: &str = "keyboards.fill_event.city";
 pub const FILL_EVENT_LOCATION_GEO_BTN_ID: &str = "keyboards.fill_event.location_geo";
 pub const FILL_EVENT_LOCATION_TITLE_BTN_ID: &str = "keyboards.fill_event.location_title";
+pub const FILL_EVENT_MEETING_URL_BTN_ID: &str = "keyboards.fill_event.meeting_url";
+pub const FILL_EVENT_FORMAT_BTN_ID: &str = "keyboards.fill_event.format";
+pub const FILL_EVENT_PRICE_BTN_ID: &str = "keyboards.fill_event.price";
+pub const FILL_EVENT_CAPACITY_BTN_ID: &str = "keyboards.fill_event.capacity";
+pub const FILL_EVENT_PRIVACY_BTN_ID: &str = "keyboards.fill_event.privacy";
+pub const FILL_EVENT_COMMUNITY_BTN_ID: &str = "keyboards.fill_event.community";
 
 pub const FILL_EVENT_PICTURE_BTN_ID: &str = "keyboards.fill_event.picture";
 pub const FILL_EVENT_CONTACT_BTN_ID: &str = "keyboards.fill_event.contact_data";
@@ -280,18 +772,160 @@ pub fn get_make_event_keyboard() -> InlineKeyboardMarkup {
         vec![
             kb_button_from_str!(FILL_EVENT_DATETIME_FROM_BTN_ID),
             kb_button_from_str!(FILL_EVENT_DATETIME_TO_BTN_ID),
+            kb_button_from_str!(FILL_EVENT_RECURRENCE_BTN_ID),
         ],
         vec![
+            kb_button_from_str!(FILL_EVENT_CITY_BTN_ID),
             kb_button_from_str!(FILL_EVENT_LOCATION_TITLE_BTN_ID),
             kb_button_from_str!(FILL_EVENT_LOCATION_GEO_BTN_ID),
+            kb_button_from_str!(FILL_EVENT_MEETING_URL_BTN_ID),
         ],
         vec![
             kb_button_from_str!(FILL_EVENT_PICTURE_BTN_ID),
             kb_button_from_str!(FILL_EVENT_CONTACT_BTN_ID),
             kb_button_from_str!(FILL_EVENT_KIND_BTN_ID),
         ],
+        vec![
+            kb_button_from_str!(FILL_EVENT_FORMAT_BTN_ID),
+            kb_button_from_str!(FILL_EVENT_PRICE_BTN_ID),
+            kb_button_from_str!(FILL_EVENT_CAPACITY_BTN_ID),
+            kb_button_from_str!(FILL_EVENT_PRIVACY_BTN_ID),
+        ],
+        vec![kb_button_from_str!(FILL_EVENT_COMMUNITY_BTN_ID)],
         vec![kb_button_from_str!(FILL_EVENT_FINALIZE_BTN_ID)],
     ];
 
     InlineKeyboardMarkup::new(buttons)
 }
+
+pub const CALENDAR_BTN_PREFIX: &str = "CALENDAR_";
+pub const CALENDAR_IGNORE_BTN: &str = "CALENDAR_IGNORE";
+pub const CALENDAR_MONTH_BTN_PREFIX: &str = "CALENDAR_MONTH:";
+pub const CALENDAR_DAY_BTN_PREFIX: &str = "CALENDAR_DAY:";
+pub const CALENDAR_TIME_BTN_PREFIX: &str = "CALENDAR_TIME:";
+pub const CALENDAR_DATE_FORMAT: &str = "%Y-%m-%d";
+pub const CALENDAR_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+const CALENDAR_FIRST_SLOT_HOUR: u32 = 8;
+const CALENDAR_SLOT_MINUTES: u32 = 30;
+const CALENDAR_SLOTS_ROW_LEN: usize = 4;
+
+fn calendar_ignore_button(text: impl Into<String>) -> InlineKeyboardButton {
+    InlineKeyboardButton::new(
+        text,
+        InlineKeyboardButtonKind::CallbackData(CALENDAR_IGNORE_BTN.to_string()),
+    )
+}
+
+fn calendar_month_button(text: impl Into<String>, month: NaiveDate) -> InlineKeyboardButton {
+    InlineKeyboardButton::new(
+        text,
+        InlineKeyboardButtonKind::CallbackData(format!(
+            "{}{}",
+            CALENDAR_MONTH_BTN_PREFIX,
+            month.format(CALENDAR_DATE_FORMAT)
+        )),
+    )
+}
+
+fn calendar_month_name(month: u32) -> String {
+    match month {
+        1 => t!("keyboards.calendar.month_1"),
+        2 => t!("keyboards.calendar.month_2"),
+        3 => t!("keyboards.calendar.month_3"),
+        4 => t!("keyboards.calendar.month_4"),
+        5 => t!("keyboards.calendar.month_5"),
+        6 => t!("keyboards.calendar.month_6"),
+        7 => t!("keyboards.calendar.month_7"),
+        8 => t!("keyboards.calendar.month_8"),
+        9 => t!("keyboards.calendar.month_9"),
+        10 => t!("keyboards.calendar.month_10"),
+        11 => t!("keyboards.calendar.month_11"),
+        _ => t!("keyboards.calendar.month_12"),
+    }
+}
+
+/// Month calendar, days before `today` are not clickable
+pub fn get_inline_kb_calendar(month: NaiveDate, today: NaiveDate) -> InlineKeyboardMarkup {
+    let month_start = month.with_day(1).unwrap_or(month);
+    let prev_month = month_start - Months::new(1);
+    let next_month = month_start + Months::new(1);
+
+    let mut buttons = vec![
+        vec![
+            calendar_month_button("«", prev_month),
+            calendar_ignore_button(format!(
+                "{} {}",
+                calendar_month_name(month_start.month()),
+                month_start.year()
+            )),
+            calendar_month_button("»", next_month),
+        ],
+        vec![
+            calendar_ignore_button(t!("keyboards.calendar.mon")),
+            calendar_ignore_button(t!("keyboards.calendar.tue")),
+            calendar_ignore_button(t!("keyboards.calendar.wed")),
+            calendar_ignore_button(t!("keyboards.calendar.thu")),
+            calendar_ignore_button(t!("keyboards.calendar.fri")),
+            calendar_ignore_button(t!("keyboards.calendar.sat")),
+            calendar_ignore_button(t!("keyboards.calendar.sun")),
+        ],
+    ];
+
+    let mut week_row =
+        vec![calendar_ignore_button(" "); month_start.weekday().num_days_from_monday() as usize];
+    for day in month_start
+        .iter_days()
+        .take_while(|day| day.month() == month_start.month())
+    {
+        let day_btn = if day < today {
+            calendar_ignore_button("·")
+        } else {
+            InlineKeyboardButton::new(
+                day.day().to_string(),
+                InlineKeyboardButtonKind::CallbackData(format!(
+                    "{}{}",
+                    CALENDAR_DAY_BTN_PREFIX,
+                    day.format(CALENDAR_DATE_FORMAT)
+                )),
+            )
+        };
+        week_row.push(day_btn);
+
+        if week_row.len() == 7 {
+            buttons.push(std::mem::take(&mut week_row));
+        }
+    }
+    if !week_row.is_empty() {
+        week_row.resize(7, calendar_ignore_button(" "));
+        buttons.push(week_row);
+    }
+
+    InlineKeyboardMarkup::new(buttons)
+}
+
+/// Time slots of chosen day with button to return to calendar
+pub fn get_inline_kb_time_slots(day: NaiveDate) -> InlineKeyboardMarkup {
+    let mut buttons = vec![vec![calendar_month_button(
+        format!("« {}", day.format("%d.%m.%Y")),
+        day,
+    )]];
+
+    let slots = (CALENDAR_FIRST_SLOT_HOUR * 60..24 * 60)
+        .step_by(CALENDAR_SLOT_MINUTES as usize)
+        .filter_map(|minutes| day.and_hms_opt(minutes / 60, minutes % 60, 0))
+        .map(|slot| {
+            InlineKeyboardButton::new(
+                slot.format("%H:%M").to_string(),
+                InlineKeyboardButtonKind::CallbackData(format!(
+                    "{}{}",
+                    CALENDAR_TIME_BTN_PREFIX,
+                    slot.format(CALENDAR_DATETIME_FORMAT)
+                )),
+            )
+        })
+        .collect::<Vec<_>>();
+    buttons.extend(slots.chunks(CALENDAR_SLOTS_ROW_LEN).map(|c| c.to_vec()));
+
+    InlineKeyboardMarkup::new(buttons)
+}
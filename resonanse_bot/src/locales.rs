@@ -0,0 +1,381 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use log::warn;
+use teloxide::dispatching::{DpHandlerDescription, UpdateHandler};
+use teloxide::dptree::di::DependencySupplier;
+use teloxide::dptree::HandlerDescription;
+use teloxide::prelude::*;
+
+use resonanse_common::repository::AccountsRepository;
+
+pub const DEFAULT_LOCALE: &str = "ru";
+pub const SUPPORTED_LOCALES: [&str; 2] = ["ru", "en"];
+
+tokio::task_local! {
+    /// Locale of user whose update is being handled
+    static USER_LOCALE: String;
+}
+
+/// Locale used by `t!` when it is called without explicit locale
+pub fn current_locale() -> String {
+    USER_LOCALE
+        .try_with(|locale| locale.clone())
+        .unwrap_or_else(|_| rust_i18n::locale())
+}
+
+/// Runs future with messages rendered in given locale, e.g. to notify another user
+pub async fn with_locale<F: Future>(locale: String, f: F) -> F::Output {
+    USER_LOCALE.scope(locale, f).await
+}
+
+/// Maps telegram `language_code` (IETF tag like `en-US`) to one of supported locales
+pub fn supported_locale(language_code: Option<&str>) -> Option<&'static str> {
+    let language = language_code?
+        .split(['-', '_'])
+        .next()?
+        .to_ascii_lowercase();
+    SUPPORTED_LOCALES
+        .into_iter()
+        .find(|locale| *locale == language)
+}
+
+/// Locale chosen by user, or locale of telegram client if user has not chosen it
+pub async fn get_user_locale(
+    accounts_repository: &dyn AccountsRepository,
+    tg_user_id: i64,
+    language_code: Option<&str>,
+) -> String {
+    let chosen_locale = accounts_repository
+        .get_user_locale_by_tg_id(tg_user_id)
+        .await
+        .unwrap_or_else(|err| {
+            warn!("cannot get locale of user {}: {:?}", tg_user_id, err);
+            None
+        });
+
+    chosen_locale
+        .filter(|locale| SUPPORTED_LOCALES.contains(&locale.as_str()))
+        .or_else(|| supported_locale(language_code).map(|locale| locale.to_string()))
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+/// Handles the rest of update with locale of user who sent it
+pub fn user_locale_scope() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>> {
+    dptree::from_fn_with_description(
+        DpHandlerDescription::entry(),
+        |deps: DependencyMap, cont| async move {
+            let update: Arc<Update> = deps.get();
+            let accounts_repository: Arc<Arc<dyn AccountsRepository>> = deps.get();
+            let user = update.user().cloned();
+            let locale = match user {
+                None => DEFAULT_LOCALE.to_string(),
+                Some(user) => {
+                    get_user_locale(
+                        accounts_repository.as_ref().as_ref(),
+                        user.id.0 as i64,
+                        user.language_code.as_deref(),
+                    )
+                    .await
+                }
+            };
+
+            with_locale(locale, cont(deps)).await
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+
+    // not compiled, kept for reference only
+    const IGNORED_SOURCES: &[&str] = &["create_event_old.rs"];
+
+    fn check_translations(path: &str, value: &serde_json::Value, keys: &mut BTreeSet<String>) {
+        let serde_json::Value::Object(entries) = value else {
+            panic!("unexpected value of {}", path);
+        };
+
+        let is_translation = entries.values().all(|v| v.is_string());
+        if is_translation {
+            for locale in SUPPORTED_LOCALES {
+                assert!(
+                    entries.get(locale).is_some_and(|v| v.as_str() != Some("")),
+                    "{} has no {} translation",
+                    path,
+                    locale
+                );
+            }
+            keys.insert(path.to_string());
+            return;
+        }
+
+        for (key, value) in entries {
+            if path.is_empty() && key == "_version" {
+                continue;
+            }
+            let key_path = match path {
+                "" => key.to_string(),
+                _ => format!("{}.{}", path, key),
+            };
+            check_translations(&key_path, value, keys);
+        }
+    }
+
+    fn translation_keys() -> BTreeSet<String> {
+        let translations: serde_json::Value =
+            serde_json::from_str(include_str!("../locales/translations.json")).unwrap();
+        let mut keys = BTreeSet::new();
+        check_translations("", &translations, &mut keys);
+        keys
+    }
+
+    /// Calls whose argument of given index is text shown to user
+    const MESSAGE_CALLS: &[(&str, usize)] = &[("send_message(", 1), ("reject_user_answer!(", 2)];
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum LiteralUse {
+        TranslationKey,
+        MessageText,
+        Other,
+    }
+
+    fn is_ident_byte(b: u8) -> bool {
+        b.is_ascii_alphanumeric() || b == b'_'
+    }
+
+    /// Literal which follows `prefix` is passed as text argument of one of `MESSAGE_CALLS`
+    fn is_message_text_arg(prefix: &str) -> bool {
+        MESSAGE_CALLS.iter().any(|(call, text_arg_idx)| {
+            let Some(call_start) = prefix.rfind(call) else {
+                return false;
+            };
+            let mut depth = 0;
+            let mut arg_idx = 0;
+            for b in prefix[call_start + call.len()..].bytes() {
+                match b {
+                    b'(' | b'[' | b'{' => depth += 1,
+                    b')' | b']' | b'}' if depth == 0 => return false,
+                    b')' | b']' | b'}' => depth -= 1,
+                    b',' if depth == 0 => arg_idx += 1,
+                    _ => {}
+                }
+            }
+            depth == 0 && arg_idx == *text_arg_idx
+        })
+    }
+
+    /// Returns string literals of rust source outside of comments,
+    /// marking the ones passed as the first argument of `t!`
+    /// and the ones sent to user as is
+    fn string_literals(source: &str) -> Vec<(String, LiteralUse)> {
+        let bytes = source.as_bytes();
+        let mut literals = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                    while i < bytes.len() && bytes[i] != b'\n' {
+                        i += 1;
+                    }
+                }
+                b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                    i = source[i..]
+                        .find("*/")
+                        .map_or(bytes.len(), |end| i + end + 2);
+                }
+                b'r' if matches!(bytes.get(i + 1), Some(b'"' | b'#'))
+                    && (i == 0 || !is_ident_byte(bytes[i - 1])) =>
+                {
+                    let hashes = bytes[i + 1..].iter().take_while(|&&b| b == b'#').count();
+                    let start = i + 1 + hashes;
+                    if bytes.get(start) != Some(&b'"') {
+                        i += 1;
+                        continue;
+                    }
+                    let terminator = format!("\"{}", "#".repeat(hashes));
+                    i = source[start + 1..]
+                        .find(&terminator)
+                        .map_or(bytes.len(), |end| start + 1 + end + terminator.len());
+                }
+                b'\'' => {
+                    // skip char literals, lifetimes are left as is
+                    if bytes.get(i + 1) == Some(&b'\\') {
+                        i += 2;
+                        while i < bytes.len() && bytes[i] != b'\'' {
+                            i += 1;
+                        }
+                        i += 1;
+                    } else if bytes.get(i + 2) == Some(&b'\'') {
+                        i += 3;
+                    } else {
+                        i += source[i + 1..]
+                            .chars()
+                            .next()
+                            .map_or(1, |c| 1 + c.len_utf8());
+                        if bytes.get(i) == Some(&b'\'') {
+                            i += 1;
+                        }
+                    }
+                }
+                b'"' => {
+                    let prefix = source[..i].trim_end();
+                    let is_t_arg = prefix.ends_with("t!(")
+                        && !prefix[..prefix.len() - 3]
+                            .bytes()
+                            .last()
+                            .is_some_and(is_ident_byte);
+                    let literal_use = if is_t_arg {
+                        LiteralUse::TranslationKey
+                    } else if is_message_text_arg(prefix) {
+                        LiteralUse::MessageText
+                    } else {
+                        LiteralUse::Other
+                    };
+                    let start = i + 1;
+                    i = start;
+                    while i < bytes.len() && bytes[i] != b'"' {
+                        i += if bytes[i] == b'\\' { 2 } else { 1 };
+                    }
+                    literals.push((source[start..i].to_string(), literal_use));
+                    i += 1;
+                }
+                _ => i += 1,
+            }
+        }
+        literals
+    }
+
+    fn collect_literals(dir: &Path, literals: &mut Vec<(String, LiteralUse)>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                collect_literals(&path, literals);
+            } else if path.extension().is_some_and(|ext| ext == "rs")
+                && !IGNORED_SOURCES
+                    .iter()
+                    .any(|ignored| path.ends_with(ignored))
+            {
+                literals.extend(string_literals(&fs::read_to_string(&path).unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn all_translations_have_supported_locales() {
+        translation_keys();
+
+        let mut available_locales = rust_i18n::available_locales!();
+        available_locales.sort();
+        let mut supported_locales = SUPPORTED_LOCALES.to_vec();
+        supported_locales.sort();
+        assert_eq!(available_locales, supported_locales);
+    }
+
+    #[test]
+    fn translation_keys_match_source() {
+        let keys = translation_keys();
+        let key_namespaces: BTreeSet<&str> = keys
+            .iter()
+            .filter_map(|key| key.split_once('.'))
+            .map(|(namespace, _)| namespace)
+            .collect();
+
+        let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let mut literals = Vec::new();
+        collect_literals(&crate_dir.join("src"), &mut literals);
+        // translated enums are serialized into keys there
+        collect_literals(
+            &crate_dir.join("../resonanse_common/src/models"),
+            &mut literals,
+        );
+
+        let looks_like_key = |literal: &str| {
+            literal
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'.')
+                && literal.split_once('.').is_some_and(|(namespace, rest)| {
+                    key_namespaces.contains(namespace) && !rest.is_empty()
+                })
+        };
+        let missing_keys: BTreeSet<&str> = literals
+            .iter()
+            .filter(|(literal, literal_use)| {
+                *literal_use == LiteralUse::TranslationKey || looks_like_key(literal)
+            })
+            .map(|(literal, _)| literal.as_str())
+            .filter(|literal| !keys.contains(*literal))
+            .collect();
+        assert!(
+            missing_keys.is_empty(),
+            "keys are used but not translated: {:?}",
+            missing_keys
+        );
+
+        let untranslated_messages: BTreeSet<&str> = literals
+            .iter()
+            .filter(|(_, literal_use)| *literal_use == LiteralUse::MessageText)
+            .map(|(literal, _)| literal.as_str())
+            .collect();
+        assert!(
+            untranslated_messages.is_empty(),
+            "messages are sent without translation: {:?}",
+            untranslated_messages
+        );
+
+        let used_literals: BTreeSet<&str> = literals.iter().map(|(l, _)| l.as_str()).collect();
+        let unused_keys: Vec<&String> = keys
+            .iter()
+            .filter(|key| !used_literals.contains(key.as_str()))
+            .collect();
+        assert!(
+            unused_keys.is_empty(),
+            "translations are not used: {:?}",
+            unused_keys
+        );
+    }
+
+    #[test]
+    fn finds_untranslated_message_texts() {
+        let source = r#"
+            bot.send_message(chat_id, "Unknown message").await?;
+            bot.send_message(chat_id, t!("actions.unrecognized_action")).await?;
+            reject_user_answer!(bot, q.from.id, "No city provided");
+            reject_user_answer!(bot, get_chat("main"), t!("actions.invalid_state"));
+        "#;
+        let message_texts: Vec<String> = string_literals(source)
+            .into_iter()
+            .filter(|(_, literal_use)| *literal_use == LiteralUse::MessageText)
+            .map(|(literal, _)| literal)
+            .collect();
+        assert_eq!(message_texts, vec!["Unknown message", "No city provided"]);
+    }
+
+    #[tokio::test]
+    async fn renders_text_in_scope_locale() {
+        assert_eq!(
+            with_locale("en".to_string(), async { t!("profile.city") }).await,
+            "City"
+        );
+        assert_eq!(
+            with_locale("en".to_string(), async {
+                t!("profile.city", locale = "ru")
+            })
+            .await,
+            "Город"
+        );
+    }
+
+    #[test]
+    fn maps_telegram_language_code() {
+        assert_eq!(supported_locale(Some("en-US")), Some("en"));
+        assert_eq!(supported_locale(Some("ru")), Some("ru"));
+        assert_eq!(supported_locale(Some("de")), None);
+        assert_eq!(supported_locale(None), None);
+    }
+}
@@ -1,39 +1,68 @@
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 
 use env_logger::{Builder, TimestampPrecision};
-use log::{info, LevelFilter};
+use log::{error, info, warn, LevelFilter};
 use teloxide::dispatching::dialogue::InMemStorage;
 use teloxide::dptree;
+use teloxide::dptree::di::DependencyMap;
 use teloxide::prelude::*;
 
 use dispatch::schema;
-use resonanse_common::repository::{AccountsRepository, EventsRepository};
+use resonanse_common::configuration::{BotConfig, Config, UpdatesMode};
+use resonanse_common::migrations::{run_migrate_command, run_migrations, MigrateCommand};
+use resonanse_common::repository::{
+    AccountsRepository, CommunitiesRepository, EventDraftsRepository, EventParticipantsRepository,
+    EventScoresRepository, EventsRepository, PgAccountFollowsRepository, PgAccountsRepository,
+    PgCommunitiesRepository, PgEventDraftsRepository, PgEventParticipantsRepository,
+    PgEventScoresRepository, PgEventsRepository, PgGroupFeedsRepository,
+};
 
-use crate::config::{check_all_mandatory_envs_is_ok, POSTGRES_DB_URL, RESONANSE_BOT_TOKEN};
-use crate::management::run_resonanse_management_bot_polling;
+use crate::config::{config, set_config};
+use crate::high_logics::AnnouncementRepositories;
+use crate::http_server::HttpServer;
+use crate::locales::DEFAULT_LOCALE;
+use crate::management::run_resonanse_management_bot;
+use crate::metrics::CountingErrorHandler;
 use crate::states::BaseState;
+use crate::supervisor::{stop_dispatcher_on_shutdown, ShutdownSignal, Supervisor};
+use crate::webhook::{BotUpdates, Webhooks};
 
+#[macro_use]
+extern crate rust_i18n;
+i18n!("locales", fallback = "ru");
+
+/// Shadows `rust_i18n::t!`, text is rendered in locale of user whose update is handled
+macro_rules! t {
+    ($key:expr, locale = $($args:tt)*) => {
+        rust_i18n::t!($key, locale = $($args)*)
+    };
+    ($key:expr $(, $($args:tt)*)?) => {
+        rust_i18n::t!($key, locale = &crate::locales::current_locale() $(, $($args)*)?)
+    };
+}
+
+// modules go after `t!` to use it instead of `rust_i18n::t!`
 mod commands;
 mod config;
 mod data_structs;
 mod data_translators;
+mod date_parser;
 mod dispatch;
 mod errors;
 mod handlers;
 mod high_logics;
+mod http_server;
 mod keyboards;
+mod locales;
 mod management;
+mod metrics;
 mod states;
+mod supervisor;
 mod utils;
-
-#[macro_use]
-extern crate rust_i18n;
-i18n!("locales", fallback = "ru");
+mod webhook;
 
 static MANAGER_BOT: OnceLock<Bot> = OnceLock::new();
 // static DB_POOL: OnceCell<resonanse_common::PgPool> = OnceCell::new();
-static EVENTS_REPOSITORY: OnceLock<EventsRepository> = OnceLock::new();
-static ACCOUNTS_REPOSITORY: OnceLock<AccountsRepository> = OnceLock::new();
 
 #[tokio::main]
 async fn main() {
@@ -44,44 +73,161 @@ async fn main() {
         .format_timestamp(Some(TimestampPrecision::Nanos))
         .init();
 
-    check_all_mandatory_envs_is_ok();
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("migrate") {
+        run_migrate_cli(&args).await;
+        return;
+    }
+
+    match BotConfig::load() {
+        Ok(config) => set_config(config),
+        Err(err) => {
+            error!("{}", err);
+            std::process::exit(1);
+        }
+    }
     setup_i18n_locales();
 
-    let conn_url = std::env::var(POSTGRES_DB_URL).unwrap();
-    let pool = resonanse_common::PgPool::connect(&conn_url).await.unwrap();
+    let pool = resonanse_common::PgPool::connect(&config().common.postgres_db_url)
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
     // DB_POOL.set(pool).unwrap();
-    let events_repository = EventsRepository::new(pool.clone());
-    EVENTS_REPOSITORY.set(events_repository).unwrap();
+    // passed to handlers as dependencies, so they can be replaced in tests
+    let events_repository: Arc<dyn EventsRepository> =
+        Arc::new(PgEventsRepository::new(pool.clone()));
+    let accounts_repository: Arc<dyn AccountsRepository> =
+        Arc::new(PgAccountsRepository::new(pool.clone()));
+    let event_scores_repository: Arc<dyn EventScoresRepository> =
+        Arc::new(PgEventScoresRepository::new(pool.clone()));
 
-    let accounts_repository = AccountsRepository::new(pool.clone());
-    ACCOUNTS_REPOSITORY.set(accounts_repository).unwrap();
+    let event_participants_repository: Arc<dyn EventParticipantsRepository> =
+        Arc::new(PgEventParticipantsRepository::new(pool.clone()));
+    let event_drafts_repository: Arc<dyn EventDraftsRepository> = Arc::new(
+        PgEventDraftsRepository::new(pool.clone(), config().event_draft_ttl),
+    );
+    let communities_repository: Arc<dyn CommunitiesRepository> =
+        Arc::new(PgCommunitiesRepository::new(pool.clone()));
+    let announcement_repositories = AnnouncementRepositories {
+        account_follows_repository: Arc::new(PgAccountFollowsRepository::new(pool.clone())),
+        group_feeds_repository: Arc::new(PgGroupFeedsRepository::new(pool.clone())),
+    };
 
-    let resonanse_bot_handle = tokio::spawn(async { run_resonanse_bot_polling().await });
-    let _resonanse_management_bot_handle =
-        tokio::spawn(async { run_resonanse_management_bot_polling().await });
+    let mut supervisor = Supervisor::new();
+    {
+        let event_drafts_repository = event_drafts_repository.clone();
+        supervisor.spawn("expired_drafts_cleanup", move |shutdown| {
+            run_expired_drafts_cleanup(event_drafts_repository.clone(), shutdown)
+        });
+    }
 
-    resonanse_bot_handle.await.unwrap()
-}
+    let bot = Bot::new(&config().bot_token);
+    let manager_bot = Bot::new(&config().management_bot_token);
+    MANAGER_BOT.set(manager_bot.clone()).unwrap();
+
+    let (webhooks, bot_updates, manager_bot_updates) = match &config().updates_mode {
+        UpdatesMode::Polling => (None, BotUpdates::Polling, BotUpdates::Polling),
+        UpdatesMode::Webhook(webhook_config) => {
+            match Webhooks::set(webhook_config, bot.clone(), manager_bot.clone()).await {
+                Ok((webhooks, bot_endpoint, manager_bot_endpoint)) => (
+                    Some(webhooks),
+                    BotUpdates::Webhook(bot_endpoint),
+                    BotUpdates::Webhook(manager_bot_endpoint),
+                ),
+                Err(err) => {
+                    error!("cannot set webhooks: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+    };
+
+    let http_server = HttpServer::new(config().listen_addr, supervisor.health(), webhooks);
+    supervisor.spawn("http_server", move |shutdown| {
+        http_server.clone().serve(shutdown)
+    });
+
+    {
+        let events_repository = events_repository.clone();
+        let accounts_repository = accounts_repository.clone();
+        let repositories = dptree::deps![
+            events_repository,
+            accounts_repository,
+            event_scores_repository,
+            event_participants_repository,
+            event_drafts_repository,
+            communities_repository,
+            announcement_repositories.account_follows_repository.clone(),
+            announcement_repositories.group_feeds_repository.clone(),
+            announcement_repositories
+        ];
+        supervisor.spawn("resonanse_bot", move |shutdown| {
+            run_resonanse_bot(
+                bot.clone(),
+                bot_updates.clone(),
+                repositories.clone(),
+                shutdown,
+            )
+        });
+    }
+    supervisor.spawn("resonanse_management_bot", move |shutdown| {
+        run_resonanse_management_bot(
+            manager_bot.clone(),
+            manager_bot_updates.clone(),
+            events_repository.clone(),
+            accounts_repository.clone(),
+            shutdown,
+        )
+    });
 
-pub async fn run_resonanse_bot_polling() {
-    info!("Run telegram resonanse bot polling...");
+    supervisor.run_until_shutdown().await;
+}
 
-    let resonanse_bot_token = std::env::var(RESONANSE_BOT_TOKEN).unwrap();
-    let bot = Bot::new(resonanse_bot_token);
+/// `repositories` are passed to handlers as dependencies, dialogue storage is added to them
+pub async fn run_resonanse_bot(
+    bot: Bot,
+    updates: BotUpdates,
+    mut repositories: DependencyMap,
+    shutdown: ShutdownSignal,
+) {
+    info!("Run telegram resonanse bot...");
 
+    repositories.insert(InMemStorage::<BaseState>::new());
     let update_handler = schema();
     let mut dispatcher = Dispatcher::builder(bot, update_handler)
-        .dependencies(dptree::deps![InMemStorage::<BaseState>::new()])
-        .enable_ctrlc_handler()
+        .dependencies(repositories)
+        .error_handler(CountingErrorHandler::new("resonanse"))
         .build();
 
-    dispatcher.dispatch().await;
+    let stop_handle = stop_dispatcher_on_shutdown(dispatcher.shutdown_token(), shutdown);
+    updates.dispatch(&mut dispatcher).await;
+    stop_handle.abort();
 
-    info!("Dispatcher started");
+    info!("Dispatcher stopped");
+}
+
+/// Expired drafts are already hidden from users, here they are removed from db
+async fn run_expired_drafts_cleanup(
+    event_drafts_repository: Arc<dyn EventDraftsRepository>,
+    mut shutdown: ShutdownSignal,
+) {
+    const CLEANUP_PERIOD: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+    let mut interval = tokio::time::interval(CLEANUP_PERIOD);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {},
+            _ = shutdown.wait() => return,
+        }
+        match event_drafts_repository.delete_expired_drafts().await {
+            Ok(deleted_count) => info!("deleted {} expired event drafts", deleted_count),
+            Err(err) => warn!("cannot delete expired event drafts: {:?}", err),
+        }
+    }
 }
 
 fn setup_i18n_locales() {
-    rust_i18n::set_locale("ru");
+    rust_i18n::set_locale(DEFAULT_LOCALE);
     info!(
         "available rust_i18n locales: {:?}",
         rust_i18n::available_locales!()
@@ -89,7 +235,22 @@ fn setup_i18n_locales() {
     info!("default rust_i18n locale: {:?}", rust_i18n::locale());
 }
 
-#[allow(unused)]
-fn run_migrations() {
-    info!("running sqlx migrate");
+/// `resonanse_bot migrate <status|apply>`, only database url is required for it
+async fn run_migrate_cli(args: &[String]) {
+    let Some(command) = args
+        .get(2)
+        .and_then(|command| command.parse::<MigrateCommand>().ok())
+    else {
+        eprintln!("usage: {} migrate <status|apply>", args[0]);
+        std::process::exit(2);
+    };
+
+    let config = Config::load().unwrap_or_else(|err| {
+        error!("{}", err);
+        std::process::exit(1);
+    });
+    let pool = resonanse_common::PgPool::connect(&config.postgres_db_url)
+        .await
+        .unwrap();
+    run_migrate_command(&pool, command).await.unwrap();
 }
@@ -1,5 +1,5 @@
-use std::env;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use log::debug;
 use teloxide::prelude::*;
@@ -9,37 +9,29 @@ use teloxide::utils::markdown;
 use teloxide::Bot;
 use uuid::Uuid;
 
-use crate::config::MANAGER_TG_IDS;
+use resonanse_common::repository::{AccountsRepository, EventsRepository};
+
+use crate::config::config;
+use crate::errors::BotHandlerError;
 use crate::management::common::HandlerResult;
-use crate::{ACCOUNTS_REPOSITORY, EVENTS_REPOSITORY};
-
-fn get_managers_ids() -> Vec<i64> {
-    let managers_ids_str = env::var(MANAGER_TG_IDS).unwrap_or("".to_string());
-    debug!("managers_ids_str: {:?}", managers_ids_str);
-    let managers_ids = managers_ids_str
-        .split(',')
-        .filter_map(|mng_id_str| mng_id_str.parse::<i64>().ok())
-        .collect();
-
-    debug!("managers_ids: {:?}", managers_ids);
-    managers_ids
-}
 
-pub async fn delete_event_command(bot: Bot, msg: Message) -> HandlerResult {
+pub async fn delete_event_command(
+    bot: Bot,
+    msg: Message,
+    events_repository: Arc<dyn EventsRepository>,
+) -> HandlerResult {
     debug!("got delete_event_command {:?}", &msg);
 
     // CHECK FOR MANAGER RIGHTS
-    if !get_managers_ids().contains(&msg.chat.id.0) {
-        return Ok(());
+    if !config().manager_tg_ids.contains(&msg.chat.id.0) {
+        return Err(Box::new(BotHandlerError::PermissionDenied));
     }
 
     if let Some(command_text) = msg.text() {
         if let Some((_command, params)) = parse_command(command_text, "") {
             if let Some(first_param) = params.first() {
                 if let Ok(event_uuid) = Uuid::from_str(first_param) {
-                    let result = EVENTS_REPOSITORY
-                        .get()
-                        .ok_or("Cannot get events repository")?
+                    let result = events_repository
                         .delete_event(event_uuid, msg.chat.id.0)
                         .await;
 
@@ -47,14 +39,14 @@ pub async fn delete_event_command(bot: Bot, msg: Message) -> HandlerResult {
                         Ok(_) => {
                             bot.send_message(
                                 msg.chat.id,
-                                format!("Событие {} удалено", event_uuid),
+                                t!("management.event_deleted", event_id = event_uuid),
                             )
                             .await?;
                         }
                         Err(_) => {
                             bot.send_message(
                                 msg.chat.id,
-                                format!("Событие {} НЕ удалено", event_uuid),
+                                t!("management.event_not_deleted", event_id = event_uuid),
                             )
                             .await?;
                         }
@@ -66,28 +58,25 @@ pub async fn delete_event_command(bot: Bot, msg: Message) -> HandlerResult {
         }
     }
 
-    let mut message = bot.send_message(msg.chat.id, "Команда не распознана");
+    let mut message = bot.send_message(msg.chat.id, t!("management.unknown_command"));
     message.parse_mode = Some(ParseMode::MarkdownV2);
     message.await?;
 
     Ok(())
 }
 
-pub async fn get_stats_command(bot: Bot, msg: Message) -> HandlerResult {
+pub async fn get_stats_command(
+    bot: Bot,
+    msg: Message,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
+) -> HandlerResult {
     debug!("got get_stats_command {:?}", &msg);
 
-    let count_accounts = ACCOUNTS_REPOSITORY
-        .get()
-        .ok_or("Cannot get accounts repository")?
-        .count_accounts()
-        .await?;
+    let count_accounts = accounts_repository.count_accounts().await?;
 
     // todo
-    let _count_events_by_subject = EVENTS_REPOSITORY
-        .get()
-        .ok_or("Cannot get events repository")?
-        .count_events_by_subject()
-        .await?;
+    let _count_events_by_subject = events_repository.count_events_by_subject().await?;
 
     // let all_events = events_uuids_map
     //     .iter()
@@ -103,10 +92,7 @@ pub async fn get_stats_command(bot: Bot, msg: Message) -> HandlerResult {
 
     let mut message = bot.send_message(
         msg.chat.id,
-        format!(
-            "\\[пока статистика только такая\\]\nКоличество пользователей: {}\n\n",
-            count_accounts,
-        ),
+        t!("management.stats", accounts_count = count_accounts),
     );
 
     message.parse_mode = Some(ParseMode::MarkdownV2);
@@ -119,21 +105,20 @@ pub async fn search_event_command(
     bot: Bot,
     msg: Message,
     searching_event_title: String,
+    events_repository: Arc<dyn EventsRepository>,
 ) -> HandlerResult {
     debug!("got search_event_command {:?}", &msg);
 
     // CHECK FOR MANAGER RIGHTS
-    if !get_managers_ids().contains(&msg.chat.id.0) {
-        return Ok(());
+    if !config().manager_tg_ids.contains(&msg.chat.id.0) {
+        return Err(Box::new(BotHandlerError::PermissionDenied));
     }
 
     debug!("{:?}", msg.entities());
 
     debug!("search by substr {:?}", searching_event_title);
 
-    let result = EVENTS_REPOSITORY
-        .get()
-        .ok_or("Cannot get events repository")?
+    let result = events_repository
         .get_events_by_title_substr(&searching_event_title)
         .await?;
 
@@ -151,14 +136,17 @@ pub async fn search_event_command(
             .collect::<Vec<String>>()
             .join("\n");
 
-        let mut message = bot.send_message(msg.chat.id, format!("События:\n{}", result_formatted));
+        let mut message = bot.send_message(
+            msg.chat.id,
+            t!("management.found_events", events = result_formatted),
+        );
 
         message.parse_mode = Some(ParseMode::MarkdownV2);
         message.await?;
         return Ok(());
     }
 
-    let mut message = bot.send_message(msg.chat.id, "Ничего не найдено");
+    let mut message = bot.send_message(msg.chat.id, t!("management.nothing_found"));
     message.parse_mode = Some(ParseMode::MarkdownV2);
     message.await?;
 
@@ -13,3 +13,13 @@ pub enum ManagementCommand {
     #[command(description = "Статистика")]
     SearchEventByName(String),
 }
+
+impl ManagementCommand {
+    pub fn metric_label(&self) -> &'static str {
+        match self {
+            ManagementCommand::DeleteEvent => "delete_event",
+            ManagementCommand::GetStatistics => "get_statistics",
+            ManagementCommand::SearchEventByName(_) => "search_event_by_name",
+        }
+    }
+}
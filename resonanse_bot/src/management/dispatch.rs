@@ -1,10 +1,11 @@
-use crate::config::MANAGER_TG_IDS;
+use crate::config::config;
+use crate::errors::report_errors;
 use crate::management::actions::*;
 use crate::management::commands::ManagementCommand;
 use crate::management::common::HandlerResult;
 use crate::management::BaseManagementState;
+use crate::metrics::{count_commands, count_updates};
 use log::debug;
-use std::env;
 use teloxide::dispatching::dialogue::InMemStorage;
 use teloxide::dispatching::{dialogue, UpdateHandler};
 use teloxide::prelude::*;
@@ -13,6 +14,10 @@ pub fn manager_schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync
     use dptree::case;
 
     let command_handler = teloxide::filter_command::<ManagementCommand, _>()
+        .chain(count_commands(
+            "management",
+            ManagementCommand::metric_label,
+        ))
         .branch(case![ManagementCommand::DeleteEvent].endpoint(delete_event_command))
         .branch(case![ManagementCommand::GetStatistics].endpoint(get_stats_command))
         .branch(case![ManagementCommand::SearchEventByName(name)].endpoint(search_event_command));
@@ -22,25 +27,17 @@ pub fn manager_schema() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync
         .branch(command_handler)
         .branch(dptree::endpoint(unhandled_message));
 
-    dialogue::enter::<Update, InMemStorage<BaseManagementState>, BaseManagementState, _>()
-        .branch(message_handler)
-}
-
-fn get_managers_ids() -> Vec<i64> {
-    let managers_ids_str = env::var(MANAGER_TG_IDS).unwrap_or("".to_string());
-    // debug!("managers_ids_str: {:?}", managers_ids_str);
-    let managers_ids = managers_ids_str
-        .split(',')
-        .filter_map(|mng_id_str| mng_id_str.parse::<i64>().ok())
-        .collect();
-
-    debug!("managers_ids: {:?}", managers_ids);
-    managers_ids
+    count_updates("management")
+        .chain(report_errors("management"))
+        .chain(
+            dialogue::enter::<Update, InMemStorage<BaseManagementState>, BaseManagementState, _>()
+                .branch(message_handler),
+        )
 }
 
 pub fn check_is_manager(msg: Message) -> bool {
     // CHECK FOR MANAGER RIGHTS
-    if get_managers_ids().contains(&msg.chat.id.0) {
+    if config().manager_tg_ids.contains(&msg.chat.id.0) {
         debug!("User with id {:?} passed as manager", msg.chat.id);
         true
     } else {
@@ -52,7 +49,8 @@ pub fn check_is_manager(msg: Message) -> bool {
 pub async fn unhandled_message(bot: Bot, msg: Message) -> HandlerResult {
     debug!("got message {:?}", &msg);
 
-    bot.send_message(msg.chat.id, "No handlers").await?;
+    bot.send_message(msg.chat.id, t!("management.unknown_command"))
+        .await?;
 
     Ok(())
 }
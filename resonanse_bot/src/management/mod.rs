@@ -1,7 +1,11 @@
-use crate::config::RESONANSE_MANAGEMENT_BOT_TOKEN;
+use std::sync::Arc;
+
 use crate::management::dispatch::manager_schema;
-use crate::MANAGER_BOT;
+use crate::metrics::CountingErrorHandler;
+use crate::supervisor::{stop_dispatcher_on_shutdown, ShutdownSignal};
+use crate::webhook::BotUpdates;
 use log::info;
+use resonanse_common::repository::{AccountsRepository, EventsRepository};
 use teloxide::dispatching::dialogue::InMemStorage;
 use teloxide::prelude::Dispatcher;
 use teloxide::{dptree, Bot};
@@ -19,22 +23,30 @@ pub enum BaseManagementState {
     Idle,
 }
 
-pub async fn run_resonanse_management_bot_polling() {
-    info!("Run telegram resonanse management bot polling...");
-
-    let resonanse_mngmnt_bot_token = std::env::var(RESONANSE_MANAGEMENT_BOT_TOKEN).unwrap();
-    let manager_bot = Bot::new(resonanse_mngmnt_bot_token);
-    MANAGER_BOT.set(manager_bot.clone()).unwrap();
+pub async fn run_resonanse_management_bot(
+    manager_bot: Bot,
+    updates: BotUpdates,
+    events_repository: Arc<dyn EventsRepository>,
+    accounts_repository: Arc<dyn AccountsRepository>,
+    shutdown: ShutdownSignal,
+) {
+    info!("Run telegram resonanse management bot...");
 
     let update_handler = manager_schema();
 
     // todo change handlers
     let mut dispatcher = Dispatcher::builder(manager_bot, update_handler)
-        .dependencies(dptree::deps![InMemStorage::<BaseManagementState>::new()])
-        .enable_ctrlc_handler()
+        .dependencies(dptree::deps![
+            InMemStorage::<BaseManagementState>::new(),
+            events_repository,
+            accounts_repository
+        ])
+        .error_handler(CountingErrorHandler::new("management"))
         .build();
 
-    dispatcher.dispatch().await;
+    let stop_handle = stop_dispatcher_on_shutdown(dispatcher.shutdown_token(), shutdown);
+    updates.dispatch(&mut dispatcher).await;
+    stop_handle.abort();
 
-    info!("Dispatcher started");
+    info!("Management dispatcher stopped");
 }
@@ -0,0 +1,195 @@
+use std::error::Error;
+use std::ops::ControlFlow;
+use std::sync::{Arc, LazyLock};
+use std::time::Instant;
+
+use futures::future::BoxFuture;
+use resonanse_common::metrics::{
+    register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec,
+};
+use teloxide::dispatching::{DpHandlerDescription, UpdateHandler};
+use teloxide::dptree::di::{DependencyMap, DependencySupplier};
+use teloxide::dptree::HandlerDescription;
+use teloxide::error_handlers::ErrorHandler;
+use teloxide::prelude::*;
+use teloxide::types::UpdateKind;
+use teloxide::RequestError;
+
+use crate::errors::BotHandlerError;
+
+type HandlerError = Box<dyn Error + Send + Sync + 'static>;
+
+pub static UPDATES_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "resonanse_bot_updates_total",
+        "Updates received by bots",
+        &["bot", "kind"]
+    )
+    .unwrap()
+});
+
+pub static COMMANDS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "resonanse_bot_commands_total",
+        "Commands sent to bots",
+        &["bot", "command"]
+    )
+    .unwrap()
+});
+
+/// Labelled by `BotHandlerError` variant, `request` for telegram errors,
+/// `storage` for database errors and `other` for the rest
+pub static HANDLER_ERRORS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "resonanse_bot_handler_errors_total",
+        "Errors returned by update handlers",
+        &["bot", "error"]
+    )
+    .unwrap()
+});
+
+pub static EVENT_PUBLICATIONS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "resonanse_bot_event_publications_total",
+        "Published events",
+        &["visibility"]
+    )
+    .unwrap()
+});
+
+pub static CALLBACK_DURATION_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "resonanse_bot_callback_duration_seconds",
+        "Duration of callback query handling",
+        &["callback"]
+    )
+    .unwrap()
+});
+
+/// Counts every update of `bot`, goes first in its schema
+pub fn count_updates(bot: &'static str) -> UpdateHandler<HandlerError> {
+    observer(move |deps| {
+        let update: Arc<Update> = deps.get();
+        UPDATES_TOTAL
+            .with_label_values(&[bot, update_kind(&update.kind)])
+            .inc();
+    })
+}
+
+/// Counts commands parsed by preceding `filter_command`
+pub fn count_commands<C>(
+    bot: &'static str,
+    label: fn(&C) -> &'static str,
+) -> UpdateHandler<HandlerError>
+where
+    C: Send + Sync + 'static,
+{
+    observer(move |deps| {
+        let command: Arc<C> = deps.get();
+        COMMANDS_TOTAL
+            .with_label_values(&[bot, label(&command)])
+            .inc();
+    })
+}
+
+/// Unlike `dptree::inspect` it does not change updates allowed by schema
+fn observer<F>(observe: F) -> UpdateHandler<HandlerError>
+where
+    F: Fn(&DependencyMap) + Send + Sync + 'static,
+{
+    dptree::from_fn_with_description(DpHandlerDescription::entry(), move |deps, cont| {
+        observe(&deps);
+        cont(deps)
+    })
+}
+
+/// Observes duration of `handler` if callback query is handled by it
+pub fn measure_callback(
+    callback: &'static str,
+    handler: UpdateHandler<HandlerError>,
+) -> UpdateHandler<HandlerError> {
+    dptree::from_fn_with_description(
+        DpHandlerDescription::entry(),
+        move |deps, cont| async move {
+            let started_at = Instant::now();
+            let result = cont(deps).await;
+            if let ControlFlow::Break(_) = result {
+                CALLBACK_DURATION_SECONDS
+                    .with_label_values(&[callback])
+                    .observe(started_at.elapsed().as_secs_f64());
+            }
+            result
+        },
+    )
+    .chain(handler)
+}
+
+/// Counts handler errors, they are logged and reported to user by `errors::report_errors`
+pub struct CountingErrorHandler {
+    bot: &'static str,
+}
+
+impl CountingErrorHandler {
+    pub fn new(bot: &'static str) -> Arc<Self> {
+        Arc::new(Self { bot })
+    }
+}
+
+impl ErrorHandler<HandlerError> for CountingErrorHandler {
+    fn handle_error(self: Arc<Self>, error: HandlerError) -> BoxFuture<'static, ()> {
+        HANDLER_ERRORS_TOTAL
+            .with_label_values(&[self.bot, handler_error_label(error.as_ref())])
+            .inc();
+        Box::pin(async {})
+    }
+}
+
+fn handler_error_label(error: &(dyn Error + 'static)) -> &'static str {
+    if let Some(error) = error.downcast_ref::<BotHandlerError>() {
+        return error.label();
+    }
+    if error.is::<RequestError>() {
+        "request"
+    } else if error.is::<sqlx::Error>() {
+        "storage"
+    } else {
+        "other"
+    }
+}
+
+fn update_kind(kind: &UpdateKind) -> &'static str {
+    match kind {
+        UpdateKind::Message(_) => "message",
+        UpdateKind::EditedMessage(_) => "edited_message",
+        UpdateKind::ChannelPost(_) => "channel_post",
+        UpdateKind::EditedChannelPost(_) => "edited_channel_post",
+        UpdateKind::InlineQuery(_) => "inline_query",
+        UpdateKind::ChosenInlineResult(_) => "chosen_inline_result",
+        UpdateKind::CallbackQuery(_) => "callback_query",
+        UpdateKind::ShippingQuery(_) => "shipping_query",
+        UpdateKind::PreCheckoutQuery(_) => "pre_checkout_query",
+        UpdateKind::Poll(_) => "poll",
+        UpdateKind::PollAnswer(_) => "poll_answer",
+        UpdateKind::MyChatMember(_) => "my_chat_member",
+        UpdateKind::ChatMember(_) => "chat_member",
+        UpdateKind::ChatJoinRequest(_) => "chat_join_request",
+        UpdateKind::Error(_) => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_handler_errors() {
+        let error: HandlerError = Box::new(BotHandlerError::UnfilledEvent);
+        assert_eq!(handler_error_label(error.as_ref()), "UnfilledEvent");
+        let error: HandlerError = Box::new(BotHandlerError::Validation("Too long".to_string()));
+        assert_eq!(handler_error_label(error.as_ref()), "Validation");
+        let error: HandlerError = Box::new(sqlx::Error::PoolTimedOut);
+        assert_eq!(handler_error_label(error.as_ref()), "storage");
+        let error: HandlerError = "Cannot get manager bot".into();
+        assert_eq!(handler_error_label(error.as_ref()), "other");
+    }
+}
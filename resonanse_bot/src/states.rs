@@ -1,8 +1,10 @@
-use crate::data_structs::FillingEvent;
-use resonanse_common::EventSubjectFilter;
+use crate::data_structs::{FillingEvent, ProfileField};
+use resonanse_common::models::City;
+use resonanse_common::{EventFormatFilter, EventSubjectFilter};
 use teloxide::types::MessageId;
 
 #[derive(Clone, Default)]
+#[allow(clippy::large_enum_variant)]
 pub enum BaseState {
     #[default]
     Start,
@@ -11,13 +13,21 @@ pub enum BaseState {
         state: CreateEventState,
         filling_event: FillingEvent,
         last_edit_msg_id: MessageId,
+        // drafts waiting for review after current one (e.g. imported from .ics)
+        pending_drafts: Vec<FillingEvent>,
     },
     GetEventList {
         page_size: i64,
         page_num: i64,
         events_filter: EventSubjectFilter,
+        /// `None` shows events of all cities
+        city_filter: Option<City>,
+        format_filter: EventFormatFilter,
     },
     SendFeedback,
+    EditProfile {
+        field: ProfileField,
+    },
 }
 
 #[derive(Clone, Debug, Default)]
@@ -31,8 +41,15 @@ pub enum CreateEventState {
     BriefDescription,
     DatetimeFrom,
     DatetimeTo,
+    Recurrence,
     Geo,
+    City,
     PlaceTitle,
+    EventFormat,
+    Price,
+    Capacity,
+    Community,
+    MeetingUrl,
     Subject,
     Picture,
     ContactInfo,
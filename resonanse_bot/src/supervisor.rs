@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use log::{error, info, warn};
+use teloxide::dispatching::ShutdownToken;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+const MIN_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+/// Task which has been running this long is considered recovered, its backoff is reset
+const STABLE_RUN_DURATION: Duration = Duration::from_secs(60);
+/// Time given to tasks to finish their work after shutdown signal
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TaskState {
+    Running,
+    Restarting { attempt: u32 },
+    Stopped,
+}
+
+impl Display for TaskState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskState::Running => write!(f, "running"),
+            TaskState::Restarting { attempt } => write!(f, "restarting (attempt {})", attempt),
+            TaskState::Stopped => write!(f, "stopped"),
+        }
+    }
+}
+
+/// States of supervised tasks, shared with health endpoint
+#[derive(Clone, Default)]
+pub struct Health {
+    tasks: Arc<Mutex<BTreeMap<&'static str, TaskState>>>,
+}
+
+impl Health {
+    fn set(&self, task: &'static str, state: TaskState) {
+        let previous = self.tasks.lock().unwrap().insert(task, state);
+        if previous != Some(state) {
+            info!("task {} is {}", task, state);
+        }
+    }
+
+    pub fn tasks(&self) -> BTreeMap<&'static str, TaskState> {
+        self.tasks.lock().unwrap().clone()
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.tasks
+            .lock()
+            .unwrap()
+            .values()
+            .all(|state| *state == TaskState::Running)
+    }
+}
+
+/// Resolves once shutdown of the whole bot is requested
+#[derive(Clone)]
+pub struct ShutdownSignal {
+    receiver: watch::Receiver<bool>,
+}
+
+impl ShutdownSignal {
+    pub fn is_shutdown(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    pub async fn wait(&mut self) {
+        // sender lives in supervisor, error means that supervisor is gone as well
+        let _ = self.receiver.wait_for(|is_shutdown| *is_shutdown).await;
+    }
+}
+
+/// Runs long-living tasks, restarts them with backoff when they fail
+/// and stops them on SIGINT or SIGTERM
+pub struct Supervisor {
+    shutdown_tx: watch::Sender<bool>,
+    health: Health,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Self {
+            shutdown_tx: watch::channel(false).0,
+            health: Health::default(),
+            tasks: Vec::new(),
+        }
+    }
+
+    pub fn health(&self) -> Health {
+        self.health.clone()
+    }
+
+    pub fn shutdown_signal(&self) -> ShutdownSignal {
+        ShutdownSignal {
+            receiver: self.shutdown_tx.subscribe(),
+        }
+    }
+
+    /// `task` is called again on every restart, it must return when shutdown is signalled
+    pub fn spawn<F, Fut>(&mut self, name: &'static str, task: F)
+    where
+        F: Fn(ShutdownSignal) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let health = self.health.clone();
+        let mut shutdown = self.shutdown_signal();
+        health.set(name, TaskState::Running);
+
+        self.tasks.push(tokio::spawn(async move {
+            let mut backoff = MIN_RESTART_BACKOFF;
+            let mut attempt = 0;
+            loop {
+                let started_at = Instant::now();
+                let result = tokio::spawn(task(shutdown.clone())).await;
+                if shutdown.is_shutdown() {
+                    break;
+                }
+                match result {
+                    Ok(()) => warn!("task {} has stopped unexpectedly", name),
+                    Err(err) => error!("task {} has failed: {}", name, err),
+                }
+
+                if started_at.elapsed() >= STABLE_RUN_DURATION {
+                    backoff = MIN_RESTART_BACKOFF;
+                    attempt = 0;
+                }
+                attempt += 1;
+                health.set(name, TaskState::Restarting { attempt });
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {},
+                    _ = shutdown.wait() => break,
+                }
+                backoff = (backoff * 2).min(MAX_RESTART_BACKOFF);
+                health.set(name, TaskState::Running);
+            }
+            health.set(name, TaskState::Stopped);
+        }));
+    }
+
+    /// Waits for SIGINT or SIGTERM, then signals shutdown and waits for tasks
+    pub async fn run_until_shutdown(self) {
+        wait_for_termination().await;
+        info!("shutdown is requested, stopping tasks...");
+        self.shutdown().await;
+    }
+
+    async fn shutdown(self) {
+        let _ = self.shutdown_tx.send(true);
+        let tasks = async {
+            for task in self.tasks {
+                let _ = task.await;
+            }
+        };
+        if tokio::time::timeout(SHUTDOWN_TIMEOUT, tasks).await.is_err() {
+            warn!("tasks have not stopped in {:?}", SHUTDOWN_TIMEOUT);
+        }
+        info!("all tasks are stopped");
+    }
+}
+
+async fn wait_for_termination() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            error!("cannot listen for ctrl-c: {}", err);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(err) => {
+                error!("cannot listen for SIGTERM: {}", err);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// Stops dispatcher on shutdown signal, returned handle is aborted after dispatching is finished
+pub fn stop_dispatcher_on_shutdown(
+    token: ShutdownToken,
+    mut shutdown: ShutdownSignal,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        shutdown.wait().await;
+        loop {
+            match token.shutdown() {
+                Ok(stopped) => return stopped.await,
+                // dispatcher has not started yet
+                Err(_) => tokio::time::sleep(Duration::from_millis(100)).await,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn restarts_failed_task_until_shutdown() {
+        let runs = Arc::new(AtomicU32::new(0));
+        let mut supervisor = Supervisor::new();
+        let health = supervisor.health();
+
+        let task_runs = runs.clone();
+        supervisor.spawn("flaky", move |mut shutdown| {
+            let run = task_runs.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if run < 2 {
+                    panic!("run {} fails", run);
+                }
+                shutdown.wait().await;
+            }
+        });
+
+        // two failures are restarted after 1s and 2s
+        tokio::time::sleep(Duration::from_secs(4)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert!(health.is_healthy());
+
+        supervisor.shutdown().await;
+        assert_eq!(health.tasks()["flaky"], TaskState::Stopped);
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+}
@@ -1,8 +1,8 @@
-use std::env;
-
+use chrono_tz::Tz;
+use resonanse_common::models::EventPrice;
 use uuid::Uuid;
 
-use crate::config::RESONANSE_BOT_USERNAME;
+use crate::config::config;
 
 // const TG_DOWNLOADS_PATH: &str = "tg_downloads";
 
@@ -35,12 +35,179 @@ pub fn repr_user_as_str(user: Option<&teloxide::types::User>) -> String {
 }
 
 pub fn build_event_deep_link(event_uuid: Uuid) -> String {
-    let bot_username = env::var(RESONANSE_BOT_USERNAME);
-    let bot_username = bot_username.as_deref().unwrap_or("resonanse_bot");
+    build_deep_link_with_param(
+        &config().bot_username,
+        &format!("event_{}", &event_uuid.to_string()),
+    )
+}
 
-    build_deep_link_with_param(bot_username, &format!("event_{}", &event_uuid.to_string()))
+/// Link to private event, opening it grants access to event
+pub fn build_event_invite_link(invite_token: &str) -> String {
+    build_deep_link_with_param(&config().bot_username, &format!("invite_{}", invite_token))
+}
+
+/// Link to private community, opening it makes user a member
+pub fn build_community_invite_link(invite_token: &str) -> String {
+    build_deep_link_with_param(
+        &config().bot_username,
+        &format!("community_{}", invite_token),
+    )
 }
 
 pub fn build_deep_link_with_param(bot_username: &str, param: &str) -> String {
     format!("https://t.me/{}?start={}", bot_username, param)
 }
+
+/// Parses IANA timezone name (`Europe/Moscow`) or whole-hour UTC offset (`+03:00`, `UTC+3`)
+pub fn parse_timezone(tz_str: &str) -> Option<Tz> {
+    let tz_str = tz_str.trim();
+    if let Ok(tz) = tz_str.parse::<Tz>() {
+        return Some(tz);
+    }
+
+    let upper_tz_str = tz_str.to_ascii_uppercase();
+    let offset_str = upper_tz_str
+        .strip_prefix("UTC")
+        .or_else(|| upper_tz_str.strip_prefix("GMT"))
+        .unwrap_or(&upper_tz_str);
+    if offset_str.is_empty() {
+        return Some(chrono_tz::UTC);
+    }
+
+    let (sign, offset_str) = match offset_str.split_at(1) {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let (hours_str, minutes_str) = offset_str.split_once(':').unwrap_or((offset_str, "00"));
+    let hours = hours_str.parse::<i32>().ok()?;
+    if minutes_str.parse::<u32>().ok()? != 0 || !(0..=14).contains(&hours) {
+        return None;
+    }
+
+    // Etc/GMT zones have inverted sign: Etc/GMT-3 is UTC+3
+    match sign * hours {
+        0 => Some(chrono_tz::Etc::GMT),
+        offset if offset > 0 => format!("Etc/GMT-{}", offset).parse().ok(),
+        offset => format!("Etc/GMT+{}", -offset).parse().ok(),
+    }
+}
+
+/// Currencies accepted in event price: ISO 4217 code, symbol and other spellings.
+/// All of them have 2 digits of minor units
+const KNOWN_CURRENCIES: &[(&str, &str, &[&str])] = &[
+    ("RUB", "₽", &["Р", "РУБ", "РУБ.", "РУБЛЕЙ"]),
+    ("USD", "$", &[]),
+    ("EUR", "€", &["ЕВРО"]),
+    ("KZT", "₸", &["ТЕНГЕ"]),
+    ("GEL", "₾", &["ЛАРИ"]),
+    ("AMD", "֏", &["ДРАМ"]),
+    ("TRY", "₺", &["ЛИР"]),
+];
+
+pub const DEFAULT_CURRENCY: &str = "RUB";
+
+const FREE_PRICE_WORDS: &[&str] = &["0", "бесплатно", "free", "-"];
+
+/// Parses event price like `500`, `1 500 ₽`, `$10`, `12.50 EUR`.
+/// Returns `Some(None)` for free event and `None` if price is not recognized
+pub fn parse_event_price(price_str: &str) -> Option<Option<EventPrice>> {
+    let price_str = price_str.trim();
+    if FREE_PRICE_WORDS.contains(&price_str.to_lowercase().as_str()) {
+        return Some(None);
+    }
+
+    let amount_start = price_str.find(|c: char| c.is_ascii_digit())?;
+    let amount_end = price_str
+        .rfind(|c: char| c.is_ascii_digit())
+        .map(|i| i + 1)?;
+    let currency_str = format!("{}{}", &price_str[..amount_start], &price_str[amount_end..])
+        .trim()
+        .to_uppercase();
+
+    let currency = if currency_str.is_empty() {
+        DEFAULT_CURRENCY
+    } else {
+        KNOWN_CURRENCIES
+            .iter()
+            .find(|(code, symbol, aliases)| {
+                currency_str == *code
+                    || currency_str == *symbol
+                    || aliases.contains(&&*currency_str)
+            })
+            .map(|(code, _, _)| *code)?
+    };
+
+    let amount_str: String = price_str[amount_start..amount_end]
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| if c == ',' { '.' } else { c })
+        .collect();
+    let (major_str, minor_str) = amount_str.split_once('.').unwrap_or((&amount_str, "0"));
+    if minor_str.len() > 2 {
+        return None;
+    }
+    let major = major_str.parse::<i64>().ok()?;
+    let minor = format!("{:0<2}", minor_str).parse::<i64>().ok()?;
+    let amount = major.checked_mul(100)?.checked_add(minor)?;
+    if amount == 0 {
+        return Some(None);
+    }
+
+    Some(Some(EventPrice {
+        amount,
+        currency: currency.to_string(),
+    }))
+}
+
+/// Formats price as `1500 ₽` or `12.50 $`
+pub fn format_event_price(price: &EventPrice) -> String {
+    let currency = KNOWN_CURRENCIES
+        .iter()
+        .find(|(code, _, _)| *code == price.currency)
+        .map(|(_, symbol, _)| *symbol)
+        .unwrap_or(&price.currency);
+
+    match price.amount % 100 {
+        0 => format!("{} {}", price.amount / 100, currency),
+        minor => format!("{}.{:02} {}", price.amount / 100, minor, currency),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_event_price() {
+        let price = |amount: i64, currency: &str| {
+            Some(Some(EventPrice {
+                amount,
+                currency: currency.to_string(),
+            }))
+        };
+
+        for (input, expected) in [
+            ("500", price(50000, "RUB")),
+            ("1 500 ₽", price(150000, "RUB")),
+            ("300 руб.", price(30000, "RUB")),
+            ("$10", price(1000, "USD")),
+            ("12,5 EUR", price(1250, "EUR")),
+            ("Бесплатно", Some(None)),
+            ("0", Some(None)),
+            ("10 монет", None),
+            ("1.999", None),
+            ("дорого", None),
+        ] {
+            assert_eq!(parse_event_price(input), expected, "input: {}", input);
+        }
+
+        assert_eq!(
+            format_event_price(&EventPrice {
+                amount: 1250,
+                currency: "USD".to_string()
+            }),
+            "12.50 $"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use futures::stream::{StreamExt, TakeUntil};
+use log::{debug, error, info, warn};
+use resonanse_common::configuration::WebhookConfig;
+use teloxide::dispatching::DefaultKey;
+use teloxide::prelude::*;
+use teloxide::stop::{mk_stop_token, StopFlag, StopToken};
+use teloxide::types::Update;
+use teloxide::update_listeners::{AsUpdateStream, UpdateListener};
+use teloxide::RequestError;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use url::Url;
+
+const SECRET_TOKEN_HEADER: &str = "x-telegram-bot-api-secret-token";
+
+type UpdateSender = mpsc::UnboundedSender<Result<Update, Infallible>>;
+type UpdateStream = TakeUntil<UnboundedReceiverStream<Result<Update, Infallible>>, StopFlag>;
+
+/// Source of updates for a bot dispatcher
+#[derive(Clone)]
+pub enum BotUpdates {
+    Polling,
+    Webhook(WebhookEndpoint),
+}
+
+impl BotUpdates {
+    /// Dispatches until dispatcher is stopped through its shutdown token
+    pub async fn dispatch<E>(&self, dispatcher: &mut Dispatcher<Bot, E, DefaultKey>)
+    where
+        E: Send + Sync + 'static,
+    {
+        match self {
+            BotUpdates::Polling => dispatcher.dispatch().await,
+            BotUpdates::Webhook(endpoint) => {
+                dispatcher
+                    .dispatch_with_listener(
+                        endpoint.listener(),
+                        LoggingErrorHandler::with_custom_text("An error from the webhook listener"),
+                    )
+                    .await
+            }
+        }
+    }
+}
+
+/// Receives updates which are posted by telegram to webhook server,
+/// stream ends when listener is stopped by dispatcher
+pub struct WebhookListener {
+    updates: UpdateStream,
+    stop_token: StopToken,
+}
+
+impl<'a> AsUpdateStream<'a> for WebhookListener {
+    type StreamErr = Infallible;
+    type Stream = &'a mut UpdateStream;
+
+    fn as_stream(&'a mut self) -> Self::Stream {
+        &mut self.updates
+    }
+}
+
+impl UpdateListener for WebhookListener {
+    type Err = Infallible;
+
+    fn stop_token(&mut self) -> StopToken {
+        self.stop_token.clone()
+    }
+}
+
+/// Webhook of one bot, updates are received on `<webhook url>/webhook/<name>`
+/// and passed to listener of the current dispatcher run
+#[derive(Clone)]
+pub struct WebhookEndpoint {
+    bot: Bot,
+    url: Url,
+    updates_tx: Arc<Mutex<Option<(UpdateSender, StopFlag)>>>,
+}
+
+impl WebhookEndpoint {
+    fn new(config: &WebhookConfig, bot: Bot, name: &str) -> Self {
+        let mut url = config.url.clone();
+        url.path_segments_mut()
+            .expect("webhook url cannot be a base")
+            .pop_if_empty()
+            .extend(["webhook", name]);
+        Self {
+            bot,
+            url,
+            updates_tx: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Listener of restarted dispatcher replaces the previous one
+    fn listener(&self) -> WebhookListener {
+        let (updates_tx, updates_rx) = mpsc::unbounded_channel();
+        let (stop_token, stop_flag) = mk_stop_token();
+        *self.updates_tx.lock().unwrap() = Some((updates_tx, stop_flag.clone()));
+
+        WebhookListener {
+            updates: UnboundedReceiverStream::new(updates_rx).take_until(stop_flag),
+            stop_token,
+        }
+    }
+
+    /// Update is not accepted while dispatcher is stopped or restarting, telegram retries it later
+    fn send(&self, update: Update) -> Result<(), ()> {
+        match self.updates_tx.lock().unwrap().as_ref() {
+            Some((updates_tx, stop_flag)) if !stop_flag.is_stopped() => {
+                updates_tx.send(Ok(update)).map_err(|_| ())
+            }
+            _ => Err(()),
+        }
+    }
+
+    async fn set_webhook(&self, secret_token: &str) -> Result<(), RequestError> {
+        self.bot
+            .set_webhook(self.url.clone())
+            .secret_token(secret_token.to_string())
+            .await?;
+        info!("Webhook is set to {}", self.url);
+        Ok(())
+    }
+
+    async fn delete_webhook(&self) {
+        if let Err(err) = self.bot.delete_webhook().await {
+            error!("cannot delete webhook {}: {:?}", self.url, err);
+        }
+    }
+}
+
+#[derive(Clone)]
+struct RouteState {
+    endpoint: WebhookEndpoint,
+    secret_token: String,
+}
+
+/// Webhooks of both bots, their routes are served by http server
+#[derive(Clone)]
+pub struct Webhooks {
+    endpoints: [WebhookEndpoint; 2],
+    secret_token: String,
+}
+
+impl Webhooks {
+    /// Sets webhooks of both bots, returned endpoints are passed to their dispatchers
+    pub async fn set(
+        config: &WebhookConfig,
+        bot: Bot,
+        manager_bot: Bot,
+    ) -> Result<(Self, WebhookEndpoint, WebhookEndpoint), RequestError> {
+        let bot_endpoint = WebhookEndpoint::new(config, bot, "resonanse");
+        let manager_endpoint = WebhookEndpoint::new(config, manager_bot, "management");
+        for endpoint in [&bot_endpoint, &manager_endpoint] {
+            endpoint.set_webhook(&config.secret_token).await?;
+        }
+
+        let webhooks = Self {
+            endpoints: [bot_endpoint.clone(), manager_endpoint.clone()],
+            secret_token: config.secret_token.clone(),
+        };
+        Ok((webhooks, bot_endpoint, manager_endpoint))
+    }
+
+    pub fn routes(&self) -> Router {
+        self.endpoints
+            .iter()
+            .fold(Router::new(), |router, endpoint| {
+                let state = RouteState {
+                    endpoint: endpoint.clone(),
+                    secret_token: self.secret_token.clone(),
+                };
+                router.route(endpoint.url.path(), post(handle_update).with_state(state))
+            })
+    }
+
+    pub async fn delete(&self) {
+        for endpoint in &self.endpoints {
+            endpoint.delete_webhook().await;
+        }
+    }
+}
+
+async fn handle_update(
+    State(state): State<RouteState>,
+    headers: HeaderMap,
+    body: String,
+) -> StatusCode {
+    let secret_token = headers
+        .get(SECRET_TOKEN_HEADER)
+        .map(|value| value.as_bytes())
+        .unwrap_or_default();
+    if !is_secret_token_valid(secret_token, state.secret_token.as_bytes()) {
+        warn!("update with invalid secret token is rejected");
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    match serde_json::from_str::<Update>(&body) {
+        Ok(update) => {
+            debug!("webhook update {}", update.id);
+            if state.endpoint.send(update).is_err() {
+                return StatusCode::SERVICE_UNAVAILABLE;
+            }
+        }
+        // telegram retries rejected updates, so unparsable update is only logged
+        Err(err) => error!("cannot parse webhook update: {:?}, body: {}", err, body),
+    }
+    StatusCode::OK
+}
+
+/// Compares in constant time so that token cannot be guessed by response timing
+fn is_secret_token_valid(received: &[u8], expected: &[u8]) -> bool {
+    received.len() == expected.len()
+        && received
+            .iter()
+            .zip(expected)
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_secret_tokens() {
+        assert!(is_secret_token_valid(b"secret", b"secret"));
+        assert!(!is_secret_token_valid(b"secreT", b"secret"));
+        assert!(!is_secret_token_valid(b"secret-", b"secret"));
+        assert!(!is_secret_token_valid(b"", b"secret"));
+    }
+
+    fn config() -> WebhookConfig {
+        WebhookConfig {
+            url: "https://example.com/bots/".parse().unwrap(),
+            secret_token: "secret".to_string(),
+        }
+    }
+
+    #[test]
+    fn builds_route_url() {
+        let endpoint = WebhookEndpoint::new(&config(), Bot::new("token"), "management");
+        assert_eq!(
+            endpoint.url.as_str(),
+            "https://example.com/bots/webhook/management"
+        );
+    }
+
+    #[tokio::test]
+    async fn passes_updates_to_current_listener() {
+        let update: Update = serde_json::from_str(
+            r#"{"update_id": 1, "message": {"message_id": 1, "date": 0,
+                "chat": {"id": 1, "type": "private"}, "text": "hi"}}"#,
+        )
+        .unwrap();
+        let endpoint = WebhookEndpoint::new(&config(), Bot::new("token"), "resonanse");
+        assert!(endpoint.send(update.clone()).is_err());
+
+        let mut listener = endpoint.listener();
+        endpoint.send(update.clone()).unwrap();
+        let received = listener.as_stream().next().await.unwrap().unwrap();
+        assert_eq!(received.id, 1);
+
+        listener.stop_token().stop();
+        assert!(endpoint.send(update.clone()).is_err());
+        assert!(listener.as_stream().next().await.is_none());
+
+        // restarted dispatcher receives updates again
+        let _listener = endpoint.listener();
+        endpoint.send(update).unwrap();
+    }
+}
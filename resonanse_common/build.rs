@@ -0,0 +1,4 @@
+fn main() {
+    // migrations are embedded by `sqlx::migrate!`, which does not track their changes
+    println!("cargo:rerun-if-changed=../migrations");
+}
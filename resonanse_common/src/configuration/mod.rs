@@ -1,2 +1,511 @@
-// pub const POSTGRES_DB_URL: &str = "POSTGRES_DB_URL";
+use std::collections::HashMap;
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::net::SocketAddr;
+use std::num::NonZeroU32;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use url::Url;
+
+/// Optional path to TOML file with configuration.
+/// Keys of file are lowercase names of env variables, env variables take precedence over file
+pub const RESONANSE_CONFIG_FILE: &str = "RESONANSE_CONFIG_FILE";
+
+pub const POSTGRES_DB_URL: &str = "POSTGRES_DB_URL";
+/// Existing directory where uploaded images are stored, current directory by default
 pub const RESONANSE_STORAGE_DIR: &str = "RESONANSE_STORAGE_DIR";
+
+pub const RESONANSE_BOT_TOKEN: &str = "RESONANSE_BOT_TOKEN";
+pub const RESONANSE_MANAGEMENT_BOT_TOKEN: &str = "RESONANSE_MANAGEMENT_BOT_TOKEN";
+pub const RESONANSE_BOT_USERNAME: &str = "RESONANSE_BOT_USERNAME";
+pub const FEEDBACK_CHANNEL_ID: &str = "FEEDBACK_CHANNEL_ID";
+pub const POSTS_CHANNEL_ID: &str = "POSTS_CHANNEL_ID";
+/// Comma separated tg ids of users who are able to manage service
+pub const MANAGER_TG_IDS: &str = "MANAGER_TG_IDS";
+pub const WEB_APP_URL: &str = "WEB_APP_URL";
+pub const DONATION_URL: &str = "DONATION_URL";
+/// Days after last change when unfinished event draft is deleted
+pub const EVENT_DRAFT_TTL_DAYS: &str = "EVENT_DRAFT_TTL_DAYS";
+
+/// Address of http server with health, metrics and webhooks
+pub const BOT_LISTEN_ADDR: &str = "BOT_LISTEN_ADDR";
+/// `polling` (default) or `webhook`
+pub const BOT_UPDATES_MODE: &str = "BOT_UPDATES_MODE";
+/// Public base url of reverse proxy, bots receive updates on `<url>/webhook/<bot>`
+pub const WEBHOOK_URL: &str = "WEBHOOK_URL";
+/// Value of `X-Telegram-Bot-Api-Secret-Token` header sent by telegram with every update
+pub const WEBHOOK_SECRET_TOKEN: &str = "WEBHOOK_SECRET_TOKEN";
+
+pub const BACKEND_LISTEN_ADDR: &str = "BACKEND_LISTEN_ADDR";
+
+const DEFAULT_STORAGE_DIR: &str = ".";
+const DEFAULT_EVENT_DRAFT_TTL_DAYS: u32 = 14;
+const DEFAULT_BOT_LISTEN_ADDR: &str = "0.0.0.0:8000";
+const DEFAULT_BACKEND_LISTEN_ADDR: &str = "0.0.0.0:3000";
+
+/// Settings shared by all binaries
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub postgres_db_url: String,
+    pub storage_dir: PathBuf,
+}
+
+/// Settings of resonanse_bot
+#[derive(Clone, Debug)]
+pub struct BotConfig {
+    pub common: Config,
+    pub bot_token: String,
+    pub management_bot_token: String,
+    pub bot_username: String,
+    pub feedback_channel_id: i64,
+    pub posts_channel_id: i64,
+    pub manager_tg_ids: Vec<i64>,
+    pub web_app_url: Url,
+    pub donation_url: Option<Url>,
+    pub event_draft_ttl: chrono::Duration,
+    pub listen_addr: SocketAddr,
+    pub updates_mode: UpdatesMode,
+}
+
+/// How bots receive updates from telegram
+#[derive(Clone, Debug)]
+pub enum UpdatesMode {
+    Polling,
+    Webhook(WebhookConfig),
+}
+
+#[derive(Clone, Debug)]
+pub struct WebhookConfig {
+    pub url: Url,
+    pub secret_token: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum UpdatesModeName {
+    Polling,
+    Webhook,
+}
+
+impl FromStr for UpdatesModeName {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "polling" => Ok(Self::Polling),
+            "webhook" => Ok(Self::Webhook),
+            _ => Err("expected `polling` or `webhook`"),
+        }
+    }
+}
+
+/// Telegram allows 1-256 characters `A-Z`, `a-z`, `0-9`, `_` and `-`
+struct SecretToken(String);
+
+impl FromStr for SecretToken {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let is_valid = (1..=256).contains(&s.len())
+            && s.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        match is_valid {
+            true => Ok(Self(s.to_string())),
+            false => Err("expected 1-256 characters of A-Z, a-z, 0-9, _ and -"),
+        }
+    }
+}
+
+struct StorageDir(PathBuf);
+
+impl FromStr for StorageDir {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let path = PathBuf::from(s);
+        match path.is_dir() {
+            true => Ok(Self(path)),
+            false => Err("expected existing directory"),
+        }
+    }
+}
+
+/// Settings of resonanse_backend
+#[derive(Clone, Debug)]
+pub struct BackendConfig {
+    pub common: Config,
+    pub listen_addr: SocketAddr,
+}
+
+#[derive(Debug)]
+pub enum ConfigKeyError {
+    Missing(&'static str),
+    Invalid { key: &'static str, reason: String },
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Config file cannot be read or is not valid TOML
+    File { path: PathBuf, reason: String },
+    /// Every missing or invalid key
+    Keys(Vec<ConfigKeyError>),
+}
+
+impl Display for ConfigKeyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigKeyError::Missing(key) => write!(f, "{} is missing", key),
+            ConfigKeyError::Invalid { key, reason } => write!(f, "{} is invalid: {}", key, reason),
+        }
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::File { path, reason } => {
+                write!(f, "cannot read config file {:?}: {}", path, reason)
+            }
+            ConfigError::Keys(errors) => {
+                write!(f, "invalid configuration:")?;
+                for error in errors {
+                    write!(f, "\n  - {}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut reader = ConfigReader::from_env()?;
+        let config = Self::read(&mut reader);
+        reader.finish(config)
+    }
+
+    fn read(reader: &mut ConfigReader) -> Option<Self> {
+        let postgres_db_url = reader.required(POSTGRES_DB_URL);
+        let storage_dir = reader
+            .optional(RESONANSE_STORAGE_DIR)
+            .or_else(|| DEFAULT_STORAGE_DIR.parse().ok())
+            .map(|StorageDir(path)| path);
+
+        Some(Self {
+            postgres_db_url: postgres_db_url?,
+            storage_dir: storage_dir?,
+        })
+    }
+}
+
+impl BotConfig {
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut reader = ConfigReader::from_env()?;
+        let config = Self::read(&mut reader);
+        reader.finish(config)
+    }
+
+    fn read(reader: &mut ConfigReader) -> Option<Self> {
+        // every key is read before `?` so that all errors are reported at once
+        let common = Config::read(reader);
+        let bot_token = reader.required(RESONANSE_BOT_TOKEN);
+        let management_bot_token = reader.required(RESONANSE_MANAGEMENT_BOT_TOKEN);
+        let bot_username = reader.required(RESONANSE_BOT_USERNAME);
+        let feedback_channel_id = reader.required(FEEDBACK_CHANNEL_ID);
+        let posts_channel_id = reader.required(POSTS_CHANNEL_ID);
+        let manager_tg_ids = reader.required_list(MANAGER_TG_IDS);
+        let web_app_url = reader.required(WEB_APP_URL);
+        let donation_url = reader.optional(DONATION_URL);
+        let event_draft_ttl_days = reader
+            .optional::<NonZeroU32>(EVENT_DRAFT_TTL_DAYS)
+            .map_or(DEFAULT_EVENT_DRAFT_TTL_DAYS, NonZeroU32::get);
+        let listen_addr = reader
+            .optional(BOT_LISTEN_ADDR)
+            .or_else(|| DEFAULT_BOT_LISTEN_ADDR.parse().ok());
+        let updates_mode = UpdatesMode::read(reader);
+
+        Some(Self {
+            common: common?,
+            bot_token: bot_token?,
+            management_bot_token: management_bot_token?,
+            bot_username: bot_username?,
+            feedback_channel_id: feedback_channel_id?,
+            posts_channel_id: posts_channel_id?,
+            manager_tg_ids: manager_tg_ids?,
+            web_app_url: web_app_url?,
+            donation_url,
+            event_draft_ttl: chrono::Duration::days(event_draft_ttl_days as i64),
+            listen_addr: listen_addr?,
+            updates_mode: updates_mode?,
+        })
+    }
+}
+
+impl UpdatesMode {
+    fn read(reader: &mut ConfigReader) -> Option<Self> {
+        let mode = reader.optional(BOT_UPDATES_MODE);
+        if mode.unwrap_or(UpdatesModeName::Polling) == UpdatesModeName::Polling {
+            return Some(Self::Polling);
+        }
+
+        let url = reader.required(WEBHOOK_URL);
+        let secret_token = reader.required::<SecretToken>(WEBHOOK_SECRET_TOKEN);
+
+        Some(Self::Webhook(WebhookConfig {
+            url: url?,
+            secret_token: secret_token?.0,
+        }))
+    }
+}
+
+impl BackendConfig {
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut reader = ConfigReader::from_env()?;
+        let config = Self::read(&mut reader);
+        reader.finish(config)
+    }
+
+    fn read(reader: &mut ConfigReader) -> Option<Self> {
+        let common = Config::read(reader);
+        let listen_addr = reader
+            .optional(BACKEND_LISTEN_ADDR)
+            .or_else(|| DEFAULT_BACKEND_LISTEN_ADDR.parse().ok());
+
+        Some(Self {
+            common: common?,
+            listen_addr: listen_addr?,
+        })
+    }
+}
+
+/// Reads values from env and config file, remembers every missing or invalid key
+struct ConfigReader {
+    env_values: HashMap<String, String>,
+    file_values: toml::Table,
+    errors: Vec<ConfigKeyError>,
+}
+
+impl ConfigReader {
+    fn new(env_values: HashMap<String, String>, file_values: toml::Table) -> Self {
+        Self {
+            env_values,
+            file_values,
+            errors: Vec::new(),
+        }
+    }
+
+    fn from_env() -> Result<Self, ConfigError> {
+        let file_values = match env::var(RESONANSE_CONFIG_FILE) {
+            Err(_) => toml::Table::new(),
+            Ok(path) => {
+                let path = PathBuf::from(path);
+                std::fs::read_to_string(&path)
+                    .map_err(|err| err.to_string())
+                    .and_then(|content| {
+                        content
+                            .parse()
+                            .map_err(|err: toml::de::Error| err.to_string())
+                    })
+                    .map_err(|reason| ConfigError::File { path, reason })?
+            }
+        };
+
+        Ok(Self::new(env::vars().collect(), file_values))
+    }
+
+    fn value(&self, key: &str) -> Option<String> {
+        if let Some(value) = self.env_values.get(key) {
+            return Some(value.clone());
+        }
+
+        match self.file_values.get(&key.to_lowercase())? {
+            toml::Value::String(value) => Some(value.clone()),
+            toml::Value::Array(values) => Some(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        toml::Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            value => Some(value.to_string()),
+        }
+    }
+
+    fn parse<T>(&mut self, key: &'static str, value: &str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match value.trim().parse() {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.errors.push(ConfigKeyError::Invalid {
+                    key,
+                    reason: err.to_string(),
+                });
+                None
+            }
+        }
+    }
+
+    fn optional<T>(&mut self, key: &'static str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self.value(key).filter(|value| !value.trim().is_empty())?;
+        self.parse(key, &value)
+    }
+
+    fn required<T>(&mut self, key: &'static str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        if self
+            .value(key)
+            .filter(|value| !value.trim().is_empty())
+            .is_none()
+        {
+            self.errors.push(ConfigKeyError::Missing(key));
+            return None;
+        }
+
+        self.optional(key)
+    }
+
+    /// Comma separated list, may be empty but must be set
+    fn required_list<T>(&mut self, key: &'static str) -> Option<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some(value) = self.value(key) else {
+            self.errors.push(ConfigKeyError::Missing(key));
+            return None;
+        };
+
+        value
+            .split(',')
+            .filter(|item| !item.trim().is_empty())
+            .map(|item| self.parse(key, item))
+            .collect()
+    }
+
+    fn finish<T>(self, config: Option<T>) -> Result<T, ConfigError> {
+        match config {
+            Some(config) if self.errors.is_empty() => Ok(config),
+            _ => Err(ConfigError::Keys(self.errors)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reader(env_values: &[(&str, &str)], file: &str) -> ConfigReader {
+        ConfigReader::new(
+            env_values
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            file.parse().unwrap(),
+        )
+    }
+
+    #[test]
+    fn reads_bot_config_from_env_and_file() {
+        let mut reader = reader(
+            &[
+                (POSTGRES_DB_URL, "postgres://localhost/resonanse"),
+                (RESONANSE_BOT_TOKEN, "env-token"),
+                (POSTS_CHANNEL_ID, "-1002"),
+            ],
+            r#"
+            resonanse_bot_token = "file-token"
+            resonanse_management_bot_token = "management-token"
+            resonanse_bot_username = "resonanse_bot"
+            feedback_channel_id = -1001
+            posts_channel_id = -1
+            manager_tg_ids = [1, 2]
+            web_app_url = "https://example.com/app"
+            "#,
+        );
+
+        let config = BotConfig::read(&mut reader);
+        let config = reader.finish(config).unwrap();
+        assert_eq!(config.common.storage_dir, PathBuf::from("."));
+        assert_eq!(config.bot_token, "env-token");
+        assert_eq!(config.feedback_channel_id, -1001);
+        assert_eq!(config.posts_channel_id, -1002);
+        assert_eq!(config.manager_tg_ids, vec![1, 2]);
+        assert_eq!(config.donation_url, None);
+        assert_eq!(config.event_draft_ttl, chrono::Duration::days(14));
+        assert_eq!(config.listen_addr, "0.0.0.0:8000".parse().unwrap());
+        assert!(matches!(config.updates_mode, UpdatesMode::Polling));
+    }
+
+    #[test]
+    fn reads_webhook_mode() {
+        let mut webhook_reader = reader(
+            &[
+                (BOT_UPDATES_MODE, "webhook"),
+                (WEBHOOK_URL, "https://example.com/bots"),
+                (WEBHOOK_SECRET_TOKEN, "secret_token-1"),
+            ],
+            "",
+        );
+        let UpdatesMode::Webhook(config) = UpdatesMode::read(&mut webhook_reader).unwrap() else {
+            panic!("webhook mode is expected");
+        };
+        assert_eq!(config.url.as_str(), "https://example.com/bots");
+        assert_eq!(config.secret_token, "secret_token-1");
+
+        let mut reader = reader(
+            &[
+                (BOT_UPDATES_MODE, "webhook"),
+                (WEBHOOK_SECRET_TOKEN, "not a secret"),
+            ],
+            "",
+        );
+        let config = UpdatesMode::read(&mut reader);
+        let error = reader.finish(config).unwrap_err().to_string();
+        assert!(error.contains(WEBHOOK_URL));
+        assert!(error.contains(WEBHOOK_SECRET_TOKEN));
+    }
+
+    #[test]
+    fn reports_every_invalid_key() {
+        let mut reader = reader(
+            &[
+                (RESONANSE_BOT_TOKEN, "token"),
+                (FEEDBACK_CHANNEL_ID, "feedback"),
+                (MANAGER_TG_IDS, "1,two"),
+                (EVENT_DRAFT_TTL_DAYS, "0"),
+                (RESONANSE_STORAGE_DIR, "/nonexistent/resonanse"),
+            ],
+            "",
+        );
+
+        let config = BotConfig::read(&mut reader);
+        let error = reader.finish(config).unwrap_err().to_string();
+        for key in [
+            POSTGRES_DB_URL,
+            RESONANSE_MANAGEMENT_BOT_TOKEN,
+            FEEDBACK_CHANNEL_ID,
+            MANAGER_TG_IDS,
+            WEB_APP_URL,
+            EVENT_DRAFT_TTL_DAYS,
+            RESONANSE_STORAGE_DIR,
+        ] {
+            assert!(error.contains(key), "{} is not reported in {}", key, error);
+        }
+        assert!(!error.contains(RESONANSE_BOT_TOKEN));
+    }
+}
@@ -1,6 +1,4 @@
-use crate::configuration::RESONANSE_STORAGE_DIR;
 use log::debug;
-use std::env;
 use std::fs::create_dir_all;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
@@ -11,10 +9,9 @@ const FEEDBACK_IMAGES_DIR_NAME: &str = "feedback_images";
 
 // static EVENT_IMAGES_PATH: PathBuf = get_event_images_path();
 
-pub fn get_feedback_images_path() -> PathBuf {
-    let resonanse_base_dir = env::var(RESONANSE_STORAGE_DIR);
-    let resonanse_base_dir = resonanse_base_dir.as_deref().unwrap_or(".");
-    let path = Path::new(resonanse_base_dir)
+/// `storage_dir` is `Config::storage_dir`
+pub fn get_feedback_images_path(storage_dir: &Path) -> PathBuf {
+    let path = storage_dir
         .join(BASE_STORAGE_DIR_NAME)
         .join(FEEDBACK_IMAGES_DIR_NAME);
 
@@ -25,10 +22,9 @@ pub fn get_feedback_images_path() -> PathBuf {
     path
 }
 
-pub fn get_event_images_path() -> PathBuf {
-    let resonanse_base_dir = env::var(RESONANSE_STORAGE_DIR);
-    let resonanse_base_dir = resonanse_base_dir.as_deref().unwrap_or(".");
-    let path = Path::new(resonanse_base_dir)
+/// `storage_dir` is `Config::storage_dir`
+pub fn get_event_images_path(storage_dir: &Path) -> PathBuf {
+    let path = storage_dir
         .join(BASE_STORAGE_DIR_NAME)
         .join(EVENT_IMAGES_DIR_NAME);
 
@@ -39,8 +35,8 @@ pub fn get_event_images_path() -> PathBuf {
     path
 }
 
-pub fn get_event_image_path_by_uuid(event_uuid: Uuid) -> PathBuf {
-    let event_image_path = get_event_images_path().join(event_uuid.to_string());
+pub fn get_event_image_path_by_uuid(storage_dir: &Path, event_uuid: Uuid) -> PathBuf {
+    let event_image_path = get_event_images_path(storage_dir).join(event_uuid.to_string());
     debug!("event_image_path {:?}", event_image_path);
     event_image_path
     // get_event_images_path().join(get_event_images_path())
@@ -1,12 +1,13 @@
-pub use models::EventSubjectFilter;
+pub use models::{EventFormatFilter, EventSubjectFilter};
 pub use sqlx::PgPool;
 
+pub mod configuration;
 pub mod file_storage;
+pub mod metrics;
+pub mod migrations;
 pub mod models;
 pub mod repository;
 
-mod configuration;
-
 pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
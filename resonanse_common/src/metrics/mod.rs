@@ -0,0 +1,74 @@
+use std::future::Future;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use prometheus::{Encoder, TextEncoder};
+
+pub use prometheus::{
+    register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec,
+};
+
+/// Value of `Content-Type` header of [`render`] output
+pub const METRICS_CONTENT_TYPE: &str = prometheus::TEXT_FORMAT;
+
+/// Labelled by repository method, e.g. `events.create_event`, and `ok` or `error` status
+pub static DB_QUERY_DURATION_SECONDS: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "resonanse_db_query_duration_seconds",
+        "Duration of database queries",
+        &["query", "status"]
+    )
+    .unwrap()
+});
+
+pub trait ObserveQuery<T>: Future<Output = sqlx::Result<T>> + Send + Sized {
+    /// Observes duration of query in [`DB_QUERY_DURATION_SECONDS`]
+    fn observe(self, query: &'static str) -> impl Future<Output = sqlx::Result<T>> + Send {
+        async move {
+            let started_at = Instant::now();
+            let result = self.await;
+            let status = match result {
+                Ok(_) => "ok",
+                Err(_) => "error",
+            };
+            DB_QUERY_DURATION_SECONDS
+                .with_label_values(&[query, status])
+                .observe(started_at.elapsed().as_secs_f64());
+            result
+        }
+    }
+}
+
+impl<T, F> ObserveQuery<T> for F where F: Future<Output = sqlx::Result<T>> + Send {}
+
+/// Metrics of the whole process in prometheus text format
+pub fn render() -> String {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&prometheus::gather(), &mut buffer)
+        .expect("metrics are encoded to vec");
+    String::from_utf8(buffer).expect("text format is utf-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn observes_query_duration() {
+        let result = async { Ok(1) }.observe("tests.query").await;
+        assert_eq!(result.unwrap(), 1);
+        let result = async { Err::<(), _>(sqlx::Error::RowNotFound) }
+            .observe("tests.query")
+            .await;
+        assert!(result.is_err());
+
+        let metrics = render();
+        assert!(metrics.contains(
+            r#"resonanse_db_query_duration_seconds_count{query="tests.query",status="ok"} 1"#
+        ));
+        assert!(metrics.contains(
+            r#"resonanse_db_query_duration_seconds_count{query="tests.query",status="error"} 1"#
+        ));
+    }
+}
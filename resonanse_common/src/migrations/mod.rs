@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use log::info;
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
+use sqlx::PgPool;
+
+/// Migrations from `migrations` dir of workspace, embedded into binary.
+/// Migrator holds postgres advisory lock while applying them,
+/// so bot and backend may be started at the same time
+pub static MIGRATOR: Migrator = sqlx::migrate!("../migrations");
+
+/// Versions of migrations which altered schema of `db_init` scripts.
+/// Initial schema, which took their versions, already contains their changes
+const LEGACY_MIGRATIONS: [(i64, &str); 2] =
+    [(1, "add contact info"), (2, "alter events for new filling")];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, strum_macros::Display)]
+pub enum MigrationState {
+    #[strum(serialize = "applied")]
+    Applied,
+    #[strum(serialize = "pending")]
+    Pending,
+    /// Migration file was changed after it had been applied
+    #[strum(serialize = "modified")]
+    Modified,
+    /// Applied migration is unknown to this binary, e.g. binary is older than database
+    #[strum(serialize = "missing")]
+    Missing,
+}
+
+/// Subcommands of `migrate` command of binaries
+#[derive(Clone, Copy, Debug, Eq, PartialEq, strum_macros::EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum MigrateCommand {
+    /// Prints state of every migration
+    Status,
+    /// Applies pending migrations and prints their state
+    Apply,
+}
+
+#[derive(Clone, Debug)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+/// Forgets legacy migrations applied to database, so migrations of the same versions
+/// are applied instead of failing on checksum mismatch. Later migrations are idempotent,
+/// so they bring such database up to date
+async fn forget_legacy_migrations(pool: &PgPool) -> Result<(), MigrateError> {
+    let mut conn = pool.acquire().await?;
+    conn.lock().await?;
+    conn.ensure_migrations_table().await?;
+    for (version, description) in LEGACY_MIGRATIONS {
+        let result = sqlx::query(
+            r#"delete from _sqlx_migrations
+            where version = $1 and description = $2
+            "#,
+        )
+        .bind(version)
+        .bind(description)
+        .execute(&mut *conn)
+        .await?;
+        if result.rows_affected() > 0 {
+            info!("forgot legacy migration {} {}", version, description);
+        }
+    }
+    conn.unlock().await?;
+
+    Ok(())
+}
+
+/// Applies pending migrations, fails if applied ones do not match embedded
+pub async fn run_migrations(pool: &PgPool) -> Result<(), MigrateError> {
+    info!("running migrations");
+    forget_legacy_migrations(pool).await?;
+    MIGRATOR.run(pool).await?;
+    info!("migrations are up to date");
+
+    Ok(())
+}
+
+/// Embedded and applied migrations ordered by version
+pub async fn get_migrations_status(pool: &PgPool) -> Result<Vec<MigrationStatus>, MigrateError> {
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+    let mut applied_checksums = conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| (migration.version, migration.checksum))
+        .collect::<HashMap<_, _>>();
+
+    let mut statuses = MIGRATOR
+        .iter()
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            state: match applied_checksums.remove(&migration.version) {
+                None => MigrationState::Pending,
+                Some(checksum) if checksum == migration.checksum => MigrationState::Applied,
+                Some(_) => MigrationState::Modified,
+            },
+        })
+        .collect::<Vec<_>>();
+    statuses.extend(
+        applied_checksums
+            .into_keys()
+            .map(|version| MigrationStatus {
+                version,
+                description: String::new(),
+                state: MigrationState::Missing,
+            }),
+    );
+    statuses.sort_by_key(|status| status.version);
+
+    Ok(statuses)
+}
+
+pub async fn run_migrate_command(
+    pool: &PgPool,
+    command: MigrateCommand,
+) -> Result<(), MigrateError> {
+    if command == MigrateCommand::Apply {
+        run_migrations(pool).await?;
+    }
+
+    for status in get_migrations_status(pool).await? {
+        println!(
+            "{:>6}  {:<8}  {}",
+            status.version, status.state, status.description
+        );
+    }
+
+    Ok(())
+}
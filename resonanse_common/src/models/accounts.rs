@@ -1,12 +1,16 @@
+use chrono_tz::Tz;
 use sqlx::postgres::PgRow;
 use sqlx::{Error, FromRow, Row};
 
-#[derive(Debug)]
+use crate::models::City;
+
+#[derive(Clone, Debug)]
 pub struct UserData {
     pub first_name: String,
     pub last_name: String,
 
-    pub city: String,
+    /// Home city, events of which are listed by default, `None` until user chooses it
+    pub city: Option<City>,
     pub headline: Option<String>,
     pub about: String, // todo add markdown
 
@@ -24,15 +28,19 @@ pub struct UserData {
     pub food: Option<String>,
     pub worldview: Option<String>,
     pub alcohol: Option<String>,
+
+    pub timezone: Option<Tz>,
+    /// Preferred language of bot messages, `None` means default one
+    pub locale: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct UserTgData {
     pub username: Option<String>,
     pub user_id: Option<i64>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct UserContactData {
     pub email: Option<String>,
     pub phone: Option<String>,
@@ -40,7 +48,7 @@ pub struct UserContactData {
     pub instagram: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct AuthData {
     pub password_hash: Option<String>,
 }
@@ -54,7 +62,7 @@ pub enum ResoAccountType {
     Premium = 3,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct BaseAccount {
     pub id: i64,
     pub username: Option<String>,
@@ -67,40 +75,44 @@ pub struct BaseAccount {
 impl FromRow<'_, PgRow> for BaseAccount {
     fn from_row(row: &PgRow) -> Result<Self, Error> {
         Ok(BaseAccount {
-            id: row.try_get::<_, usize>(0)?,
-            username: row.try_get::<_, usize>(1)?,
+            id: row.try_get("id")?,
+            username: row.try_get("username")?,
             user_data: UserData {
-                first_name: row.try_get::<_, usize>(2)?,
-                last_name: row.try_get::<_, usize>(3)?,
-                city: row.try_get::<_, usize>(4)?,
-                about: row.try_get::<_, usize>(5)?,
-                headline: row.try_get::<_, usize>(6)?,
-                goals: row.try_get::<_, usize>(7)?,
-                interests: row.try_get::<_, usize>(8)?,
-                language: row.try_get::<_, usize>(9)?,
-                age: row.try_get::<_, usize>(10)?,
-                education: row.try_get::<_, usize>(11)?,
-                hobby: row.try_get::<_, usize>(12)?,
-                music: row.try_get::<_, usize>(13)?,
-                sport: row.try_get::<_, usize>(14)?,
-                books: row.try_get::<_, usize>(15)?,
-                food: row.try_get::<_, usize>(16)?,
-                worldview: row.try_get::<_, usize>(17)?,
-                alcohol: row.try_get::<_, usize>(18)?,
+                first_name: row.try_get("first_name")?,
+                last_name: row.try_get("last_name")?,
+                city: row.try_get("city")?,
+                about: row.try_get("about")?,
+                headline: row.try_get("headline")?,
+                goals: row.try_get("goals")?,
+                interests: row.try_get("interests")?,
+                language: row.try_get("language")?,
+                age: row.try_get("age")?,
+                education: row.try_get("education")?,
+                hobby: row.try_get("hobby")?,
+                music: row.try_get("music")?,
+                sport: row.try_get("sport")?,
+                books: row.try_get("books")?,
+                food: row.try_get("food")?,
+                worldview: row.try_get("worldview")?,
+                alcohol: row.try_get("alcohol")?,
+                timezone: row
+                    .try_get::<Option<String>, _>("timezone")?
+                    .and_then(|tz_name| tz_name.parse::<Tz>().ok()),
+                locale: row.try_get("locale")?,
             },
             contact_data: UserContactData {
-                email: row.try_get::<_, usize>(19)?,
-                phone: row.try_get::<_, usize>(20)?,
+                email: row.try_get("email")?,
+                phone: row.try_get("phone")?,
                 telegram: UserTgData {
-                    username: row.try_get::<_, usize>(21)?,
-                    user_id: row.try_get::<_, usize>(22)?,
+                    username: row.try_get("tg_username")?,
+                    user_id: row.try_get("tg_user_id")?,
                 },
-                instagram: row.try_get::<_, usize>(23)?,
+                instagram: row.try_get("instagram")?,
             },
             auth_data: AuthData {
-                password_hash: row.try_get::<_, usize>(24)?,
+                password_hash: row.try_get("password_hash")?,
             },
-            user_type: row.try_get::<_, usize>(25)?,
+            user_type: row.try_get("user_type")?,
         })
     }
 }
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use strum_macros;
+
+use crate::models::Location;
+
+/// Cities where events are listed, ordered as shown to users
+pub const CITIES: [City; 6] = [
+    City::SaintPetersburg,
+    City::Moscow,
+    City::Novosibirsk,
+    City::Yekaterinburg,
+    City::Kazan,
+    City::NizhnyNovgorod,
+];
+
+/// Rectangle which covers city with its suburbs
+#[derive(Clone, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min_latitude: f64,
+    pub min_longitude: f64,
+    pub max_latitude: f64,
+    pub max_longitude: f64,
+}
+
+impl BoundingBox {
+    pub fn contains(&self, location: &Location) -> bool {
+        (self.min_latitude..=self.max_latitude).contains(&location.latitude)
+            && (self.min_longitude..=self.max_longitude).contains(&location.longitude)
+    }
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    sqlx::Type,
+    Eq,
+    Hash,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    Serialize,
+    Deserialize,
+)]
+#[repr(i32)]
+pub enum City {
+    #[strum(serialize = "city.saint_petersburg")]
+    SaintPetersburg = 0,
+    #[strum(serialize = "city.moscow")]
+    Moscow = 1,
+    #[strum(serialize = "city.novosibirsk")]
+    Novosibirsk = 2,
+    #[strum(serialize = "city.yekaterinburg")]
+    Yekaterinburg = 3,
+    #[strum(serialize = "city.kazan")]
+    Kazan = 4,
+    #[strum(serialize = "city.nizhny_novgorod")]
+    NizhnyNovgorod = 5,
+}
+
+impl City {
+    pub fn center(&self) -> Location {
+        match self {
+            City::SaintPetersburg => Location::from_ll(59.9386, 30.3141),
+            City::Moscow => Location::from_ll(55.7558, 37.6173),
+            City::Novosibirsk => Location::from_ll(55.0302, 82.9204),
+            City::Yekaterinburg => Location::from_ll(56.8389, 60.6057),
+            City::Kazan => Location::from_ll(55.7963, 49.1088),
+            City::NizhnyNovgorod => Location::from_ll(56.3269, 44.0059),
+        }
+    }
+
+    pub fn bounding_box(&self) -> BoundingBox {
+        let (min_latitude, min_longitude, max_latitude, max_longitude) = match self {
+            City::SaintPetersburg => (59.63, 29.42, 60.25, 30.76),
+            City::Moscow => (55.14, 36.80, 56.02, 37.97),
+            City::Novosibirsk => (54.80, 82.70, 55.20, 83.20),
+            City::Yekaterinburg => (56.63, 60.35, 56.99, 60.85),
+            City::Kazan => (55.60, 48.80, 55.95, 49.40),
+            City::NizhnyNovgorod => (56.17, 43.70, 56.42, 44.15),
+        };
+
+        BoundingBox {
+            min_latitude,
+            min_longitude,
+            max_latitude,
+            max_longitude,
+        }
+    }
+
+    /// City which bounding box contains location, `None` for places outside of known cities
+    pub fn from_location(location: &Location) -> Option<City> {
+        CITIES
+            .into_iter()
+            .find(|city| city.bounding_box().contains(location))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_city_by_location() {
+        for city in CITIES {
+            assert_eq!(City::from_location(&city.center()), Some(city));
+        }
+        // Pulkovo airport is out of city limits, but belongs to its area
+        assert_eq!(
+            City::from_location(&Location::from_ll(59.8003, 30.2625)),
+            Some(City::SaintPetersburg)
+        );
+        // Tver is between Moscow and Saint-Petersburg
+        assert_eq!(
+            City::from_location(&Location::from_ll(56.8587, 35.9176)),
+            None
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Community of people who organize events together, owner manages members
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct Community {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub poster_image_link: Option<String>,
+    #[sqlx(rename = "private")]
+    pub is_private: bool,
+    /// Secret part of invite link, set by repository for private communities
+    #[serde(skip)]
+    pub invite_token: Option<String>,
+    pub telegram_channel_link: Option<String>,
+    /// Community has its own telegram chat
+    pub community_chat: bool,
+    pub category: String,
+    pub location: String,
+    pub owner_id: i64,
+    pub creation_time: NaiveDateTime,
+}
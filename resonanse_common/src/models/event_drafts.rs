@@ -0,0 +1,12 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// Unfinished event of account. Draft content is defined by client
+/// and stored as json, so it can be changed without migrations
+#[derive(Clone, Debug)]
+pub struct EventDraft<T> {
+    pub id: Uuid,
+    pub account_id: i64,
+    pub draft: T,
+    pub update_time: NaiveDateTime,
+}
@@ -0,0 +1,20 @@
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Clone, Copy, Debug, sqlx::Type, Eq, PartialEq)]
+#[repr(i32)]
+pub enum ParticipationStatus {
+    Going = 0,
+    /// Event is full, place is given when someone cancels participation
+    Waitlisted = 1,
+}
+
+#[derive(Clone, Debug, FromRow)]
+pub struct EventParticipant {
+    pub event_id: Uuid,
+    /// Account id, not telegram user id
+    pub user_id: i64,
+    pub status: ParticipationStatus,
+    pub registration_time: NaiveDateTime,
+}
@@ -1,28 +1,27 @@
 use std::collections::HashMap;
 
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use log::debug;
+use serde::{Deserialize, Serialize};
 use sqlx::postgres::PgRow;
 use sqlx::{FromRow, Row};
 use strum_macros;
 use uuid::Uuid;
 
-#[derive(Clone, Copy, Debug, sqlx::Type)]
+use crate::models::{City, EventRecurrence};
+
+#[derive(Clone, Copy, Debug, Default, sqlx::Type)]
 #[repr(i32)]
 pub enum EventType {
+    #[default]
     Unknown = 0,
     OfflineMeetup = 1,
     OneToOne = 2,
     Online = 3,
 }
 
-impl Default for EventType {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Location {
     pub latitude: f64,
     pub longitude: f64,
@@ -83,6 +82,8 @@ impl Location {
     PartialEq,
     strum_macros::EnumString,
     strum_macros::Display,
+    Serialize,
+    Deserialize,
 )]
 #[repr(i32)]
 pub enum EventSubject {
@@ -190,29 +191,27 @@ impl Default for EventSubjectFilter {
     Clone,
     Copy,
     Debug,
+    Default,
     sqlx::Type,
     Eq,
     Hash,
     PartialEq,
     strum_macros::EnumString,
     strum_macros::Display,
+    Serialize,
+    Deserialize,
 )]
 #[repr(i32)]
 /// Kind of resonanse event
 pub enum ResonanseEventKind {
     #[strum(serialize = "event_kind.announcement")]
     Announcement = 0,
+    #[default]
     #[strum(serialize = "event_kind.user_offer")]
     UserOffer = 1,
     // Private = 2,
 }
 
-impl Default for ResonanseEventKind {
-    fn default() -> Self {
-        Self::UserOffer
-    }
-}
-
 // impl MyI18N for ResonanseEventKind {
 //     fn to_text(&self) -> &'static str {
 //         match self {
@@ -277,13 +276,116 @@ impl Default for ResonanseEventKind {
 //     "Другое",
 // ];
 
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    sqlx::Type,
+    Eq,
+    Hash,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    Serialize,
+    Deserialize,
+)]
+#[repr(i32)]
+/// Where event takes place: at venue, online or both
+pub enum EventFormat {
+    #[default]
+    #[strum(serialize = "event_format.offline")]
+    Offline = 0,
+    #[strum(serialize = "event_format.online")]
+    Online = 1,
+    #[strum(serialize = "event_format.hybrid")]
+    Hybrid = 2,
+}
+
+impl EventFormat {
+    /// Event can be joined by meeting link
+    pub fn is_online(&self) -> bool {
+        !matches!(self, EventFormat::Offline)
+    }
+
+    /// Event has physical place
+    pub fn has_venue(&self) -> bool {
+        !matches!(self, EventFormat::Online)
+    }
+}
+
+#[derive(Clone)]
+pub struct EventFormatFilter {
+    pub formats: HashMap<EventFormat, bool>,
+    pub only_free: bool,
+}
+
+impl EventFormatFilter {
+    pub fn new() -> Self {
+        Self {
+            formats: HashMap::from([
+                (EventFormat::Offline, true),
+                (EventFormat::Online, true),
+                (EventFormat::Hybrid, true),
+            ]),
+            only_free: false,
+        }
+    }
+
+    pub fn switch(&mut self, event_format: EventFormat) {
+        if let Some(f) = self.formats.get_mut(&event_format) {
+            *f = !*f;
+        }
+    }
+
+    pub fn switch_only_free(&mut self) {
+        self.only_free = !self.only_free;
+    }
+}
+
+impl Default for EventFormatFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Price of paid event in minor units of currency (e.g. kopecks, cents)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventPrice {
+    pub amount: i64,
+    /// ISO 4217 code, e.g. "RUB"
+    pub currency: String,
+}
+
+/// Timezone of events and accounts that did not specify it
+pub const DEFAULT_TIMEZONE: Tz = chrono_tz::Europe::Moscow;
+
+/// Interprets wall-clock time in given timezone.
+/// For ambiguous time (DST fold) earliest instant is taken,
+/// non-existent time (DST gap) is moved forward by an hour
+pub fn localize_naive_datetime(naive_dt: NaiveDateTime, tz: Tz) -> DateTime<Tz> {
+    tz.from_local_datetime(&naive_dt)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(naive_dt + chrono::Duration::hours(1)))
+                .earliest()
+        })
+        .unwrap_or_else(|| tz.from_utc_datetime(&naive_dt))
+}
+
 #[derive(Clone, Debug)]
 pub struct BaseEvent {
     pub id: Uuid,
+    /// Private event is shown only to creator and accounts allowed by invite link
     pub is_private: bool,
+    /// Secret part of invite link, set by repository for private events
+    pub invite_token: Option<String>,
     pub is_commercial: bool,
     pub is_online: bool,
     pub is_paid: bool,
+    pub event_format: EventFormat,
+    /// Set only for paid events
+    pub price: Option<EventPrice>,
     pub event_kind: ResonanseEventKind,
     pub title: String,
     pub description: String,
@@ -292,41 +394,128 @@ pub struct BaseEvent {
     pub subject: EventSubject,
     pub datetime_from: NaiveDateTime,
     pub datetime_to: Option<NaiveDateTime>,
-    // pub timezone: chrono_tz::Tz,
+    /// Timezone of `datetime_from` and `datetime_to` wall-clock values
+    pub timezone: Tz,
     pub location: Option<Location>,
-    pub location_title: String,
+    /// Set for events with venue, derived from location or chosen by creator
+    pub city: Option<City>,
+    /// Empty for online events
+    pub location_title: Option<String>,
+    pub meeting_url: Option<String>,
+    /// Max number of participants, `None` means unlimited
+    pub capacity: Option<i32>,
     pub creator_id: i64,
+    /// Community which organizes event
+    pub community_id: Option<i64>,
     pub event_type: EventType,
     pub picture: Option<Uuid>,
     pub creation_time: NaiveDateTime,
     pub contact_info: Option<String>,
+    /// Stored in separate table, loaded by repository
+    pub recurrence: Option<EventRecurrence>,
+    /// Participants with confirmed place, loaded by repository
+    pub participants_count: i64,
+}
+
+impl BaseEvent {
+    /// Number of places left, `None` for events without capacity limit
+    pub fn free_places(&self) -> Option<i64> {
+        self.capacity
+            .map(|capacity| (capacity as i64 - self.participants_count).max(0))
+    }
+
+    pub fn datetime_from_instant(&self) -> DateTime<Tz> {
+        localize_naive_datetime(self.datetime_from, self.timezone)
+    }
+
+    pub fn datetime_to_instant(&self) -> Option<DateTime<Tz>> {
+        self.datetime_to
+            .map(|datetime_to| localize_naive_datetime(datetime_to, self.timezone))
+    }
+
+    /// Copy of event moved to another start time, duration is kept
+    pub fn occurrence(&self, occurrence_from: NaiveDateTime) -> BaseEvent {
+        let mut occurrence = self.clone();
+        occurrence.datetime_from = occurrence_from;
+        occurrence.datetime_to = self
+            .datetime_to
+            .map(|datetime_to| occurrence_from + (datetime_to - self.datetime_from));
+        occurrence
+    }
+
+    /// Occurrences that are not finished yet, at most `limit` of them.
+    /// Non-recurring event is its own single occurrence
+    pub fn upcoming_occurrences(&self, now: DateTime<Utc>, limit: usize) -> Vec<BaseEvent> {
+        let is_upcoming = |occurrence: &BaseEvent| {
+            occurrence
+                .datetime_to_instant()
+                .unwrap_or_else(|| occurrence.datetime_from_instant())
+                >= now
+        };
+
+        match self.recurrence.as_ref() {
+            None => Some(self.clone()).filter(is_upcoming).into_iter().collect(),
+            Some(recurrence) => recurrence
+                .occurrences(self.datetime_from)
+                .map(|occurrence_from| self.occurrence(occurrence_from))
+                .filter(is_upcoming)
+                .take(limit)
+                .collect(),
+        }
+    }
+}
+
+pub(crate) fn parse_timezone_column(row: &PgRow, column: &str) -> Result<Tz, sqlx::Error> {
+    let tz_name: String = row.try_get(column)?;
+    tz_name
+        .parse::<Tz>()
+        .map_err(|err| sqlx::Error::ColumnDecode {
+            index: column.to_string(),
+            source: err.into(),
+        })
 }
 
 impl FromRow<'_, PgRow> for BaseEvent {
     fn from_row(row: &PgRow) -> Result<Self, sqlx::error::Error> {
         Ok(Self {
-            id: row.try_get::<_, &str>("id")?,
-            is_private: row.try_get::<_, &str>("is_private")?,
-            is_commercial: row.try_get::<_, &str>("is_commercial")?,
-            is_online: row.try_get::<_, &str>("is_online")?,
-            is_paid: row.try_get::<_, &str>("is_paid")?,
-            event_kind: row.try_get::<_, &str>("event_kind")?,
-            title: row.try_get::<_, &str>("title")?,
-            description: row.try_get::<_, &str>("description")?,
-            brief_description: row.try_get::<_, &str>("brief_description")?,
-            subject: row.try_get::<_, &str>("subject")?,
-            datetime_from: row.try_get::<_, &str>("datetime_from")?,
-            datetime_to: row.try_get::<_, &str>("datetime_to")?,
+            id: row.try_get("id")?,
+            is_private: row.try_get("is_private")?,
+            invite_token: row.try_get("invite_token")?,
+            is_commercial: row.try_get("is_commercial")?,
+            is_online: row.try_get("is_online")?,
+            is_paid: row.try_get("is_paid")?,
+            event_format: row.try_get("event_format")?,
+            price: match (
+                row.try_get::<Option<i64>, _>("price_amount")?,
+                row.try_get::<Option<String>, _>("price_currency")?,
+            ) {
+                (Some(amount), Some(currency)) => Some(EventPrice { amount, currency }),
+                _ => None,
+            },
+            event_kind: row.try_get("event_kind")?,
+            title: row.try_get("title")?,
+            description: row.try_get("description")?,
+            brief_description: row.try_get("brief_description")?,
+            subject: row.try_get("subject")?,
+            datetime_from: row.try_get("datetime_from")?,
+            datetime_to: row.try_get("datetime_to")?,
+            timezone: parse_timezone_column(row, "timezone")?,
             location: Location::try_from_ll(
-                row.try_get::<_, &str>("location_latitude")?,
-                row.try_get::<_, &str>("location_longitude")?,
+                row.try_get("location_latitude")?,
+                row.try_get("location_longitude")?,
             ),
-            location_title: row.try_get::<_, &str>("location_title")?,
-            creator_id: row.try_get::<_, &str>("creator_id")?,
-            event_type: row.try_get::<_, &str>("event_type")?,
-            picture: row.try_get::<_, &str>("picture")?,
-            creation_time: row.try_get::<_, &str>("creation_time")?,
-            contact_info: row.try_get::<_, &str>("contact_info")?,
+            city: row.try_get("city")?,
+            location_title: row.try_get("location_title")?,
+            meeting_url: row.try_get("meeting_url")?,
+            capacity: row.try_get("capacity")?,
+            creator_id: row.try_get("creator_id")?,
+            community_id: row.try_get("community_id")?,
+            event_type: row.try_get("event_type")?,
+            picture: row.try_get("picture")?,
+            creation_time: row.try_get("creation_time")?,
+            contact_info: row.try_get("contact_info")?,
+            recurrence: None,
+            participants_count: 0,
         })
     }
 }
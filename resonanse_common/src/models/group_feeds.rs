@@ -0,0 +1,28 @@
+use chrono::NaiveDateTime;
+
+use crate::models::{City, EventSubject};
+
+/// Telegram group which gets new public events of followed subjects and cities
+#[derive(Clone, Debug)]
+pub struct GroupFeed {
+    pub chat_id: i64,
+    pub title: Option<String>,
+    pub subjects: Vec<EventSubject>,
+    pub cities: Vec<City>,
+    pub creation_time: NaiveDateTime,
+}
+
+impl GroupFeed {
+    pub fn is_following(&self, subject: EventSubject) -> bool {
+        self.subjects.contains(&subject)
+    }
+
+    pub fn is_following_city(&self, city: City) -> bool {
+        self.cities.contains(&city)
+    }
+
+    /// Online events have no city, they are posted to groups of any cities
+    pub fn is_following_event(&self, subject: EventSubject, city: Option<City>) -> bool {
+        self.is_following(subject) && city.is_none_or(|city| self.is_following_city(city))
+    }
+}
@@ -1,7 +1,19 @@
 pub use accounts::*;
+pub use cities::*;
+pub use communities::*;
+pub use event_drafts::*;
+pub use event_participants::*;
 pub use event_scores::*;
 pub use events::*;
+pub use group_feeds::*;
+pub use recurrence::*;
 
 mod accounts;
+mod cities;
+mod communities;
+mod event_drafts;
+mod event_participants;
 mod event_scores;
 mod events;
+mod group_feeds;
+mod recurrence;
@@ -0,0 +1,127 @@
+use chrono::{Duration, Months, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use strum_macros;
+
+/// Recurring event is never expanded to more occurrences than this
+pub const MAX_RECURRENCE_OCCURRENCES: u32 = 366;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    sqlx::Type,
+    Eq,
+    Hash,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    Serialize,
+    Deserialize,
+)]
+#[repr(i32)]
+pub enum RecurrenceFrequency {
+    #[strum(serialize = "recurrence.daily")]
+    Daily = 0,
+    #[strum(serialize = "recurrence.weekly")]
+    Weekly = 1,
+    #[strum(serialize = "recurrence.monthly")]
+    Monthly = 2,
+}
+
+/// Repetition rule of event, occurrences are counted from event's `datetime_from`
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct EventRecurrence {
+    pub frequency: RecurrenceFrequency,
+    /// Last day when event may occur, inclusive
+    pub until_date: Option<NaiveDate>,
+    /// Total number of occurrences including the first one
+    pub occurrences_count: Option<i32>,
+    /// Start times of occurrences cancelled by organizer
+    pub cancelled_occurrences: Vec<NaiveDateTime>,
+}
+
+impl EventRecurrence {
+    pub fn new(frequency: RecurrenceFrequency) -> Self {
+        Self {
+            frequency,
+            until_date: None,
+            occurrences_count: None,
+            cancelled_occurrences: Vec::new(),
+        }
+    }
+
+    /// Start of n-th occurrence, n = 0 is the first occurrence itself.
+    /// Monthly events on 29-31 days are moved to the last day of shorter months
+    pub fn nth_occurrence(&self, first_occurrence: NaiveDateTime, n: u32) -> Option<NaiveDateTime> {
+        if let Some(occurrences_count) = self.occurrences_count {
+            if n as i64 >= occurrences_count as i64 {
+                return None;
+            }
+        }
+
+        let occurrence = match self.frequency {
+            RecurrenceFrequency::Daily => {
+                first_occurrence.checked_add_signed(Duration::days(n as i64))?
+            }
+            RecurrenceFrequency::Weekly => {
+                first_occurrence.checked_add_signed(Duration::weeks(n as i64))?
+            }
+            RecurrenceFrequency::Monthly => first_occurrence.checked_add_months(Months::new(n))?,
+        };
+
+        match self.until_date {
+            Some(until_date) if occurrence.date() > until_date => None,
+            _ => Some(occurrence),
+        }
+    }
+
+    /// Starts of occurrences that are not cancelled
+    pub fn occurrences(
+        &self,
+        first_occurrence: NaiveDateTime,
+    ) -> impl Iterator<Item = NaiveDateTime> + '_ {
+        (0..MAX_RECURRENCE_OCCURRENCES)
+            .map_while(move |n| self.nth_occurrence(first_occurrence, n))
+            .filter(|occurrence| !self.is_cancelled(*occurrence))
+    }
+
+    pub fn is_cancelled(&self, occurrence: NaiveDateTime) -> bool {
+        self.cancelled_occurrences.contains(&occurrence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dt(y: i32, m: u32, d: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .and_then(|date| date.and_hms_opt(19, 0, 0))
+            .unwrap()
+    }
+
+    #[test]
+    fn expands_occurrences() {
+        let mut weekly = EventRecurrence::new(RecurrenceFrequency::Weekly);
+        weekly.until_date = NaiveDate::from_ymd_opt(2024, 1, 3);
+        weekly.cancelled_occurrences = vec![dt(2023, 12, 27)];
+        assert_eq!(
+            weekly.occurrences(dt(2023, 12, 13)).collect::<Vec<_>>(),
+            vec![dt(2023, 12, 13), dt(2023, 12, 20), dt(2024, 1, 3)]
+        );
+
+        let mut monthly = EventRecurrence::new(RecurrenceFrequency::Monthly);
+        monthly.occurrences_count = Some(3);
+        assert_eq!(
+            monthly.occurrences(dt(2024, 1, 31)).collect::<Vec<_>>(),
+            vec![dt(2024, 1, 31), dt(2024, 2, 29), dt(2024, 3, 31)]
+        );
+
+        let daily = EventRecurrence::new(RecurrenceFrequency::Daily);
+        assert_eq!(
+            daily.occurrences(dt(2023, 12, 13)).count(),
+            MAX_RECURRENCE_OCCURRENCES as usize
+        );
+    }
+}
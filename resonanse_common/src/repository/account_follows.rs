@@ -0,0 +1,114 @@
+use async_trait::async_trait;
+use log::debug;
+use sqlx::{PgPool, Result};
+
+use crate::metrics::ObserveQuery;
+use crate::models::BaseAccount;
+
+#[async_trait]
+pub trait AccountFollowsRepository: Send + Sync {
+    async fn follow(&self, follower_id: i64, organizer_id: i64) -> Result<()>;
+
+    async fn unfollow(&self, follower_id: i64, organizer_id: i64) -> Result<()>;
+
+    async fn is_following(&self, follower_id: i64, organizer_id: i64) -> Result<bool>;
+
+    /// Organizers in order of following
+    async fn get_followed_organizers(&self, follower_id: i64) -> Result<Vec<BaseAccount>>;
+
+    /// Telegram ids of organizer followers, to notify them about new events
+    async fn get_follower_tg_ids(&self, organizer_id: i64) -> Result<Vec<i64>>;
+}
+
+#[derive(Debug)]
+pub struct PgAccountFollowsRepository {
+    db_pool: PgPool,
+}
+
+impl PgAccountFollowsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { db_pool: pool }
+    }
+}
+
+#[async_trait]
+impl AccountFollowsRepository for PgAccountFollowsRepository {
+    async fn follow(&self, follower_id: i64, organizer_id: i64) -> Result<()> {
+        let result = sqlx::query(
+            r#"insert into account_follows
+            (follower_id, organizer_id)
+            values ($1, $2)
+            on conflict do nothing
+            "#,
+        )
+        .bind(follower_id)
+        .bind(organizer_id)
+        .execute(&self.db_pool)
+        .observe("account_follows.follow")
+        .await?;
+        debug!("account_follows result {:?}", result);
+
+        Ok(())
+    }
+
+    async fn unfollow(&self, follower_id: i64, organizer_id: i64) -> Result<()> {
+        let result = sqlx::query(
+            r#"delete from account_follows
+            where follower_id = $1 and organizer_id = $2
+            "#,
+        )
+        .bind(follower_id)
+        .bind(organizer_id)
+        .execute(&self.db_pool)
+        .observe("account_follows.unfollow")
+        .await?;
+        debug!("account_follows result {:?}", result);
+
+        Ok(())
+    }
+
+    async fn is_following(&self, follower_id: i64, organizer_id: i64) -> Result<bool> {
+        sqlx::query_scalar(
+            r#"select exists (
+                select 1
+                from account_follows
+                where follower_id = $1 and organizer_id = $2
+            )
+            "#,
+        )
+        .bind(follower_id)
+        .bind(organizer_id)
+        .fetch_one(&self.db_pool)
+        .observe("account_follows.is_following")
+        .await
+    }
+
+    async fn get_followed_organizers(&self, follower_id: i64) -> Result<Vec<BaseAccount>> {
+        sqlx::query_as(
+            r#"select a.*
+            from user_accounts a
+            join account_follows f on f.organizer_id = a.id
+            where f.follower_id = $1
+            order by f.follow_time
+            "#,
+        )
+        .bind(follower_id)
+        .fetch_all(&self.db_pool)
+        .observe("account_follows.get_followed_organizers")
+        .await
+    }
+
+    async fn get_follower_tg_ids(&self, organizer_id: i64) -> Result<Vec<i64>> {
+        sqlx::query_scalar(
+            r#"select a.tg_user_id
+            from user_accounts a
+            join account_follows f on f.follower_id = a.id
+            where f.organizer_id = $1 and a.tg_user_id is not null
+            "#,
+        )
+        .bind(organizer_id)
+        .fetch_all(&self.db_pool)
+        .observe("account_follows.get_follower_tg_ids")
+        .await
+    }
+}
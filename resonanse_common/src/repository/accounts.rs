@@ -1,19 +1,14 @@
-use crate::models::BaseAccount;
+use crate::metrics::ObserveQuery;
+use crate::models::{BaseAccount, City, UserData};
+use async_trait::async_trait;
+use chrono_tz::Tz;
 use log::debug;
-use sqlx::{PgPool, Result, Row};
-
-#[derive(Debug)]
-pub struct AccountsRepository {
-    db_pool: PgPool,
-}
-
-impl AccountsRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { db_pool: pool }
-    }
+use sqlx::{PgPool, Result};
 
+#[async_trait]
+pub trait AccountsRepository: Send + Sync {
     /// Creates new user if there are no user with given tg_user_id
-    pub async fn create_user_by_tg_user_id(&self, account: BaseAccount) -> Result<BaseAccount> {
+    async fn create_user_by_tg_user_id(&self, account: BaseAccount) -> Result<BaseAccount> {
         if let Some(tg_user_id) = account.contact_data.telegram.user_id {
             match self.get_user_by_tg_id(tg_user_id).await {
                 Err(sqlx::error::Error::RowNotFound) => {
@@ -33,7 +28,41 @@ impl AccountsRepository {
         self.create_user(account).await
     }
 
-    pub async fn get_user_by_tg_id(&self, tg_user_id: i64) -> Result<BaseAccount> {
+    async fn get_user_by_tg_id(&self, tg_user_id: i64) -> Result<BaseAccount>;
+
+    async fn get_account_id_by_tg_user_id(&self, tg_user_id: i64) -> Result<i64>;
+
+    async fn create_user(&self, account: BaseAccount) -> Result<BaseAccount>;
+
+    /// Updates profile data of account, timezone is set separately
+    async fn update_user_data(&self, account_id: i64, user_data: &UserData) -> Result<()>;
+
+    async fn set_user_timezone(&self, account_id: i64, timezone: Tz) -> Result<()>;
+
+    async fn set_user_city(&self, account_id: i64, city: City) -> Result<()>;
+
+    async fn set_user_locale(&self, account_id: i64, locale: &str) -> Result<()>;
+
+    /// Locale chosen by user, `None` if user has no account or has not chosen it
+    async fn get_user_locale_by_tg_id(&self, tg_user_id: i64) -> Result<Option<String>>;
+
+    async fn count_accounts(&self) -> Result<i64>;
+}
+
+#[derive(Debug)]
+pub struct PgAccountsRepository {
+    db_pool: PgPool,
+}
+
+impl PgAccountsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { db_pool: pool }
+    }
+}
+
+#[async_trait]
+impl AccountsRepository for PgAccountsRepository {
+    async fn get_user_by_tg_id(&self, tg_user_id: i64) -> Result<BaseAccount> {
         let account: BaseAccount = sqlx::query_as(
             r#"select * from user_accounts
                 where tg_user_id=$1
@@ -41,14 +70,15 @@ impl AccountsRepository {
         )
         .bind(tg_user_id)
         .fetch_one(&self.db_pool)
+        .observe("accounts.get_user_by_tg_id")
         .await?;
 
         Ok(account)
     }
 
-    pub async fn get_account_id_by_tg_user_id(&self, tg_user_id: i64) -> Result<i64> {
+    async fn get_account_id_by_tg_user_id(&self, tg_user_id: i64) -> Result<i64> {
         debug!("searching account_id by tg_user_id {}", tg_user_id);
-        let account_id: Result<i64> = sqlx::query(
+        let account_id: Result<i64> = sqlx::query_scalar(
             r#"
             select id from user_accounts
             where tg_user_id=$1
@@ -56,15 +86,15 @@ impl AccountsRepository {
         )
         .bind(tg_user_id)
         .fetch_one(&self.db_pool)
-        .await?
-        .try_get::<_, usize>(0);
+        .observe("accounts.get_account_id_by_tg_user_id")
+        .await;
 
         debug!("account_id {:?}", account_id);
 
         account_id
     }
 
-    pub async fn create_user(&self, account: BaseAccount) -> Result<BaseAccount> {
+    async fn create_user(&self, account: BaseAccount) -> Result<BaseAccount> {
         let created_account: BaseAccount = sqlx::query_as(
             r#"insert into user_accounts
             (
@@ -73,7 +103,7 @@ impl AccountsRepository {
             hobby, music, sport, books, food, worldview, alcohol,
             email, phone, tg_username, tg_user_id, instagram,
             password_hash,
-            user_type
+            user_type, timezone, locale
             )
             values (
             $1, $2, $3, $4, $5,
@@ -81,7 +111,7 @@ impl AccountsRepository {
             $12, $13, $14, $15, $16, $17, $18,
             $19, $20, $21, $22, $23,
             $24,
-            $25
+            $25, $26, $27
             )
             returning *
             "#,
@@ -111,7 +141,10 @@ impl AccountsRepository {
         .bind(account.contact_data.instagram)
         .bind(account.auth_data.password_hash)
         .bind(account.user_type)
+        .bind(account.user_data.timezone.map(|tz| tz.name()))
+        .bind(account.user_data.locale)
         .fetch_one(&self.db_pool)
+        .observe("accounts.create_user")
         .await?;
 
         // debug!("inserted account: {:?}", created_account.get::<i64, usize>(0));
@@ -139,15 +172,122 @@ impl AccountsRepository {
         Ok(created_account)
     }
 
-    pub async fn count_accounts(&self) -> Result<i64> {
+    async fn update_user_data(&self, account_id: i64, user_data: &UserData) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            update user_accounts
+            set first_name=$1, last_name=$2, city=$3, about=$4,
+            headline=$5, goals=$6, interests=$7, language=$8, age=$9, education=$10,
+            hobby=$11, music=$12, sport=$13, books=$14, food=$15, worldview=$16, alcohol=$17
+            where id=$18
+            "#,
+        )
+        .bind(&user_data.first_name)
+        .bind(&user_data.last_name)
+        .bind(user_data.city)
+        .bind(&user_data.about)
+        .bind(&user_data.headline)
+        .bind(&user_data.goals)
+        .bind(&user_data.interests)
+        .bind(&user_data.language)
+        .bind(user_data.age)
+        .bind(&user_data.education)
+        .bind(&user_data.hobby)
+        .bind(&user_data.music)
+        .bind(&user_data.sport)
+        .bind(&user_data.books)
+        .bind(&user_data.food)
+        .bind(&user_data.worldview)
+        .bind(&user_data.alcohol)
+        .bind(account_id)
+        .execute(&self.db_pool)
+        .observe("accounts.update_user_data")
+        .await?;
+        debug!("update_user_data result {:?}", result);
+
+        Ok(())
+    }
+
+    async fn set_user_timezone(&self, account_id: i64, timezone: Tz) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            update user_accounts
+            set timezone=$1
+            where id=$2
+            "#,
+        )
+        .bind(timezone.name())
+        .bind(account_id)
+        .execute(&self.db_pool)
+        .observe("accounts.set_user_timezone")
+        .await?;
+        debug!("set_user_timezone result {:?}", result);
+
+        Ok(())
+    }
+
+    async fn set_user_city(&self, account_id: i64, city: City) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            update user_accounts
+            set city=$1
+            where id=$2
+            "#,
+        )
+        .bind(city)
+        .bind(account_id)
+        .execute(&self.db_pool)
+        .observe("accounts.set_user_city")
+        .await?;
+        debug!("set_user_city result {:?}", result);
+
+        Ok(())
+    }
+
+    async fn set_user_locale(&self, account_id: i64, locale: &str) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            update user_accounts
+            set locale=$1
+            where id=$2
+            "#,
+        )
+        .bind(locale)
+        .bind(account_id)
+        .execute(&self.db_pool)
+        .observe("accounts.set_user_locale")
+        .await?;
+        debug!("set_user_locale result {:?}", result);
+
+        Ok(())
+    }
+
+    async fn get_user_locale_by_tg_id(&self, tg_user_id: i64) -> Result<Option<String>> {
+        let locale: Option<Option<String>> = sqlx::query_scalar(
+            r#"
+            select locale from user_accounts
+            where tg_user_id=$1
+            "#,
+        )
+        .bind(tg_user_id)
+        .fetch_optional(&self.db_pool)
+        .observe("accounts.get_user_locale_by_tg_id")
+        .await?;
+
+        Ok(locale.flatten())
+    }
+
+    async fn count_accounts(&self) -> Result<i64> {
         debug!("count_account");
-        sqlx::query(
+        let count = sqlx::query_scalar(
             r#"
             select count(*) from user_accounts
             "#,
         )
         .fetch_one(&self.db_pool)
-        .await?
-        .try_get::<_, usize>(0)
+        .observe("accounts.count_accounts")
+        .await?;
+
+        Ok(count)
     }
 }
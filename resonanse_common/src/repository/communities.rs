@@ -0,0 +1,249 @@
+use async_trait::async_trait;
+use log::debug;
+use sqlx::{PgPool, Result};
+use uuid::Uuid;
+
+use crate::metrics::ObserveQuery;
+use crate::models::{BaseAccount, Community};
+
+#[async_trait]
+pub trait CommunitiesRepository: Send + Sync {
+    /// Creates community, owner becomes its first member
+    async fn create_community(&self, community: Community) -> Result<Community>;
+
+    async fn get_community(&self, community_id: i64) -> Result<Community>;
+
+    async fn get_community_by_invite_token(&self, invite_token: &str) -> Result<Community>;
+
+    async fn get_public_communities(&self, page: i64, page_size: i64) -> Result<Vec<Community>>;
+
+    /// Communities account is member of, including owned ones
+    async fn get_account_communities(&self, account_id: i64) -> Result<Vec<Community>>;
+
+    async fn get_owned_communities(&self, owner_id: i64) -> Result<Vec<Community>>;
+
+    async fn add_member(&self, community_id: i64, account_id: i64) -> Result<()>;
+
+    /// Owner cannot be removed from own community
+    async fn remove_member(&self, community_id: i64, account_id: i64) -> Result<()>;
+
+    async fn is_member(&self, community_id: i64, account_id: i64) -> Result<bool>;
+
+    /// Members in order of joining, owner goes first
+    async fn get_members(&self, community_id: i64) -> Result<Vec<BaseAccount>>;
+
+    async fn count_members(&self, community_id: i64) -> Result<i64>;
+}
+
+#[derive(Debug)]
+pub struct PgCommunitiesRepository {
+    db_pool: PgPool,
+}
+
+impl PgCommunitiesRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { db_pool: pool }
+    }
+}
+
+#[async_trait]
+impl CommunitiesRepository for PgCommunitiesRepository {
+    async fn create_community(&self, community: Community) -> Result<Community> {
+        // random uuid is unguessable enough to be used as access secret
+        let invite_token = community
+            .is_private
+            .then(|| Uuid::new_v4().simple().to_string());
+
+        let mut tx = self.db_pool.begin().await?;
+        let created_community: Community = sqlx::query_as(
+            r#"insert into communities
+            (
+            name, description, poster_image_link, private, invite_token, telegram_channel_link,
+            community_chat, category, location, owner_id
+            )
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            returning *
+            "#,
+        )
+        .bind(community.name)
+        .bind(community.description)
+        .bind(community.poster_image_link)
+        .bind(community.is_private)
+        .bind(invite_token)
+        .bind(community.telegram_channel_link)
+        .bind(community.community_chat)
+        .bind(community.category)
+        .bind(community.location)
+        .bind(community.owner_id)
+        .fetch_one(&mut *tx)
+        .observe("communities.create_community")
+        .await?;
+
+        sqlx::query(
+            r#"insert into community_members
+            (community_id, account_id)
+            values ($1, $2)
+            "#,
+        )
+        .bind(created_community.id)
+        .bind(created_community.owner_id)
+        .execute(&mut *tx)
+        .observe("communities.create_community")
+        .await?;
+
+        tx.commit().await?;
+        Ok(created_community)
+    }
+
+    async fn get_community(&self, community_id: i64) -> Result<Community> {
+        sqlx::query_as(
+            r#"select *
+            from communities
+            where id = $1
+            "#,
+        )
+        .bind(community_id)
+        .fetch_one(&self.db_pool)
+        .observe("communities.get_community")
+        .await
+    }
+
+    async fn get_community_by_invite_token(&self, invite_token: &str) -> Result<Community> {
+        sqlx::query_as(
+            r#"select *
+            from communities
+            where invite_token = $1
+            "#,
+        )
+        .bind(invite_token)
+        .fetch_one(&self.db_pool)
+        .observe("communities.get_community_by_invite_token")
+        .await
+    }
+
+    async fn get_public_communities(&self, page: i64, page_size: i64) -> Result<Vec<Community>> {
+        sqlx::query_as(
+            r#"select *
+            from communities
+            where private = false
+            order by name
+            offset $1
+            limit $2
+            "#,
+        )
+        .bind(page * page_size)
+        .bind(page_size)
+        .fetch_all(&self.db_pool)
+        .observe("communities.get_public_communities")
+        .await
+    }
+
+    async fn get_account_communities(&self, account_id: i64) -> Result<Vec<Community>> {
+        sqlx::query_as(
+            r#"select c.*
+            from communities c
+            join community_members m on m.community_id = c.id
+            where m.account_id = $1
+            order by c.name
+            "#,
+        )
+        .bind(account_id)
+        .fetch_all(&self.db_pool)
+        .observe("communities.get_account_communities")
+        .await
+    }
+
+    async fn get_owned_communities(&self, owner_id: i64) -> Result<Vec<Community>> {
+        sqlx::query_as(
+            r#"select *
+            from communities
+            where owner_id = $1
+            order by name
+            "#,
+        )
+        .bind(owner_id)
+        .fetch_all(&self.db_pool)
+        .observe("communities.get_owned_communities")
+        .await
+    }
+
+    async fn add_member(&self, community_id: i64, account_id: i64) -> Result<()> {
+        let result = sqlx::query(
+            r#"insert into community_members
+            (community_id, account_id)
+            values ($1, $2)
+            on conflict do nothing
+            "#,
+        )
+        .bind(community_id)
+        .bind(account_id)
+        .execute(&self.db_pool)
+        .observe("communities.add_member")
+        .await?;
+        debug!("community_members result {:?}", result);
+
+        Ok(())
+    }
+
+    async fn remove_member(&self, community_id: i64, account_id: i64) -> Result<()> {
+        let result = sqlx::query(
+            r#"delete from community_members m
+            using communities c
+            where m.community_id = $1 and m.account_id = $2
+            and c.id = m.community_id and c.owner_id <> m.account_id
+            "#,
+        )
+        .bind(community_id)
+        .bind(account_id)
+        .execute(&self.db_pool)
+        .observe("communities.remove_member")
+        .await?;
+        debug!("community_members result {:?}", result);
+
+        Ok(())
+    }
+
+    async fn is_member(&self, community_id: i64, account_id: i64) -> Result<bool> {
+        sqlx::query_scalar(
+            r#"select exists (
+                select 1
+                from community_members
+                where community_id = $1 and account_id = $2
+            )
+            "#,
+        )
+        .bind(community_id)
+        .bind(account_id)
+        .fetch_one(&self.db_pool)
+        .observe("communities.is_member")
+        .await
+    }
+
+    async fn get_members(&self, community_id: i64) -> Result<Vec<BaseAccount>> {
+        sqlx::query_as(
+            r#"select a.*
+            from user_accounts a
+            join community_members m on m.account_id = a.id
+            where m.community_id = $1
+            order by m.join_time
+            "#,
+        )
+        .bind(community_id)
+        .fetch_all(&self.db_pool)
+        .observe("communities.get_members")
+        .await
+    }
+
+    async fn count_members(&self, community_id: i64) -> Result<i64> {
+        sqlx::query_scalar(
+            r#"select count(*)
+            from community_members
+            where community_id = $1
+            "#,
+        )
+        .bind(community_id)
+        .fetch_one(&self.db_pool)
+        .observe("communities.count_members")
+        .await
+    }
+}
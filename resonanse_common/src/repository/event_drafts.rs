@@ -0,0 +1,185 @@
+use async_trait::async_trait;
+use chrono::Duration;
+use log::debug;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::types::Json;
+use sqlx::{Error, PgPool, Result, Row};
+use uuid::Uuid;
+
+use crate::metrics::ObserveQuery;
+use crate::models::EventDraft;
+
+/// Drafts are stored as json, typed access is provided by methods of `dyn EventDraftsRepository`
+#[async_trait]
+pub trait EventDraftsRepository: Send + Sync {
+    /// Creates draft or replaces content of existing one
+    async fn save_draft_json(&self, draft_id: Uuid, account_id: i64, draft: Value) -> Result<()>;
+
+    /// Not expired drafts of account, recently updated first
+    async fn get_account_drafts_json(&self, account_id: i64) -> Result<Vec<EventDraft<Value>>>;
+
+    async fn get_draft_json(&self, draft_id: Uuid, account_id: i64) -> Result<Value>;
+
+    async fn delete_draft(&self, draft_id: Uuid) -> Result<()>;
+
+    /// Returns number of deleted drafts
+    async fn delete_expired_drafts(&self) -> Result<u64>;
+}
+
+impl<'a> dyn EventDraftsRepository + 'a {
+    pub async fn save_draft<T: Serialize + Sync>(
+        &self,
+        draft_id: Uuid,
+        account_id: i64,
+        draft: &T,
+    ) -> Result<()> {
+        let draft = serde_json::to_value(draft).map_err(|err| Error::Protocol(err.to_string()))?;
+        self.save_draft_json(draft_id, account_id, draft).await
+    }
+
+    /// Drafts that cannot be parsed (e.g. saved by older version) are skipped
+    pub async fn get_account_drafts<T: DeserializeOwned>(
+        &self,
+        account_id: i64,
+    ) -> Result<Vec<EventDraft<T>>> {
+        let mut drafts = Vec::new();
+        for draft in self.get_account_drafts_json(account_id).await? {
+            match serde_json::from_value(draft.draft) {
+                Ok(content) => drafts.push(EventDraft {
+                    id: draft.id,
+                    account_id: draft.account_id,
+                    draft: content,
+                    update_time: draft.update_time,
+                }),
+                Err(err) => debug!("cannot parse event draft: {:?}", err),
+            }
+        }
+
+        Ok(drafts)
+    }
+
+    pub async fn get_draft<T: DeserializeOwned>(
+        &self,
+        draft_id: Uuid,
+        account_id: i64,
+    ) -> Result<T> {
+        let draft = self.get_draft_json(draft_id, account_id).await?;
+        serde_json::from_value(draft).map_err(|err| Error::Decode(Box::new(err)))
+    }
+}
+
+#[derive(Debug)]
+pub struct PgEventDraftsRepository {
+    db_pool: PgPool,
+    /// Drafts not updated during this period are considered abandoned
+    draft_ttl: Duration,
+}
+
+impl PgEventDraftsRepository {
+    pub fn new(pool: PgPool, draft_ttl: Duration) -> Self {
+        Self {
+            db_pool: pool,
+            draft_ttl,
+        }
+    }
+}
+
+#[async_trait]
+impl EventDraftsRepository for PgEventDraftsRepository {
+    async fn save_draft_json(&self, draft_id: Uuid, account_id: i64, draft: Value) -> Result<()> {
+        let result = sqlx::query(
+            r#"insert into event_drafts
+            (id, account_id, draft, update_time)
+            values ($1, $2, $3, now())
+            on conflict (id) do update
+            set draft = excluded.draft, update_time = excluded.update_time
+            where event_drafts.account_id = excluded.account_id
+            "#,
+        )
+        .bind(draft_id)
+        .bind(account_id)
+        .bind(Json(draft))
+        .execute(&self.db_pool)
+        .observe("event_drafts.save_draft")
+        .await?;
+        debug!("event_drafts result {:?}", result);
+
+        Ok(())
+    }
+
+    async fn get_account_drafts_json(&self, account_id: i64) -> Result<Vec<EventDraft<Value>>> {
+        let rows = sqlx::query(
+            r#"select id, account_id, draft, update_time
+            from event_drafts
+            where account_id = $1 and update_time > now() - $2::interval
+            order by update_time desc
+            "#,
+        )
+        .bind(account_id)
+        .bind(self.draft_ttl)
+        .fetch_all(&self.db_pool)
+        .observe("event_drafts.get_account_drafts")
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let Json(draft) = row.try_get("draft")?;
+                Ok(EventDraft {
+                    id: row.try_get("id")?,
+                    account_id: row.try_get("account_id")?,
+                    draft,
+                    update_time: row.try_get("update_time")?,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_draft_json(&self, draft_id: Uuid, account_id: i64) -> Result<Value> {
+        let row = sqlx::query(
+            r#"select draft
+            from event_drafts
+            where id = $1 and account_id = $2 and update_time > now() - $3::interval
+            "#,
+        )
+        .bind(draft_id)
+        .bind(account_id)
+        .bind(self.draft_ttl)
+        .fetch_one(&self.db_pool)
+        .observe("event_drafts.get_draft")
+        .await?;
+        let Json(draft) = row.try_get("draft")?;
+
+        Ok(draft)
+    }
+
+    async fn delete_draft(&self, draft_id: Uuid) -> Result<()> {
+        let result = sqlx::query(
+            r#"delete from event_drafts
+            where id = $1
+            "#,
+        )
+        .bind(draft_id)
+        .execute(&self.db_pool)
+        .observe("event_drafts.delete_draft")
+        .await?;
+        debug!("delete event_drafts result {:?}", result);
+
+        Ok(())
+    }
+
+    async fn delete_expired_drafts(&self) -> Result<u64> {
+        let result = sqlx::query(
+            r#"delete from event_drafts
+            where update_time <= now() - $1::interval
+            "#,
+        )
+        .bind(self.draft_ttl)
+        .execute(&self.db_pool)
+        .observe("event_drafts.delete_expired_drafts")
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
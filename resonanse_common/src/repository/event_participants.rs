@@ -0,0 +1,191 @@
+use async_trait::async_trait;
+use log::debug;
+use sqlx::{PgPool, Postgres, Result, Transaction};
+use uuid::Uuid;
+
+use crate::metrics::ObserveQuery;
+use crate::models::ParticipationStatus;
+
+#[async_trait]
+pub trait EventParticipantsRepository: Send + Sync {
+    async fn get_participation_status(
+        &self,
+        event_id: Uuid,
+        user_id: i64,
+    ) -> Result<Option<ParticipationStatus>>;
+
+    /// Registers user to event, user gets to waitlist when there are no free places.
+    /// Status of already registered user is not changed
+    async fn join_event(&self, event_id: Uuid, user_id: i64) -> Result<ParticipationStatus>;
+
+    /// Removes user from participants or waitlist.
+    /// Freed place is given to the earliest waitlisted user,
+    /// telegram user id of promoted user is returned to notify them
+    async fn leave_event(&self, event_id: Uuid, user_id: i64) -> Result<Option<i64>>;
+}
+
+#[derive(Debug)]
+pub struct PgEventParticipantsRepository {
+    db_pool: PgPool,
+}
+
+impl PgEventParticipantsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { db_pool: pool }
+    }
+
+    /// Locks event row until end of transaction, so places are not given twice
+    async fn lock_event_capacity(
+        tx: &mut Transaction<'_, Postgres>,
+        event_id: Uuid,
+    ) -> Result<Option<i32>> {
+        sqlx::query_scalar(
+            r#"select capacity
+            from resonanse_events
+            where id = $1
+            for update
+            "#,
+        )
+        .bind(event_id)
+        .fetch_one(&mut **tx)
+        .observe("event_participants.lock_event_capacity")
+        .await
+    }
+
+    async fn count_going_participants(
+        tx: &mut Transaction<'_, Postgres>,
+        event_id: Uuid,
+    ) -> Result<i64> {
+        sqlx::query_scalar(
+            r#"select count(*)
+            from event_participants
+            where event_id = $1 and status = $2
+            "#,
+        )
+        .bind(event_id)
+        .bind(ParticipationStatus::Going)
+        .fetch_one(&mut **tx)
+        .observe("event_participants.count_going_participants")
+        .await
+    }
+}
+
+#[async_trait]
+impl EventParticipantsRepository for PgEventParticipantsRepository {
+    async fn get_participation_status(
+        &self,
+        event_id: Uuid,
+        user_id: i64,
+    ) -> Result<Option<ParticipationStatus>> {
+        sqlx::query_scalar(
+            r#"select status
+            from event_participants
+            where event_id = $1 and user_id = $2
+            "#,
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .fetch_optional(&self.db_pool)
+        .observe("event_participants.get_participation_status")
+        .await
+    }
+
+    async fn join_event(&self, event_id: Uuid, user_id: i64) -> Result<ParticipationStatus> {
+        let mut tx = self.db_pool.begin().await?;
+        let capacity = Self::lock_event_capacity(&mut tx, event_id).await?;
+
+        let current_status: Option<ParticipationStatus> = sqlx::query_scalar(
+            r#"select status
+            from event_participants
+            where event_id = $1 and user_id = $2
+            "#,
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .observe("event_participants.join_event")
+        .await?;
+        if let Some(current_status) = current_status {
+            return Ok(current_status);
+        }
+
+        let going_count = Self::count_going_participants(&mut tx, event_id).await?;
+        let status = match capacity {
+            Some(capacity) if going_count >= capacity as i64 => ParticipationStatus::Waitlisted,
+            _ => ParticipationStatus::Going,
+        };
+
+        let result = sqlx::query(
+            r#"insert into event_participants
+            (event_id, user_id, status)
+            values ($1, $2, $3)
+            "#,
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .bind(status)
+        .execute(&mut *tx)
+        .observe("event_participants.join_event")
+        .await?;
+        debug!("event_participants result {:?}", result);
+
+        tx.commit().await?;
+        Ok(status)
+    }
+
+    async fn leave_event(&self, event_id: Uuid, user_id: i64) -> Result<Option<i64>> {
+        let mut tx = self.db_pool.begin().await?;
+        let capacity = Self::lock_event_capacity(&mut tx, event_id).await?;
+
+        let left_status: Option<ParticipationStatus> = sqlx::query_scalar(
+            r#"delete from event_participants
+            where event_id = $1 and user_id = $2
+            returning status
+            "#,
+        )
+        .bind(event_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .observe("event_participants.leave_event")
+        .await?;
+
+        let has_free_place = match capacity {
+            Some(capacity) => {
+                Self::count_going_participants(&mut tx, event_id).await? < capacity as i64
+            }
+            None => true,
+        };
+        if left_status != Some(ParticipationStatus::Going) || !has_free_place {
+            tx.commit().await?;
+            return Ok(None);
+        }
+
+        let promoted_tg_user_id: Option<Option<i64>> = sqlx::query_scalar(
+            r#"with promoted as (
+                update event_participants
+                set status = $2
+                where event_id = $1 and user_id = (
+                    select w.user_id
+                    from event_participants w
+                    where w.event_id = $1 and w.status = $3
+                    order by w.registration_time
+                    limit 1
+                )
+                returning user_id
+            )
+            select a.tg_user_id
+            from promoted p
+            join user_accounts a on a.id = p.user_id
+            "#,
+        )
+        .bind(event_id)
+        .bind(ParticipationStatus::Going)
+        .bind(ParticipationStatus::Waitlisted)
+        .fetch_optional(&mut *tx)
+        .observe("event_participants.leave_event")
+        .await?;
+
+        tx.commit().await?;
+        Ok(promoted_tg_user_id.flatten())
+    }
+}
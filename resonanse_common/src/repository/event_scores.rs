@@ -1,17 +1,36 @@
+use crate::metrics::ObserveQuery;
 use crate::models::{EventScore, EventScoreType};
+use async_trait::async_trait;
 use sqlx::{PgPool, Result};
 use uuid::Uuid;
 
+#[async_trait]
+pub trait EventScoresRepository: Send + Sync {
+    /// Score of user replaces previous one for the same event
+    async fn set_event_score_by_user(
+        &self,
+        event_id: Uuid,
+        user_id: i64,
+        score: EventScoreType,
+    ) -> Result<EventScore>;
+
+    async fn get_event_scores_by_user(&self, user_id: i64) -> Result<Vec<EventScore>>;
+}
+
 #[derive(Debug)]
-pub struct EventScoresRepository {
+pub struct PgEventScoresRepository {
     db_pool: PgPool,
 }
 
-impl EventScoresRepository {
+impl PgEventScoresRepository {
     pub fn new(pool: PgPool) -> Self {
         Self { db_pool: pool }
     }
-    pub async fn set_event_score_by_user(
+}
+
+#[async_trait]
+impl EventScoresRepository for PgEventScoresRepository {
+    async fn set_event_score_by_user(
         &self,
         event_id: Uuid,
         user_id: i64,
@@ -30,17 +49,15 @@ impl EventScoresRepository {
         )
         .bind(event_id)
         .bind(user_id)
-        .bind(score as i64)
+        .bind(score)
         .fetch_one(&self.db_pool)
+        .observe("event_scores.set_event_score_by_user")
         .await?;
 
         Ok(event_score)
     }
 
-    pub async fn get_event_scores_by_user(
-        &self,
-        user_id: i64,
-    ) -> Result<Vec<EventScore>, sqlx::Error> {
+    async fn get_event_scores_by_user(&self, user_id: i64) -> Result<Vec<EventScore>> {
         let event_scores: Vec<EventScore> = sqlx::query_as(
             r#"
                 SELECT user_id, event_id, event_score
@@ -50,6 +67,7 @@ impl EventScoresRepository {
         )
         .bind(user_id)
         .fetch_all(&self.db_pool)
+        .observe("event_scores.get_event_scores_by_user")
         .await?;
 
         Ok(event_scores)
@@ -1,11 +1,19 @@
 use std::collections::HashMap;
 
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
 use log::debug;
-use sqlx::{PgPool, Result};
+use sqlx::{FromRow, PgPool, Postgres, Result, Row, Transaction};
 use uuid::Uuid;
 
-use crate::models::{BaseEvent, EventSubject};
-use crate::EventSubjectFilter;
+use crate::metrics::ObserveQuery;
+use crate::models::{
+    BaseEvent, City, EventRecurrence, EventSubject, ParticipationStatus, MAX_RECURRENCE_OCCURRENCES,
+};
+use crate::{EventFormatFilter, EventSubjectFilter};
+
+/// Recurring event takes at most this number of rows in events listing
+pub(super) const LISTED_OCCURRENCES_LIMIT: usize = 4;
 
 // #[derive(Clone)]
 // pub struct CreateBaseEvent {
@@ -27,34 +35,246 @@ use crate::EventSubjectFilter;
 //     pub contact_info: Option<String>,
 // }
 
+#[async_trait]
+pub trait EventsRepository: Send + Sync {
+    async fn create_event(&self, event: BaseEvent) -> Result<BaseEvent>;
+
+    /// Marks single occurrence of recurring event as cancelled, other occurrences are kept
+    async fn cancel_event_occurrence(
+        &self,
+        event_id: Uuid,
+        occurrence_datetime: NaiveDateTime,
+    ) -> Result<()>;
+
+    async fn get_all_events(&self) -> Result<Vec<BaseEvent>>;
+
+    async fn get_events_by_title_substr(&self, title: &str) -> Result<Vec<BaseEvent>>;
+
+    async fn get_all_public_events(&self, page: i64, page_size: i64) -> Result<Vec<BaseEvent>>;
+
+    /// Page of upcoming public events matching filters,
+    /// recurring events are expanded into their occurrences
+    async fn get_public_events(
+        &self,
+        page: i64,
+        page_size: i64,
+        events_subject_filter: &EventSubjectFilter,
+        events_city: Option<City>,
+        events_format_filter: &EventFormatFilter,
+    ) -> Result<Vec<BaseEvent>>;
+
+    /// Searches upcoming public events by title or description,
+    /// recurring events are represented by their nearest occurrence
+    async fn search_upcoming_public_events(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<BaseEvent>>;
+
+    /// Upcoming public events organized by community
+    async fn get_community_events(
+        &self,
+        community_id: i64,
+        limit: usize,
+    ) -> Result<Vec<BaseEvent>>;
+
+    async fn get_event_by_uuid(&self, uuid: Uuid) -> Result<BaseEvent>;
+
+    async fn get_event_by_invite_token(&self, invite_token: &str) -> Result<BaseEvent>;
+
+    /// Adds account to allow-list of private event
+    async fn grant_event_access(&self, event_id: Uuid, account_id: i64) -> Result<()>;
+
+    /// Checks only allow-list, creator and public events are not taken into account
+    async fn is_event_access_granted(&self, event_id: Uuid, account_id: i64) -> Result<bool>;
+
+    async fn delete_event(&self, event_uuid: Uuid, deleted_by_id: i64) -> Result<()>;
+
+    async fn create_event_tg_binding(&self, post_id: i64, event_id: Uuid) -> Result<()>;
+
+    async fn count_events_by_subject(&self) -> Result<HashMap<EventSubject, i64>>;
+}
+
+/// Upcoming occurrences of events ordered by start,
+/// each event contributes at most `per_event_limit` of them
+pub(super) fn upcoming_occurrences(events: &[BaseEvent], per_event_limit: usize) -> Vec<BaseEvent> {
+    let now = Utc::now();
+    let mut occurrences = events
+        .iter()
+        .flat_map(|event| event.upcoming_occurrences(now, per_event_limit))
+        .collect::<Vec<_>>();
+    occurrences.sort_by_key(|occurrence| occurrence.datetime_from_instant());
+
+    occurrences
+}
+
+/// Selects upcoming public events matching `filter`: at most `$<limit_param>` nearest
+/// one-off events, and recurring events whose last possible occurrence has not ended yet.
+/// Recurring events are expanded into occurrences and limited after loading
+fn upcoming_events_query(filter: &str, limit_param: usize) -> String {
+    format!(
+        r#"(select *
+            from resonanse_events
+            where is_private=false and {filter}
+            and not exists (select 1 from event_recurrences r where r.event_id = id)
+            and (coalesce(datetime_to, datetime_from) at time zone timezone) >= now()
+            order by datetime_from at time zone timezone
+            limit ${limit_param})
+        union all
+        (select *
+            from resonanse_events
+            where is_private=false and {filter}
+            and exists (
+                select 1 from event_recurrences r
+                where r.event_id = id
+                and (r.until_date is null or r.until_date >= (now() at time zone timezone)::date)
+                and ((coalesce(datetime_to, datetime_from)
+                    + (least(coalesce(r.occurrences_count, {max_occurrences}), {max_occurrences}) - 1)
+                    * case r.frequency
+                        when 0 then interval '1 day'
+                        when 1 then interval '1 week'
+                        else interval '1 month'
+                    end) at time zone timezone) >= now()
+            ))
+        "#,
+        filter = filter,
+        limit_param = limit_param,
+        max_occurrences = MAX_RECURRENCE_OCCURRENCES,
+    )
+}
+
 #[derive(Debug)]
-pub struct EventsRepository {
+pub struct PgEventsRepository {
     db_pool: PgPool,
 }
 
-impl EventsRepository {
+impl PgEventsRepository {
     pub fn new(pool: PgPool) -> Self {
         Self { db_pool: pool }
     }
 
-    pub async fn create_event(&self, event: BaseEvent) -> Result<BaseEvent> {
+    async fn create_event_recurrence(
+        tx: &mut Transaction<'_, Postgres>,
+        event_id: Uuid,
+        recurrence: &EventRecurrence,
+    ) -> Result<()> {
+        let result = sqlx::query(
+            r#"insert into event_recurrences
+            (event_id, frequency, until_date, occurrences_count)
+            values ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(event_id)
+        .bind(recurrence.frequency)
+        .bind(recurrence.until_date)
+        .bind(recurrence.occurrences_count)
+        .execute(&mut **tx)
+        .observe("events.create_event_recurrence")
+        .await?;
+        debug!("event_recurrences result {:?}", result);
+
+        Ok(())
+    }
+
+    /// Loads recurrence rules of given events, events without rule are not in result
+    async fn get_event_recurrences(
+        &self,
+        event_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, EventRecurrence>> {
+        let rows = sqlx::query(
+            r#"select r.event_id, r.frequency, r.until_date, r.occurrences_count,
+            array(
+                select c.occurrence_datetime
+                from cancelled_occurrences c
+                where c.event_id = r.event_id
+            ) as cancelled_occurrences
+            from event_recurrences r
+            where r.event_id = any($1)
+            "#,
+        )
+        .bind(event_ids)
+        .fetch_all(&self.db_pool)
+        .observe("events.get_event_recurrences")
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok((row.try_get("event_id")?, EventRecurrence::from_row(row)?)))
+            .collect()
+    }
+
+    async fn fill_events_recurrences(&self, events: &mut [BaseEvent]) -> Result<()> {
+        let event_ids = events.iter().map(|event| event.id).collect::<Vec<_>>();
+        let mut recurrences = self.get_event_recurrences(&event_ids).await?;
+        for event in events.iter_mut() {
+            event.recurrence = recurrences.remove(&event.id);
+        }
+
+        Ok(())
+    }
+
+    /// Loads number of participants with confirmed place, waitlisted ones are not counted
+    async fn fill_events_participants_count(&self, events: &mut [BaseEvent]) -> Result<()> {
+        let event_ids = events.iter().map(|event| event.id).collect::<Vec<_>>();
+        let rows = sqlx::query(
+            r#"select event_id, count(*)
+            from event_participants
+            where event_id = any($1) and status = $2
+            group by event_id
+            "#,
+        )
+        .bind(&event_ids)
+        .bind(ParticipationStatus::Going)
+        .fetch_all(&self.db_pool)
+        .observe("events.fill_events_participants_count")
+        .await?;
+
+        let participants_counts = rows
+            .iter()
+            .map(|row| Ok((row.try_get("event_id")?, row.try_get("count")?)))
+            .collect::<Result<HashMap<Uuid, i64>>>()?;
+        for event in events.iter_mut() {
+            event.participants_count = participants_counts.get(&event.id).copied().unwrap_or(0);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventsRepository for PgEventsRepository {
+    async fn create_event(&self, event: BaseEvent) -> Result<BaseEvent> {
+        let recurrence = event.recurrence.clone();
+        // random uuid is unguessable enough to be used as access secret
+        let invite_token = event
+            .is_private
+            .then(|| Uuid::new_v4().simple().to_string());
+        // event without its recurrence rule would be listed as one-off
+        let mut tx = self.db_pool.begin().await?;
         // fill all event except id nad creation_time
-        let created_event: BaseEvent = sqlx::query_as(
+        let mut created_event: BaseEvent = sqlx::query_as(
             r#"insert into resonanse_events
             (
-            id, is_private, is_commercial, is_online, is_paid, event_kind, title, description, brief_description,
-            subject, datetime_from, datetime_to, location_latitude, location_longitude,
-            location_title, creator_id, event_type, picture, contact_info
+            id, is_private, invite_token, is_commercial, is_online, is_paid, event_format, price_amount, price_currency,
+            event_kind, title, description, brief_description, subject, datetime_from, datetime_to, timezone,
+            location_latitude, location_longitude, location_title, meeting_url, capacity, creator_id,
+            community_id, event_type, picture, contact_info, city
+            )
+            values (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20,
+            $21, $22, $23, $24, $25, $26, $27, $28
             )
-            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
             returning *
             "#,
         )
             .bind(Uuid::new_v4())
             .bind(event.is_private)
+            .bind(invite_token)
             .bind(event.is_commercial)
             .bind(event.is_online)
             .bind(event.is_paid)
+            .bind(event.event_format)
+            .bind(event.price.as_ref().map(|price| price.amount))
+            .bind(event.price.as_ref().map(|price| price.currency.clone()))
             .bind(event.event_kind)
             .bind(event.title)
             .bind(event.description)
@@ -62,19 +282,53 @@ impl EventsRepository {
             .bind(event.subject as i32)
             .bind(event.datetime_from)
             .bind(event.datetime_to)
+            .bind(event.timezone.name())
             .bind(event.location.as_ref().map(|geo| geo.latitude))
             .bind(event.location.as_ref().map(|geo| geo.longitude))
             .bind(event.location_title)
+            .bind(event.meeting_url)
+            .bind(event.capacity)
             .bind(event.creator_id)
+            .bind(event.community_id)
             .bind(event.event_type)
             .bind(event.picture)
             .bind(event.contact_info)
-            .fetch_one(&self.db_pool)
+            .bind(event.city)
+            .fetch_one(&mut *tx)
+            .observe("events.create_event")
             .await?;
 
+        if let Some(recurrence) = recurrence {
+            Self::create_event_recurrence(&mut tx, created_event.id, &recurrence).await?;
+            created_event.recurrence = Some(recurrence);
+        }
+        tx.commit().await?;
+
         Ok(created_event)
     }
 
+    async fn cancel_event_occurrence(
+        &self,
+        event_id: Uuid,
+        occurrence_datetime: NaiveDateTime,
+    ) -> Result<()> {
+        let result = sqlx::query(
+            r#"insert into cancelled_occurrences
+            (event_id, occurrence_datetime)
+            values ($1, $2)
+            on conflict do nothing
+            "#,
+        )
+        .bind(event_id)
+        .bind(occurrence_datetime)
+        .execute(&self.db_pool)
+        .observe("events.cancel_event_occurrence")
+        .await?;
+        debug!("cancelled_occurrences result {:?}", result);
+
+        Ok(())
+    }
+
     // pub async fn edit_event(&self, event: CreateBaseEvent, event_uuid: Uuid) -> Result<BaseEvent> {
     //     let created_event: BaseEvent = sqlx::query_as(
     //         r#"insert into resonanse_events
@@ -107,21 +361,22 @@ impl EventsRepository {
     //     Ok(created_event)
     // }
 
-    pub async fn get_all_events(&self) -> Result<Vec<BaseEvent>> {
+    async fn get_all_events(&self) -> Result<Vec<BaseEvent>> {
         let events: Result<Vec<BaseEvent>> = sqlx::query_as(
             r#"select *
             from resonanse_events
-            where datetime_from >= current_date
+            where (coalesce(datetime_to, datetime_from) at time zone timezone) >= now()
             order by datetime_from
             "#,
         )
             .fetch_all(&self.db_pool)
+            .observe("events.get_all_events")
             .await;
 
         events
     }
 
-    pub async fn get_events_by_title_substr(&self, title: &str) -> Result<Vec<BaseEvent>> {
+    async fn get_events_by_title_substr(&self, title: &str) -> Result<Vec<BaseEvent>> {
         let events: Result<Vec<BaseEvent>> = sqlx::query_as(
             r#"select *
             from resonanse_events
@@ -130,16 +385,17 @@ impl EventsRepository {
         )
             .bind(format!("%{}%", title))
             .fetch_all(&self.db_pool)
+            .observe("events.get_events_by_title_substr")
             .await;
 
         events
     }
 
-    pub async fn get_all_public_events(&self, page: i64, page_size: i64) -> Result<Vec<BaseEvent>> {
+    async fn get_all_public_events(&self, page: i64, page_size: i64) -> Result<Vec<BaseEvent>> {
         let events: Result<Vec<BaseEvent>> = sqlx::query_as(
             r#"select *
             from resonanse_events
-            where is_private=false and datetime_from >= current_date
+            where is_private=false and (coalesce(datetime_to, datetime_from) at time zone timezone) >= now()
             order by datetime_from
             offset $1 rows
             fetch next $2 rows only
@@ -148,25 +404,34 @@ impl EventsRepository {
             .bind(page * page_size)
             .bind(page_size)
             .fetch_all(&self.db_pool)
+            .observe("events.get_all_public_events")
             .await;
 
         events
     }
 
-    pub async fn get_public_events(
+    async fn get_public_events(
         &self,
         page: i64,
         page_size: i64,
         events_subject_filter: &EventSubjectFilter,
+        events_city: Option<City>,
+        events_format_filter: &EventFormatFilter,
     ) -> Result<Vec<BaseEvent>> {
         let filters_vec = events_subject_filter
             .0
             .iter()
             .filter(|(_, f)| **f)
-            .map(|(f, _)| (*f as i32))
+            .map(|(f, _)| *f as i32)
+            .collect::<Vec<_>>();
+        let format_filters_vec = events_format_filter
+            .formats
+            .iter()
+            .filter(|(_, f)| **f)
+            .map(|(f, _)| *f as i32)
             .collect::<Vec<_>>();
 
-        if filters_vec.is_empty() {
+        if filters_vec.is_empty() || format_filters_vec.is_empty() {
             return Ok(Vec::new());
         }
 
@@ -175,35 +440,99 @@ impl EventsRepository {
             .map(|i| format!("${}", i))
             .collect::<Vec<String>>()
             .join(", ");
-        let query_str = format!(
-            r#"select *
-            from resonanse_events
-            WHERE subject IN ( { } ) and is_private=false and datetime_from >= current_date
-            order by datetime_from
-            offset ${} rows
-            fetch next ${} rows only
-            "#,
+        let format_filter_params_len = format_filters_vec.len();
+        let format_filter_params = (1..=format_filter_params_len)
+            .map(|i| format!("${}", filter_params_len + i))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let only_free_param = filter_params_len + format_filter_params_len + 1;
+        // events without city (online ones) are listed in every city
+        let filter = format!(
+            r#"subject IN ( {} ) and event_format IN ( {} ) and (is_paid=false or ${}=false)
+            and (${}::int is null or city is null or city=${})"#,
             filter_params,
-            filter_params_len + 1,
-            filter_params_len + 2,
+            format_filter_params,
+            only_free_param,
+            only_free_param + 1,
+            only_free_param + 1,
         );
+        // one-off events after the requested page cannot get into it
+        let query_str = upcoming_events_query(&filter, only_free_param + 2);
         debug!("get_public_events builded query: {}", query_str);
 
         let mut events_query = sqlx::query_as(&query_str);
         for subj_i32 in filters_vec {
             events_query = events_query.bind(subj_i32);
         }
+        for format_i32 in format_filters_vec {
+            events_query = events_query.bind(format_i32);
+        }
+        events_query = events_query.bind(events_format_filter.only_free);
+        events_query = events_query.bind(events_city);
+        events_query = events_query.bind((page + 1) * page_size);
 
-        let events: Result<Vec<BaseEvent>> = events_query
-            .bind(page * page_size)
-            .bind(page_size)
+        let mut events: Vec<BaseEvent> = events_query
             .fetch_all(&self.db_pool)
-            .await;
-        events
+            .observe("events.get_public_events")
+            .await?;
+        self.fill_events_recurrences(&mut events).await?;
+        self.fill_events_participants_count(&mut events).await?;
+
+        Ok(upcoming_occurrences(&events, LISTED_OCCURRENCES_LIMIT)
+            .into_iter()
+            .skip((page * page_size) as usize)
+            .take(page_size as usize)
+            .collect())
+    }
+
+    async fn search_upcoming_public_events(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<BaseEvent>> {
+        let escaped_query = query
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let query_str = upcoming_events_query("(title ilike $1 or description ilike $1)", 2);
+        let mut events: Vec<BaseEvent> = sqlx::query_as(&query_str)
+            .bind(format!("%{}%", escaped_query))
+            .bind(limit as i64)
+            .fetch_all(&self.db_pool)
+            .observe("events.search_upcoming_public_events")
+            .await?;
+        self.fill_events_recurrences(&mut events).await?;
+        self.fill_events_participants_count(&mut events).await?;
+
+        let mut occurrences = upcoming_occurrences(&events, 1);
+        occurrences.truncate(limit);
+
+        Ok(occurrences)
+    }
+
+    async fn get_community_events(
+        &self,
+        community_id: i64,
+        limit: usize,
+    ) -> Result<Vec<BaseEvent>> {
+        let query_str = upcoming_events_query("community_id=$1", 2);
+        let mut events: Vec<BaseEvent> = sqlx::query_as(&query_str)
+            .bind(community_id)
+            .bind(limit as i64)
+            .fetch_all(&self.db_pool)
+            .observe("events.get_community_events")
+            .await?;
+        self.fill_events_recurrences(&mut events).await?;
+        self.fill_events_participants_count(&mut events).await?;
+
+        let mut occurrences = upcoming_occurrences(&events, 1);
+        occurrences.truncate(limit);
+
+        Ok(occurrences)
     }
 
-    pub async fn get_event_by_uuid(&self, uuid: Uuid) -> Result<BaseEvent> {
-        let event: Result<BaseEvent> = sqlx::query_as(
+    async fn get_event_by_uuid(&self, uuid: Uuid) -> Result<BaseEvent> {
+        let mut event: BaseEvent = sqlx::query_as(
             r#"select *
             from resonanse_events
             where id=$1
@@ -211,30 +540,91 @@ impl EventsRepository {
         )
             .bind(uuid)
             .fetch_one(&self.db_pool)
-            .await;
+            .observe("events.get_event_by_uuid")
+            .await?;
+        event.recurrence = self.get_event_recurrences(&[uuid]).await?.remove(&uuid);
+        self.fill_events_participants_count(std::slice::from_mut(&mut event))
+            .await?;
+
+        Ok(event)
+    }
+
+    async fn get_event_by_invite_token(&self, invite_token: &str) -> Result<BaseEvent> {
+        let event_id: Uuid = sqlx::query_scalar(
+            r#"select id
+            from resonanse_events
+            where invite_token=$1
+            "#,
+        )
+            .bind(invite_token)
+            .fetch_one(&self.db_pool)
+            .observe("events.get_event_by_invite_token")
+            .await?;
+
+        self.get_event_by_uuid(event_id).await
+    }
+
+    async fn grant_event_access(&self, event_id: Uuid, account_id: i64) -> Result<()> {
+        let result = sqlx::query(
+            r#"insert into event_allowed_accounts
+            (event_id, account_id)
+            values ($1, $2)
+            on conflict do nothing
+            "#,
+        )
+            .bind(event_id)
+            .bind(account_id)
+            .execute(&self.db_pool)
+            .observe("events.grant_event_access")
+            .await?;
+        debug!("event_allowed_accounts result {:?}", result);
 
-        event
+        Ok(())
     }
 
-    pub async fn delete_event(&self, event_uuid: Uuid, _deleted_by_id: i64) -> Result<()> {
+    async fn is_event_access_granted(&self, event_id: Uuid, account_id: i64) -> Result<bool> {
+        sqlx::query_scalar(
+            r#"select exists (
+                select 1
+                from event_allowed_accounts
+                where event_id=$1 and account_id=$2
+            )
+            "#,
+        )
+            .bind(event_id)
+            .bind(account_id)
+            .fetch_one(&self.db_pool)
+            .observe("events.is_event_access_granted")
+            .await
+    }
+
+    async fn delete_event(&self, event_uuid: Uuid, _deleted_by_id: i64) -> Result<()> {
         let deleting_event = self.get_event_by_uuid(event_uuid).await?;
 
         let _deleted_event: BaseEvent = sqlx::query_as(
             r#"insert into deleted_events
             (
-            id, is_private, is_commercial, is_online, is_paid, event_kind, title, description, brief_description,
-            subject, datetime_from, datetime_to, location_latitude, location_longitude,
-            location_title, creator_id, event_type, picture, contact_info
+            id, is_private, invite_token, is_commercial, is_online, is_paid, event_format, price_amount, price_currency,
+            event_kind, title, description, brief_description, subject, datetime_from, datetime_to, timezone,
+            location_latitude, location_longitude, location_title, meeting_url, capacity, creator_id,
+            community_id, event_type, picture, contact_info, city
+            )
+            values (
+            $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20,
+            $21, $22, $23, $24, $25, $26, $27, $28
             )
-            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
             returning *
             "#,
         )
             .bind(deleting_event.id)
             .bind(deleting_event.is_private)
+            .bind(deleting_event.invite_token)
             .bind(deleting_event.is_commercial)
             .bind(deleting_event.is_online)
             .bind(deleting_event.is_paid)
+            .bind(deleting_event.event_format)
+            .bind(deleting_event.price.as_ref().map(|price| price.amount))
+            .bind(deleting_event.price.as_ref().map(|price| price.currency.clone()))
             .bind(deleting_event.event_kind)
             .bind(deleting_event.title)
             .bind(deleting_event.description)
@@ -242,14 +632,20 @@ impl EventsRepository {
             .bind(deleting_event.subject as i32)
             .bind(deleting_event.datetime_from)
             .bind(deleting_event.datetime_to)
+            .bind(deleting_event.timezone.name())
             .bind(deleting_event.location.as_ref().map(|geo| geo.latitude))
             .bind(deleting_event.location.as_ref().map(|geo| geo.longitude))
             .bind(deleting_event.location_title)
+            .bind(deleting_event.meeting_url)
+            .bind(deleting_event.capacity)
             .bind(deleting_event.creator_id)
+            .bind(deleting_event.community_id)
             .bind(deleting_event.event_type)
             .bind(deleting_event.picture)
             .bind(deleting_event.contact_info)
+            .bind(deleting_event.city)
             .fetch_one(&self.db_pool)
+            .observe("events.delete_event")
             .await?;
 
         let result = sqlx::query(
@@ -260,6 +656,7 @@ impl EventsRepository {
         )
             .bind(event_uuid)
             .execute(&self.db_pool)
+            .observe("events.delete_event")
             .await?;
 
         debug!("delete_events result {:?}", result);
@@ -267,32 +664,34 @@ impl EventsRepository {
         Ok(())
     }
 
-    pub async fn create_event_tg_binding(&self, post_id: i64, event_id: Uuid) -> Result<()> {
+    async fn create_event_tg_binding(&self, post_id: i64, event_id: Uuid) -> Result<()> {
         let result = sqlx::query(
             r#"insert into event_tg_table
-            (post_id, creator_id)
+            (post_id, event_id)
             values ($1, $2)
             "#,
         )
             .bind(post_id)
             .bind(event_id)
             .execute(&self.db_pool)
+            .observe("events.create_event_tg_binding")
             .await?;
         debug!("event_tg_table result {:?}", result);
 
         Ok(())
     }
 
-    pub async fn count_events_by_subject(&self) -> Result<HashMap<EventSubject, i64>> {
-        let _result = sqlx::query(
+    async fn count_events_by_subject(&self) -> Result<HashMap<EventSubject, i64>> {
+        let counts: Vec<(EventSubject, i64)> = sqlx::query_as(
             r#"select subject, count(*)
             from resonanse_events
             group by subject
             "#,
         )
             .fetch_all(&self.db_pool)
-            .await;
+            .observe("events.count_events_by_subject")
+            .await?;
 
-        Ok(HashMap::new())
+        Ok(counts.into_iter().collect())
     }
 }
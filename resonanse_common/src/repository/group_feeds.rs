@@ -0,0 +1,290 @@
+use async_trait::async_trait;
+use log::debug;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Result, Row};
+
+use crate::metrics::ObserveQuery;
+use crate::models::{City, EventSubject, EventSubjectFilter, GroupFeed, CITIES};
+
+#[async_trait]
+pub trait GroupFeedsRepository: Send + Sync {
+    /// Registers group feed, new feed follows all subjects and cities.
+    /// Title of already registered group is updated
+    async fn add_group_feed(&self, chat_id: i64, title: Option<&str>) -> Result<GroupFeed>;
+
+    async fn get_group_feed(&self, chat_id: i64) -> Result<Option<GroupFeed>>;
+
+    /// Follows subject if it is not followed by group, unfollows otherwise
+    async fn switch_group_feed_subject(
+        &self,
+        chat_id: i64,
+        subject: EventSubject,
+    ) -> Result<GroupFeed>;
+
+    /// Follows city if it is not followed by group, unfollows otherwise
+    async fn switch_group_feed_city(&self, chat_id: i64, city: City) -> Result<GroupFeed>;
+
+    async fn delete_group_feed(&self, chat_id: i64) -> Result<()>;
+
+    /// Chat ids of groups which follow subject and city of event,
+    /// events without city are posted regardless of followed cities
+    async fn get_event_group_chats(
+        &self,
+        subject: EventSubject,
+        city: Option<City>,
+    ) -> Result<Vec<i64>>;
+}
+
+#[derive(Debug)]
+pub struct PgGroupFeedsRepository {
+    db_pool: PgPool,
+}
+
+impl PgGroupFeedsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { db_pool: pool }
+    }
+
+    async fn get_group_feed_subjects(&self, chat_id: i64) -> Result<Vec<EventSubject>> {
+        sqlx::query_scalar(
+            r#"select subject
+            from group_feed_subjects
+            where chat_id = $1
+            order by subject
+            "#,
+        )
+        .bind(chat_id)
+        .fetch_all(&self.db_pool)
+        .observe("group_feeds.get_group_feed_subjects")
+        .await
+    }
+
+    async fn get_group_feed_cities(&self, chat_id: i64) -> Result<Vec<City>> {
+        sqlx::query_scalar(
+            r#"select city
+            from group_feed_cities
+            where chat_id = $1
+            order by city
+            "#,
+        )
+        .bind(chat_id)
+        .fetch_all(&self.db_pool)
+        .observe("group_feeds.get_group_feed_cities")
+        .await
+    }
+
+    fn group_feed_from_row(row: &PgRow) -> Result<GroupFeed> {
+        Ok(GroupFeed {
+            chat_id: row.try_get("chat_id")?,
+            title: row.try_get("title")?,
+            subjects: Vec::new(),
+            cities: Vec::new(),
+            creation_time: row.try_get("creation_time")?,
+        })
+    }
+}
+
+#[async_trait]
+impl GroupFeedsRepository for PgGroupFeedsRepository {
+    async fn add_group_feed(&self, chat_id: i64, title: Option<&str>) -> Result<GroupFeed> {
+        let mut tx = self.db_pool.begin().await?;
+
+        let is_created = sqlx::query(
+            r#"insert into group_feeds
+            (chat_id, title)
+            values ($1, $2)
+            on conflict (chat_id) do nothing
+            "#,
+        )
+        .bind(chat_id)
+        .bind(title)
+        .execute(&mut *tx)
+        .observe("group_feeds.add_group_feed")
+        .await?
+        .rows_affected()
+            > 0;
+
+        if is_created {
+            let all_subjects = EventSubjectFilter::new()
+                .0
+                .into_keys()
+                .map(|subject| subject as i32)
+                .collect::<Vec<_>>();
+            let result = sqlx::query(
+                r#"insert into group_feed_subjects
+                (chat_id, subject)
+                select $1, unnest($2::int[])
+                "#,
+            )
+            .bind(chat_id)
+            .bind(all_subjects)
+            .execute(&mut *tx)
+            .observe("group_feeds.add_group_feed")
+            .await?;
+            debug!("group_feed_subjects result {:?}", result);
+
+            let all_cities = CITIES.iter().map(|city| *city as i32).collect::<Vec<_>>();
+            let result = sqlx::query(
+                r#"insert into group_feed_cities
+                (chat_id, city)
+                select $1, unnest($2::int[])
+                "#,
+            )
+            .bind(chat_id)
+            .bind(all_cities)
+            .execute(&mut *tx)
+            .observe("group_feeds.add_group_feed")
+            .await?;
+            debug!("group_feed_cities result {:?}", result);
+        } else {
+            sqlx::query(
+                r#"update group_feeds
+                set title = $2
+                where chat_id = $1
+                "#,
+            )
+            .bind(chat_id)
+            .bind(title)
+            .execute(&mut *tx)
+            .observe("group_feeds.add_group_feed")
+            .await?;
+        }
+
+        tx.commit().await?;
+        self.get_group_feed(chat_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn get_group_feed(&self, chat_id: i64) -> Result<Option<GroupFeed>> {
+        let row = sqlx::query(
+            r#"select chat_id, title, creation_time
+            from group_feeds
+            where chat_id = $1
+            "#,
+        )
+        .bind(chat_id)
+        .fetch_optional(&self.db_pool)
+        .observe("group_feeds.get_group_feed")
+        .await?;
+
+        match row {
+            None => Ok(None),
+            Some(row) => Ok(Some(GroupFeed {
+                subjects: self.get_group_feed_subjects(chat_id).await?,
+                cities: self.get_group_feed_cities(chat_id).await?,
+                ..Self::group_feed_from_row(&row)?
+            })),
+        }
+    }
+
+    async fn switch_group_feed_subject(
+        &self,
+        chat_id: i64,
+        subject: EventSubject,
+    ) -> Result<GroupFeed> {
+        let unfollowed = sqlx::query(
+            r#"delete from group_feed_subjects
+            where chat_id = $1 and subject = $2
+            "#,
+        )
+        .bind(chat_id)
+        .bind(subject)
+        .execute(&self.db_pool)
+        .observe("group_feeds.switch_group_feed_subject")
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !unfollowed {
+            sqlx::query(
+                r#"insert into group_feed_subjects
+                (chat_id, subject)
+                values ($1, $2)
+                on conflict do nothing
+                "#,
+            )
+            .bind(chat_id)
+            .bind(subject)
+            .execute(&self.db_pool)
+            .observe("group_feeds.switch_group_feed_subject")
+            .await?;
+        }
+
+        self.get_group_feed(chat_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn switch_group_feed_city(&self, chat_id: i64, city: City) -> Result<GroupFeed> {
+        let unfollowed = sqlx::query(
+            r#"delete from group_feed_cities
+            where chat_id = $1 and city = $2
+            "#,
+        )
+        .bind(chat_id)
+        .bind(city)
+        .execute(&self.db_pool)
+        .observe("group_feeds.switch_group_feed_city")
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !unfollowed {
+            sqlx::query(
+                r#"insert into group_feed_cities
+                (chat_id, city)
+                values ($1, $2)
+                on conflict do nothing
+                "#,
+            )
+            .bind(chat_id)
+            .bind(city)
+            .execute(&self.db_pool)
+            .observe("group_feeds.switch_group_feed_city")
+            .await?;
+        }
+
+        self.get_group_feed(chat_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn delete_group_feed(&self, chat_id: i64) -> Result<()> {
+        let result = sqlx::query(
+            r#"delete from group_feeds
+            where chat_id = $1
+            "#,
+        )
+        .bind(chat_id)
+        .execute(&self.db_pool)
+        .observe("group_feeds.delete_group_feed")
+        .await?;
+        debug!("group_feeds result {:?}", result);
+
+        Ok(())
+    }
+
+    async fn get_event_group_chats(
+        &self,
+        subject: EventSubject,
+        city: Option<City>,
+    ) -> Result<Vec<i64>> {
+        sqlx::query_scalar(
+            r#"select chat_id
+            from group_feed_subjects
+            where subject = $1
+                and ($2::int is null or exists (
+                    select 1 from group_feed_cities
+                    where group_feed_cities.chat_id = group_feed_subjects.chat_id
+                        and group_feed_cities.city = $2
+                ))
+            "#,
+        )
+        .bind(subject)
+        .bind(city)
+        .fetch_all(&self.db_pool)
+        .observe("group_feeds.get_event_group_chats")
+        .await
+    }
+}
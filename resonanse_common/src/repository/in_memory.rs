@@ -0,0 +1,1122 @@
+//! Repositories keeping data in process memory, they behave like postgres ones
+//! as far as handlers can tell, but know nothing about tables they are not given,
+//! e.g. events have no participants
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use serde_json::Value;
+use sqlx::{Error, Result};
+use uuid::Uuid;
+
+use crate::models::{
+    BaseAccount, BaseEvent, City, Community, EventDraft, EventScore, EventScoreType, EventSubject,
+    GroupFeed, ParticipationStatus, UserData, CITIES,
+};
+use crate::repository::events::{upcoming_occurrences, LISTED_OCCURRENCES_LIMIT};
+use crate::repository::{
+    AccountFollowsRepository, AccountsRepository, CommunitiesRepository, EventDraftsRepository,
+    EventParticipantsRepository, EventScoresRepository, EventsRepository, GroupFeedsRepository,
+};
+use crate::{EventFormatFilter, EventSubjectFilter};
+
+fn is_upcoming(event: &BaseEvent) -> bool {
+    event
+        .datetime_to_instant()
+        .unwrap_or_else(|| event.datetime_from_instant())
+        >= Utc::now()
+}
+
+fn is_enabled<K: Eq + std::hash::Hash>(filter: &HashMap<K, bool>, key: &K) -> bool {
+    filter.get(key).copied().unwrap_or(false)
+}
+
+fn sorted_by_start(mut events: Vec<BaseEvent>) -> Vec<BaseEvent> {
+    events.sort_by_key(|event| event.datetime_from);
+    events
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryEventsRepository {
+    events: Mutex<HashMap<Uuid, BaseEvent>>,
+    allowed_accounts: Mutex<HashSet<(Uuid, i64)>>,
+    tg_bindings: Mutex<Vec<(i64, Uuid)>>,
+}
+
+impl InMemoryEventsRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events for which predicate holds, in no particular order
+    fn filter_events(&self, predicate: impl Fn(&BaseEvent) -> bool) -> Vec<BaseEvent> {
+        self.events
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|event| predicate(event))
+            .cloned()
+            .collect()
+    }
+
+    /// Public events which are not finished yet or have more occurrences
+    fn filter_listed_events(&self, predicate: impl Fn(&BaseEvent) -> bool) -> Vec<BaseEvent> {
+        self.filter_events(|event| {
+            !event.is_private
+                && (is_upcoming(event) || event.recurrence.is_some())
+                && predicate(event)
+        })
+    }
+}
+
+#[async_trait]
+impl EventsRepository for InMemoryEventsRepository {
+    async fn create_event(&self, event: BaseEvent) -> Result<BaseEvent> {
+        let created_event = BaseEvent {
+            id: Uuid::new_v4(),
+            invite_token: event
+                .is_private
+                .then(|| Uuid::new_v4().simple().to_string()),
+            creation_time: Utc::now().naive_utc(),
+            participants_count: 0,
+            ..event
+        };
+        self.events
+            .lock()
+            .unwrap()
+            .insert(created_event.id, created_event.clone());
+
+        Ok(created_event)
+    }
+
+    async fn cancel_event_occurrence(
+        &self,
+        event_id: Uuid,
+        occurrence_datetime: NaiveDateTime,
+    ) -> Result<()> {
+        let mut events = self.events.lock().unwrap();
+        let recurrence = events
+            .get_mut(&event_id)
+            .and_then(|event| event.recurrence.as_mut())
+            .ok_or(Error::RowNotFound)?;
+        if !recurrence
+            .cancelled_occurrences
+            .contains(&occurrence_datetime)
+        {
+            recurrence.cancelled_occurrences.push(occurrence_datetime);
+        }
+
+        Ok(())
+    }
+
+    async fn get_all_events(&self) -> Result<Vec<BaseEvent>> {
+        Ok(sorted_by_start(self.filter_events(is_upcoming)))
+    }
+
+    async fn get_events_by_title_substr(&self, title: &str) -> Result<Vec<BaseEvent>> {
+        Ok(self.filter_events(|event| event.title.contains(title)))
+    }
+
+    async fn get_all_public_events(&self, page: i64, page_size: i64) -> Result<Vec<BaseEvent>> {
+        let events = self.filter_events(|event| !event.is_private && is_upcoming(event));
+
+        Ok(sorted_by_start(events)
+            .into_iter()
+            .skip((page * page_size) as usize)
+            .take(page_size as usize)
+            .collect())
+    }
+
+    async fn get_public_events(
+        &self,
+        page: i64,
+        page_size: i64,
+        events_subject_filter: &EventSubjectFilter,
+        events_city: Option<City>,
+        events_format_filter: &EventFormatFilter,
+    ) -> Result<Vec<BaseEvent>> {
+        let events = self.filter_listed_events(|event| {
+            is_enabled(&events_subject_filter.0, &event.subject)
+                && is_enabled(&events_format_filter.formats, &event.event_format)
+                && !(event.is_paid && events_format_filter.only_free)
+                && (events_city.is_none() || event.city.is_none() || event.city == events_city)
+        });
+
+        Ok(upcoming_occurrences(&events, LISTED_OCCURRENCES_LIMIT)
+            .into_iter()
+            .skip((page * page_size) as usize)
+            .take(page_size as usize)
+            .collect())
+    }
+
+    async fn search_upcoming_public_events(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<BaseEvent>> {
+        let query = query.to_lowercase();
+        let events = self.filter_listed_events(|event| {
+            event.title.to_lowercase().contains(&query)
+                || event.description.to_lowercase().contains(&query)
+        });
+
+        let mut occurrences = upcoming_occurrences(&events, 1);
+        occurrences.truncate(limit);
+
+        Ok(occurrences)
+    }
+
+    async fn get_community_events(
+        &self,
+        community_id: i64,
+        limit: usize,
+    ) -> Result<Vec<BaseEvent>> {
+        let events = self.filter_listed_events(|event| event.community_id == Some(community_id));
+
+        let mut occurrences = upcoming_occurrences(&events, 1);
+        occurrences.truncate(limit);
+
+        Ok(occurrences)
+    }
+
+    async fn get_event_by_uuid(&self, uuid: Uuid) -> Result<BaseEvent> {
+        self.events
+            .lock()
+            .unwrap()
+            .get(&uuid)
+            .cloned()
+            .ok_or(Error::RowNotFound)
+    }
+
+    async fn get_event_by_invite_token(&self, invite_token: &str) -> Result<BaseEvent> {
+        self.filter_events(|event| event.invite_token.as_deref() == Some(invite_token))
+            .pop()
+            .ok_or(Error::RowNotFound)
+    }
+
+    async fn grant_event_access(&self, event_id: Uuid, account_id: i64) -> Result<()> {
+        self.allowed_accounts
+            .lock()
+            .unwrap()
+            .insert((event_id, account_id));
+
+        Ok(())
+    }
+
+    async fn is_event_access_granted(&self, event_id: Uuid, account_id: i64) -> Result<bool> {
+        Ok(self
+            .allowed_accounts
+            .lock()
+            .unwrap()
+            .contains(&(event_id, account_id)))
+    }
+
+    async fn delete_event(&self, event_uuid: Uuid, _deleted_by_id: i64) -> Result<()> {
+        self.events
+            .lock()
+            .unwrap()
+            .remove(&event_uuid)
+            .map(|_| ())
+            .ok_or(Error::RowNotFound)
+    }
+
+    async fn create_event_tg_binding(&self, post_id: i64, event_id: Uuid) -> Result<()> {
+        self.tg_bindings.lock().unwrap().push((post_id, event_id));
+
+        Ok(())
+    }
+
+    async fn count_events_by_subject(&self) -> Result<HashMap<EventSubject, i64>> {
+        let mut counts = HashMap::new();
+        for event in self.events.lock().unwrap().values() {
+            *counts.entry(event.subject).or_insert(0) += 1;
+        }
+
+        Ok(counts)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryAccountsRepository {
+    accounts: Mutex<Vec<BaseAccount>>,
+}
+
+impl InMemoryAccountsRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn get_account(&self, account_id: i64) -> Option<BaseAccount> {
+        self.accounts
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|account| account.id == account_id)
+            .cloned()
+    }
+
+    /// Applies change to account with given id, missing account is ignored as by `update`
+    fn update_account(&self, account_id: i64, change: impl FnOnce(&mut BaseAccount)) {
+        if let Some(account) = self
+            .accounts
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|account| account.id == account_id)
+        {
+            change(account);
+        }
+    }
+}
+
+#[async_trait]
+impl AccountsRepository for InMemoryAccountsRepository {
+    async fn get_user_by_tg_id(&self, tg_user_id: i64) -> Result<BaseAccount> {
+        self.accounts
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|account| account.contact_data.telegram.user_id == Some(tg_user_id))
+            .cloned()
+            .ok_or(Error::RowNotFound)
+    }
+
+    async fn get_account_id_by_tg_user_id(&self, tg_user_id: i64) -> Result<i64> {
+        self.get_user_by_tg_id(tg_user_id)
+            .await
+            .map(|account| account.id)
+    }
+
+    async fn create_user(&self, account: BaseAccount) -> Result<BaseAccount> {
+        let mut accounts = self.accounts.lock().unwrap();
+        let created_account = BaseAccount {
+            id: accounts.len() as i64 + 1,
+            ..account
+        };
+        accounts.push(created_account.clone());
+
+        Ok(created_account)
+    }
+
+    async fn update_user_data(&self, account_id: i64, user_data: &UserData) -> Result<()> {
+        self.update_account(account_id, |account| {
+            account.user_data = UserData {
+                timezone: account.user_data.timezone,
+                locale: account.user_data.locale.take(),
+                ..user_data.clone()
+            };
+        });
+
+        Ok(())
+    }
+
+    async fn set_user_timezone(&self, account_id: i64, timezone: Tz) -> Result<()> {
+        self.update_account(account_id, |account| {
+            account.user_data.timezone = Some(timezone);
+        });
+
+        Ok(())
+    }
+
+    async fn set_user_city(&self, account_id: i64, city: City) -> Result<()> {
+        self.update_account(account_id, |account| account.user_data.city = Some(city));
+
+        Ok(())
+    }
+
+    async fn set_user_locale(&self, account_id: i64, locale: &str) -> Result<()> {
+        self.update_account(account_id, |account| {
+            account.user_data.locale = Some(locale.to_string());
+        });
+
+        Ok(())
+    }
+
+    async fn get_user_locale_by_tg_id(&self, tg_user_id: i64) -> Result<Option<String>> {
+        match self.get_user_by_tg_id(tg_user_id).await {
+            Ok(account) => Ok(account.user_data.locale),
+            Err(Error::RowNotFound) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    async fn count_accounts(&self) -> Result<i64> {
+        Ok(self.accounts.lock().unwrap().len() as i64)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryEventScoresRepository {
+    scores: Mutex<HashMap<(i64, Uuid), EventScoreType>>,
+}
+
+impl InMemoryEventScoresRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl EventScoresRepository for InMemoryEventScoresRepository {
+    async fn set_event_score_by_user(
+        &self,
+        event_id: Uuid,
+        user_id: i64,
+        score: EventScoreType,
+    ) -> Result<EventScore> {
+        self.scores
+            .lock()
+            .unwrap()
+            .insert((user_id, event_id), score);
+
+        Ok(EventScore {
+            user_id,
+            event_id,
+            event_score: score,
+        })
+    }
+
+    async fn get_event_scores_by_user(&self, user_id: i64) -> Result<Vec<EventScore>> {
+        Ok(self
+            .scores
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((score_user_id, _), _)| *score_user_id == user_id)
+            .map(|((_, event_id), score)| EventScore {
+                user_id,
+                event_id: *event_id,
+                event_score: *score,
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug)]
+pub struct InMemoryEventParticipantsRepository {
+    /// Registrations in order of joining
+    participants: Mutex<Vec<(Uuid, i64, ParticipationStatus)>>,
+    events_repository: Arc<InMemoryEventsRepository>,
+    accounts_repository: Arc<InMemoryAccountsRepository>,
+}
+
+impl InMemoryEventParticipantsRepository {
+    pub fn new(
+        events_repository: Arc<InMemoryEventsRepository>,
+        accounts_repository: Arc<InMemoryAccountsRepository>,
+    ) -> Self {
+        Self {
+            participants: Mutex::default(),
+            events_repository,
+            accounts_repository,
+        }
+    }
+
+    fn get_event_capacity(&self, event_id: Uuid) -> Result<Option<i32>> {
+        self.events_repository
+            .events
+            .lock()
+            .unwrap()
+            .get(&event_id)
+            .map(|event| event.capacity)
+            .ok_or(Error::RowNotFound)
+    }
+}
+
+fn count_going(participants: &[(Uuid, i64, ParticipationStatus)], event_id: Uuid) -> i64 {
+    participants
+        .iter()
+        .filter(|(id, _, status)| *id == event_id && *status == ParticipationStatus::Going)
+        .count() as i64
+}
+
+#[async_trait]
+impl EventParticipantsRepository for InMemoryEventParticipantsRepository {
+    async fn get_participation_status(
+        &self,
+        event_id: Uuid,
+        user_id: i64,
+    ) -> Result<Option<ParticipationStatus>> {
+        Ok(self
+            .participants
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(id, user, _)| *id == event_id && *user == user_id)
+            .map(|(_, _, status)| *status))
+    }
+
+    async fn join_event(&self, event_id: Uuid, user_id: i64) -> Result<ParticipationStatus> {
+        let capacity = self.get_event_capacity(event_id)?;
+        let mut participants = self.participants.lock().unwrap();
+        if let Some((_, _, status)) = participants
+            .iter()
+            .find(|(id, user, _)| *id == event_id && *user == user_id)
+        {
+            return Ok(*status);
+        }
+
+        let status = match capacity {
+            Some(capacity) if count_going(&participants, event_id) >= capacity as i64 => {
+                ParticipationStatus::Waitlisted
+            }
+            _ => ParticipationStatus::Going,
+        };
+        participants.push((event_id, user_id, status));
+
+        Ok(status)
+    }
+
+    async fn leave_event(&self, event_id: Uuid, user_id: i64) -> Result<Option<i64>> {
+        let capacity = self.get_event_capacity(event_id)?;
+        let mut participants = self.participants.lock().unwrap();
+        let Some(position) = participants
+            .iter()
+            .position(|(id, user, _)| *id == event_id && *user == user_id)
+        else {
+            return Ok(None);
+        };
+        let (_, _, left_status) = participants.remove(position);
+
+        let has_free_place = match capacity {
+            Some(capacity) => count_going(&participants, event_id) < capacity as i64,
+            None => true,
+        };
+        if left_status != ParticipationStatus::Going || !has_free_place {
+            return Ok(None);
+        }
+
+        let promoted = participants
+            .iter_mut()
+            .find(|(id, _, status)| *id == event_id && *status == ParticipationStatus::Waitlisted);
+        Ok(promoted.and_then(|(_, promoted_user_id, status)| {
+            *status = ParticipationStatus::Going;
+            self.accounts_repository
+                .get_account(*promoted_user_id)
+                .and_then(|account| account.contact_data.telegram.user_id)
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub struct InMemoryEventDraftsRepository {
+    drafts: Mutex<HashMap<Uuid, EventDraft<Value>>>,
+    draft_ttl: Duration,
+}
+
+impl InMemoryEventDraftsRepository {
+    pub fn new(draft_ttl: Duration) -> Self {
+        Self {
+            drafts: Mutex::default(),
+            draft_ttl,
+        }
+    }
+
+    fn is_expired(&self, draft: &EventDraft<Value>) -> bool {
+        draft.update_time <= Utc::now().naive_utc() - self.draft_ttl
+    }
+}
+
+#[async_trait]
+impl EventDraftsRepository for InMemoryEventDraftsRepository {
+    async fn save_draft_json(&self, draft_id: Uuid, account_id: i64, draft: Value) -> Result<()> {
+        let mut drafts = self.drafts.lock().unwrap();
+        // draft of another account is not replaced
+        if drafts
+            .get(&draft_id)
+            .is_some_and(|saved| saved.account_id != account_id)
+        {
+            return Ok(());
+        }
+        drafts.insert(
+            draft_id,
+            EventDraft {
+                id: draft_id,
+                account_id,
+                draft,
+                update_time: Utc::now().naive_utc(),
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn get_account_drafts_json(&self, account_id: i64) -> Result<Vec<EventDraft<Value>>> {
+        let mut drafts = self
+            .drafts
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|draft| draft.account_id == account_id && !self.is_expired(draft))
+            .cloned()
+            .collect::<Vec<_>>();
+        drafts.sort_by_key(|draft| std::cmp::Reverse(draft.update_time));
+
+        Ok(drafts)
+    }
+
+    async fn get_draft_json(&self, draft_id: Uuid, account_id: i64) -> Result<Value> {
+        self.drafts
+            .lock()
+            .unwrap()
+            .get(&draft_id)
+            .filter(|draft| draft.account_id == account_id && !self.is_expired(draft))
+            .map(|draft| draft.draft.clone())
+            .ok_or(Error::RowNotFound)
+    }
+
+    async fn delete_draft(&self, draft_id: Uuid) -> Result<()> {
+        self.drafts.lock().unwrap().remove(&draft_id);
+
+        Ok(())
+    }
+
+    async fn delete_expired_drafts(&self) -> Result<u64> {
+        let mut drafts = self.drafts.lock().unwrap();
+        let drafts_count = drafts.len();
+        drafts.retain(|_, draft| !self.is_expired(draft));
+
+        Ok((drafts_count - drafts.len()) as u64)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryGroupFeedsRepository {
+    feeds: Mutex<HashMap<i64, GroupFeed>>,
+}
+
+impl InMemoryGroupFeedsRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl GroupFeedsRepository for InMemoryGroupFeedsRepository {
+    async fn add_group_feed(&self, chat_id: i64, title: Option<&str>) -> Result<GroupFeed> {
+        let mut feeds = self.feeds.lock().unwrap();
+        let feed = feeds.entry(chat_id).or_insert_with(|| {
+            let mut subjects = EventSubjectFilter::new().0.into_keys().collect::<Vec<_>>();
+            subjects.sort_by_key(|subject| *subject as i32);
+            GroupFeed {
+                chat_id,
+                title: None,
+                subjects,
+                cities: CITIES.to_vec(),
+                creation_time: Utc::now().naive_utc(),
+            }
+        });
+        feed.title = title.map(str::to_string);
+
+        Ok(feed.clone())
+    }
+
+    async fn get_group_feed(&self, chat_id: i64) -> Result<Option<GroupFeed>> {
+        Ok(self.feeds.lock().unwrap().get(&chat_id).cloned())
+    }
+
+    async fn switch_group_feed_subject(
+        &self,
+        chat_id: i64,
+        subject: EventSubject,
+    ) -> Result<GroupFeed> {
+        let mut feeds = self.feeds.lock().unwrap();
+        let feed = feeds.get_mut(&chat_id).ok_or(Error::RowNotFound)?;
+        if feed.is_following(subject) {
+            feed.subjects.retain(|followed| *followed != subject);
+        } else {
+            feed.subjects.push(subject);
+            feed.subjects.sort_by_key(|subject| *subject as i32);
+        }
+
+        Ok(feed.clone())
+    }
+
+    async fn switch_group_feed_city(&self, chat_id: i64, city: City) -> Result<GroupFeed> {
+        let mut feeds = self.feeds.lock().unwrap();
+        let feed = feeds.get_mut(&chat_id).ok_or(Error::RowNotFound)?;
+        if feed.is_following_city(city) {
+            feed.cities.retain(|followed| *followed != city);
+        } else {
+            feed.cities.push(city);
+            feed.cities.sort_by_key(|city| *city as i32);
+        }
+
+        Ok(feed.clone())
+    }
+
+    async fn delete_group_feed(&self, chat_id: i64) -> Result<()> {
+        self.feeds.lock().unwrap().remove(&chat_id);
+
+        Ok(())
+    }
+
+    async fn get_event_group_chats(
+        &self,
+        subject: EventSubject,
+        city: Option<City>,
+    ) -> Result<Vec<i64>> {
+        Ok(self
+            .feeds
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|feed| feed.is_following_event(subject, city))
+            .map(|feed| feed.chat_id)
+            .collect())
+    }
+}
+
+#[derive(Debug)]
+pub struct InMemoryCommunitiesRepository {
+    communities: Mutex<Vec<Community>>,
+    /// Memberships in order of joining
+    members: Mutex<Vec<(i64, i64)>>,
+    accounts_repository: Arc<InMemoryAccountsRepository>,
+}
+
+impl InMemoryCommunitiesRepository {
+    pub fn new(accounts_repository: Arc<InMemoryAccountsRepository>) -> Self {
+        Self {
+            communities: Mutex::default(),
+            members: Mutex::default(),
+            accounts_repository,
+        }
+    }
+
+    /// Communities for which predicate holds, sorted by name
+    fn filter_communities(&self, predicate: impl Fn(&Community) -> bool) -> Vec<Community> {
+        let mut communities = self
+            .communities
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|community| predicate(community))
+            .cloned()
+            .collect::<Vec<_>>();
+        communities.sort_by(|a, b| a.name.cmp(&b.name));
+        communities
+    }
+}
+
+#[async_trait]
+impl CommunitiesRepository for InMemoryCommunitiesRepository {
+    async fn create_community(&self, community: Community) -> Result<Community> {
+        let mut communities = self.communities.lock().unwrap();
+        let created_community = Community {
+            id: communities.len() as i64 + 1,
+            invite_token: community
+                .is_private
+                .then(|| Uuid::new_v4().simple().to_string()),
+            creation_time: Utc::now().naive_utc(),
+            ..community
+        };
+        communities.push(created_community.clone());
+        self.members
+            .lock()
+            .unwrap()
+            .push((created_community.id, created_community.owner_id));
+
+        Ok(created_community)
+    }
+
+    async fn get_community(&self, community_id: i64) -> Result<Community> {
+        self.filter_communities(|community| community.id == community_id)
+            .pop()
+            .ok_or(Error::RowNotFound)
+    }
+
+    async fn get_community_by_invite_token(&self, invite_token: &str) -> Result<Community> {
+        self.filter_communities(|community| community.invite_token.as_deref() == Some(invite_token))
+            .pop()
+            .ok_or(Error::RowNotFound)
+    }
+
+    async fn get_public_communities(&self, page: i64, page_size: i64) -> Result<Vec<Community>> {
+        Ok(self
+            .filter_communities(|community| !community.is_private)
+            .into_iter()
+            .skip((page * page_size) as usize)
+            .take(page_size as usize)
+            .collect())
+    }
+
+    async fn get_account_communities(&self, account_id: i64) -> Result<Vec<Community>> {
+        let members = self.members.lock().unwrap().clone();
+        Ok(self.filter_communities(|community| members.contains(&(community.id, account_id))))
+    }
+
+    async fn get_owned_communities(&self, owner_id: i64) -> Result<Vec<Community>> {
+        Ok(self.filter_communities(|community| community.owner_id == owner_id))
+    }
+
+    async fn add_member(&self, community_id: i64, account_id: i64) -> Result<()> {
+        let mut members = self.members.lock().unwrap();
+        if !members.contains(&(community_id, account_id)) {
+            members.push((community_id, account_id));
+        }
+
+        Ok(())
+    }
+
+    async fn remove_member(&self, community_id: i64, account_id: i64) -> Result<()> {
+        let owner_id = self
+            .get_community(community_id)
+            .await
+            .map(|community| community.owner_id);
+        if owner_id.is_ok_and(|owner_id| owner_id != account_id) {
+            self.members
+                .lock()
+                .unwrap()
+                .retain(|member| *member != (community_id, account_id));
+        }
+
+        Ok(())
+    }
+
+    async fn is_member(&self, community_id: i64, account_id: i64) -> Result<bool> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .contains(&(community_id, account_id)))
+    }
+
+    async fn get_members(&self, community_id: i64) -> Result<Vec<BaseAccount>> {
+        let members = self.members.lock().unwrap().clone();
+        Ok(members
+            .into_iter()
+            .filter(|(id, _)| *id == community_id)
+            .filter_map(|(_, account_id)| self.accounts_repository.get_account(account_id))
+            .collect())
+    }
+
+    async fn count_members(&self, community_id: i64) -> Result<i64> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(id, _)| *id == community_id)
+            .count() as i64)
+    }
+}
+
+#[derive(Debug)]
+pub struct InMemoryAccountFollowsRepository {
+    /// Follower and organizer ids in order of following
+    follows: Mutex<Vec<(i64, i64)>>,
+    accounts_repository: Arc<InMemoryAccountsRepository>,
+}
+
+impl InMemoryAccountFollowsRepository {
+    pub fn new(accounts_repository: Arc<InMemoryAccountsRepository>) -> Self {
+        Self {
+            follows: Mutex::default(),
+            accounts_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl AccountFollowsRepository for InMemoryAccountFollowsRepository {
+    async fn follow(&self, follower_id: i64, organizer_id: i64) -> Result<()> {
+        let mut follows = self.follows.lock().unwrap();
+        if !follows.contains(&(follower_id, organizer_id)) {
+            follows.push((follower_id, organizer_id));
+        }
+
+        Ok(())
+    }
+
+    async fn unfollow(&self, follower_id: i64, organizer_id: i64) -> Result<()> {
+        self.follows
+            .lock()
+            .unwrap()
+            .retain(|follow| *follow != (follower_id, organizer_id));
+
+        Ok(())
+    }
+
+    async fn is_following(&self, follower_id: i64, organizer_id: i64) -> Result<bool> {
+        Ok(self
+            .follows
+            .lock()
+            .unwrap()
+            .contains(&(follower_id, organizer_id)))
+    }
+
+    async fn get_followed_organizers(&self, follower_id: i64) -> Result<Vec<BaseAccount>> {
+        let follows = self.follows.lock().unwrap().clone();
+        Ok(follows
+            .into_iter()
+            .filter(|(follower, _)| *follower == follower_id)
+            .filter_map(|(_, organizer_id)| self.accounts_repository.get_account(organizer_id))
+            .collect())
+    }
+
+    async fn get_follower_tg_ids(&self, organizer_id: i64) -> Result<Vec<i64>> {
+        let follows = self.follows.lock().unwrap().clone();
+        Ok(follows
+            .into_iter()
+            .filter(|(_, organizer)| *organizer == organizer_id)
+            .filter_map(|(follower_id, _)| self.accounts_repository.get_account(follower_id))
+            .filter_map(|account| account.contact_data.telegram.user_id)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use super::*;
+    use crate::models::{
+        AuthData, EventFormat, EventRecurrence, EventType, RecurrenceFrequency, ResoAccountType,
+        ResonanseEventKind, UserContactData, UserTgData,
+    };
+
+    fn event(title: &str, datetime_from: NaiveDateTime) -> BaseEvent {
+        BaseEvent {
+            id: Uuid::nil(),
+            is_private: false,
+            invite_token: None,
+            is_commercial: false,
+            is_online: false,
+            is_paid: false,
+            event_format: EventFormat::Offline,
+            price: None,
+            event_kind: ResonanseEventKind::UserOffer,
+            title: title.to_string(),
+            description: String::new(),
+            brief_description: None,
+            subject: EventSubject::Social,
+            datetime_from,
+            datetime_to: None,
+            timezone: Tz::UTC,
+            location: None,
+            city: Some(City::SaintPetersburg),
+            location_title: None,
+            meeting_url: None,
+            capacity: None,
+            creator_id: 1,
+            community_id: None,
+            event_type: EventType::Unknown,
+            picture: None,
+            creation_time: NaiveDateTime::default(),
+            contact_info: None,
+            recurrence: None,
+            participants_count: 0,
+        }
+    }
+
+    fn account(tg_user_id: i64) -> BaseAccount {
+        BaseAccount {
+            id: 0,
+            username: None,
+            user_data: UserData {
+                first_name: "Ivan".to_string(),
+                last_name: String::new(),
+                city: None,
+                headline: None,
+                about: String::new(),
+                goals: None,
+                interests: None,
+                language: None,
+                age: None,
+                education: None,
+                hobby: None,
+                music: None,
+                sport: None,
+                books: None,
+                food: None,
+                worldview: None,
+                alcohol: None,
+                timezone: None,
+                locale: None,
+            },
+            contact_data: UserContactData {
+                email: None,
+                phone: None,
+                telegram: UserTgData {
+                    username: None,
+                    user_id: Some(tg_user_id),
+                },
+                instagram: None,
+            },
+            auth_data: AuthData {
+                password_hash: None,
+            },
+            user_type: ResoAccountType::Standard,
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_public_events() {
+        let repository = InMemoryEventsRepository::new();
+        let now = Utc::now().naive_utc();
+        let past_weekly = NaiveDate::from_ymd_opt(2020, 1, 1)
+            .and_then(|date| date.and_hms_opt(19, 0, 0))
+            .unwrap();
+
+        let upcoming = repository
+            .create_event(event("upcoming", now + Duration::days(1)))
+            .await
+            .unwrap();
+        repository
+            .create_event(event("finished", now - Duration::days(1)))
+            .await
+            .unwrap();
+        let private = repository
+            .create_event(BaseEvent {
+                is_private: true,
+                ..event("private", now + Duration::days(1))
+            })
+            .await
+            .unwrap();
+        repository
+            .create_event(BaseEvent {
+                city: Some(City::Moscow),
+                ..event("moscow", now + Duration::days(1))
+            })
+            .await
+            .unwrap();
+        repository
+            .create_event(BaseEvent {
+                recurrence: Some(EventRecurrence::new(RecurrenceFrequency::Weekly)),
+                ..event("weekly", past_weekly)
+            })
+            .await
+            .unwrap();
+
+        let events = repository
+            .get_public_events(
+                0,
+                10,
+                &EventSubjectFilter::new(),
+                Some(City::SaintPetersburg),
+                &EventFormatFilter::new(),
+            )
+            .await
+            .unwrap();
+        let titles = events
+            .iter()
+            .map(|event| event.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(titles.iter().filter(|title| **title == "weekly").count(), 4);
+        assert!(titles.contains(&"upcoming"));
+        assert!(!titles.contains(&"finished"));
+        assert!(!titles.contains(&"private"));
+        assert!(!titles.contains(&"moscow"));
+
+        assert_eq!(
+            repository
+                .search_upcoming_public_events("UPCOM", 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        let token = private.invite_token.unwrap();
+        assert_eq!(
+            repository
+                .get_event_by_invite_token(&token)
+                .await
+                .unwrap()
+                .id,
+            private.id
+        );
+
+        repository.delete_event(upcoming.id, 1).await.unwrap();
+        assert!(matches!(
+            repository.get_event_by_uuid(upcoming.id).await,
+            Err(Error::RowNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn stores_accounts() {
+        let repository = InMemoryAccountsRepository::new();
+
+        let created = repository
+            .create_user_by_tg_user_id(account(42))
+            .await
+            .unwrap();
+        let existing = repository
+            .create_user_by_tg_user_id(account(42))
+            .await
+            .unwrap();
+        assert_eq!(created.id, existing.id);
+        assert_eq!(repository.count_accounts().await.unwrap(), 1);
+
+        repository.set_user_locale(created.id, "en").await.unwrap();
+        repository
+            .set_user_city(created.id, City::Kazan)
+            .await
+            .unwrap();
+        let mut user_data = account(42).user_data;
+        user_data.first_name = "Pavel".to_string();
+        repository
+            .update_user_data(created.id, &user_data)
+            .await
+            .unwrap();
+
+        let updated = repository.get_user_by_tg_id(42).await.unwrap();
+        assert_eq!(updated.user_data.first_name, "Pavel");
+        assert_eq!(updated.user_data.city, None);
+        assert_eq!(
+            repository.get_user_locale_by_tg_id(42).await.unwrap(),
+            Some("en".to_string())
+        );
+        assert_eq!(repository.get_user_locale_by_tg_id(7).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn promotes_waitlisted_participant() {
+        let events_repository = Arc::new(InMemoryEventsRepository::new());
+        let accounts_repository = Arc::new(InMemoryAccountsRepository::new());
+        let repository = InMemoryEventParticipantsRepository::new(
+            events_repository.clone(),
+            accounts_repository.clone(),
+        );
+        let event = events_repository
+            .create_event(BaseEvent {
+                capacity: Some(1),
+                ..event("small", Utc::now().naive_utc() + Duration::days(1))
+            })
+            .await
+            .unwrap();
+        let going = accounts_repository.create_user(account(1)).await.unwrap();
+        let waiting = accounts_repository.create_user(account(2)).await.unwrap();
+
+        assert_eq!(
+            repository.join_event(event.id, going.id).await.unwrap(),
+            ParticipationStatus::Going
+        );
+        assert_eq!(
+            repository.join_event(event.id, waiting.id).await.unwrap(),
+            ParticipationStatus::Waitlisted
+        );
+        assert_eq!(
+            repository.leave_event(event.id, going.id).await.unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            repository
+                .get_participation_status(event.id, waiting.id)
+                .await
+                .unwrap(),
+            Some(ParticipationStatus::Going)
+        );
+        assert_eq!(
+            repository
+                .get_participation_status(event.id, going.id)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
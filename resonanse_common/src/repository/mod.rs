@@ -1,7 +1,19 @@
+pub use account_follows::*;
 pub use accounts::*;
+pub use communities::*;
+pub use event_drafts::*;
+pub use event_participants::*;
 pub use event_scores::*;
 pub use events::*;
+pub use group_feeds::*;
 
+mod account_follows;
 mod accounts;
+mod communities;
+mod event_drafts;
+mod event_participants;
 mod event_scores;
 mod events;
+mod group_feeds;
+#[cfg(feature = "in-memory")]
+pub mod in_memory;
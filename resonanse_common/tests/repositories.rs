@@ -0,0 +1,947 @@
+//! Repositories checked against real postgres.
+//!
+//! Set `RESONANSE_TEST_DB_URL` to url of any database on server where test user
+//! may create databases, e.g. `postgres://postgres@localhost:5432/postgres`.
+//! Every test creates its own disposable database with migrations applied
+//! and drops it afterwards. Tests are skipped when variable is not set.
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{ConnectOptions, Executor, PgPool};
+use uuid::Uuid;
+
+use resonanse_common::migrations::{
+    get_migrations_status, run_migrations, MigrationState, MIGRATOR,
+};
+use resonanse_common::models::{
+    AuthData, BaseAccount, BaseEvent, City, Community, EventFormat, EventPrice, EventRecurrence,
+    EventScoreType, EventSubject, EventType, Location, ParticipationStatus, RecurrenceFrequency,
+    ResoAccountType, ResonanseEventKind, UserContactData, UserData, UserTgData,
+};
+use resonanse_common::repository::{
+    AccountFollowsRepository, AccountsRepository, CommunitiesRepository, EventDraftsRepository,
+    EventParticipantsRepository, EventScoresRepository, EventsRepository, GroupFeedsRepository,
+    PgAccountFollowsRepository, PgAccountsRepository, PgCommunitiesRepository,
+    PgEventDraftsRepository, PgEventParticipantsRepository, PgEventScoresRepository,
+    PgEventsRepository, PgGroupFeedsRepository,
+};
+use resonanse_common::{EventFormatFilter, EventSubjectFilter};
+
+const TEST_DB_URL_ENV: &str = "RESONANSE_TEST_DB_URL";
+
+struct TestDb {
+    server_options: PgConnectOptions,
+    name: String,
+    pool: PgPool,
+}
+
+impl TestDb {
+    /// Creates database with fresh schema, `None` if test database is not configured
+    async fn create() -> Option<Self> {
+        let db = Self::create_empty().await?;
+        run_migrations(&db.pool).await.unwrap();
+
+        Some(db)
+    }
+
+    async fn create_empty() -> Option<Self> {
+        let Ok(url) = std::env::var(TEST_DB_URL_ENV) else {
+            eprintln!("{} is not set, skipping test", TEST_DB_URL_ENV);
+            return None;
+        };
+        let server_options: PgConnectOptions = url.parse().expect("invalid test db url");
+        let name = format!("resonanse_test_{}", Uuid::new_v4().simple());
+
+        let mut server_conn = server_options.connect().await.unwrap();
+        server_conn
+            .execute(format!("create database {}", name).as_str())
+            .await
+            .unwrap();
+
+        let pool = PgPoolOptions::new()
+            .max_connections(2)
+            .connect_with(server_options.clone().database(&name))
+            .await
+            .unwrap();
+
+        Some(Self {
+            server_options,
+            name,
+            pool,
+        })
+    }
+
+    async fn drop(self) {
+        self.pool.close().await;
+        let mut server_conn = self.server_options.connect().await.unwrap();
+        server_conn
+            .execute(format!("drop database {} with (force)", self.name).as_str())
+            .await
+            .unwrap();
+    }
+}
+
+fn account(tg_user_id: i64) -> BaseAccount {
+    BaseAccount {
+        id: 0,
+        username: None,
+        user_data: UserData {
+            first_name: "Ivan".to_string(),
+            last_name: String::new(),
+            city: None,
+            headline: None,
+            about: String::new(),
+            goals: None,
+            interests: None,
+            language: None,
+            age: None,
+            education: None,
+            hobby: None,
+            music: None,
+            sport: None,
+            books: None,
+            food: None,
+            worldview: None,
+            alcohol: None,
+            timezone: None,
+            locale: None,
+        },
+        contact_data: UserContactData {
+            email: None,
+            phone: None,
+            telegram: UserTgData {
+                username: None,
+                user_id: Some(tg_user_id),
+            },
+            instagram: None,
+        },
+        auth_data: AuthData {
+            password_hash: None,
+        },
+        user_type: ResoAccountType::Standard,
+    }
+}
+
+fn event(title: &str, creator_id: i64, datetime_from: NaiveDateTime) -> BaseEvent {
+    BaseEvent {
+        id: Uuid::nil(),
+        is_private: false,
+        invite_token: None,
+        is_commercial: false,
+        is_online: false,
+        is_paid: false,
+        event_format: EventFormat::Offline,
+        price: None,
+        event_kind: ResonanseEventKind::UserOffer,
+        title: title.to_string(),
+        description: String::new(),
+        brief_description: None,
+        subject: EventSubject::Social,
+        datetime_from,
+        datetime_to: None,
+        timezone: Tz::UTC,
+        location: None,
+        city: Some(City::SaintPetersburg),
+        location_title: None,
+        meeting_url: None,
+        capacity: None,
+        creator_id,
+        community_id: None,
+        event_type: EventType::Unknown,
+        picture: None,
+        creation_time: NaiveDateTime::default(),
+        contact_info: None,
+        recurrence: None,
+        participants_count: 0,
+    }
+}
+
+fn community(name: &str, owner_id: i64) -> Community {
+    Community {
+        id: 0,
+        name: name.to_string(),
+        description: String::new(),
+        poster_image_link: None,
+        is_private: false,
+        invite_token: None,
+        telegram_channel_link: None,
+        community_chat: false,
+        category: String::new(),
+        location: String::new(),
+        owner_id,
+        creation_time: NaiveDateTime::default(),
+    }
+}
+
+fn tomorrow() -> NaiveDateTime {
+    Utc::now().naive_utc() + Duration::days(1)
+}
+
+#[tokio::test]
+async fn migrations_are_applied_once() {
+    let Some(db) = TestDb::create_empty().await else {
+        return;
+    };
+
+    // concurrent runs wait for advisory lock of each other
+    let (first, second) = tokio::join!(run_migrations(&db.pool), run_migrations(&db.pool));
+    first.unwrap();
+    second.unwrap();
+
+    let statuses = get_migrations_status(&db.pool).await.unwrap();
+    assert_eq!(statuses.len(), MIGRATOR.iter().count());
+    assert!(statuses
+        .iter()
+        .all(|status| status.state == MigrationState::Applied));
+
+    sqlx::query("delete from _sqlx_migrations")
+        .execute(&db.pool)
+        .await
+        .unwrap();
+    // database with schema but without migrations history adopts it
+    run_migrations(&db.pool).await.unwrap();
+
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn upgrades_legacy_database() {
+    let Some(db) = TestDb::create_empty().await else {
+        return;
+    };
+
+    // schema of `db_init` scripts with legacy migrations recorded by sqlx
+    db.pool
+        .execute(include_str!("../../migrations/1_initial_schema.sql"))
+        .await
+        .unwrap();
+    db.pool
+        .execute(
+            r#"insert into user_accounts (first_name, last_name, city, about, user_type)
+            values ('Ivan', '', 'Санкт-Петербург', '', 0), ('Anna', '', 'Atlantis', '', 0);
+            "#,
+        )
+        .await
+        .unwrap();
+    get_migrations_status(&db.pool).await.unwrap();
+    db.pool
+        .execute(
+            r#"insert into _sqlx_migrations (version, description, success, checksum, execution_time)
+            values (1, 'add contact info', true, '\x00', 0),
+                (2, 'alter events for new filling', true, '\x00', 0);
+            "#,
+        )
+        .await
+        .unwrap();
+
+    run_migrations(&db.pool).await.unwrap();
+    assert!(get_migrations_status(&db.pool)
+        .await
+        .unwrap()
+        .iter()
+        .all(|status| status.state == MigrationState::Applied));
+
+    let cities: Vec<(Option<City>, Option<String>)> =
+        sqlx::query_as("select city, legacy_city from user_accounts order by first_name")
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        cities,
+        vec![
+            (None, Some("Atlantis".to_string())),
+            (
+                Some(City::SaintPetersburg),
+                Some("Санкт-Петербург".to_string())
+            ),
+        ]
+    );
+
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn accounts_repository() {
+    let Some(db) = TestDb::create().await else {
+        return;
+    };
+    let repository = PgAccountsRepository::new(db.pool.clone());
+
+    let created = repository
+        .create_user_by_tg_user_id(account(42))
+        .await
+        .unwrap();
+    let existing = repository
+        .create_user_by_tg_user_id(account(42))
+        .await
+        .unwrap();
+    assert_eq!(created.id, existing.id);
+    assert_eq!(created.contact_data.telegram.user_id, Some(42));
+    assert_eq!(
+        repository.get_account_id_by_tg_user_id(42).await.unwrap(),
+        created.id
+    );
+    assert!(matches!(
+        repository.get_account_id_by_tg_user_id(7).await,
+        Err(sqlx::Error::RowNotFound)
+    ));
+
+    let other = repository.create_user(account(7)).await.unwrap();
+    assert_ne!(other.id, created.id);
+    assert_eq!(repository.count_accounts().await.unwrap(), 2);
+
+    let mut user_data = account(42).user_data;
+    user_data.first_name = "Pavel".to_string();
+    user_data.age = Some(30);
+    user_data.hobby = Some("chess".to_string());
+    repository
+        .update_user_data(created.id, &user_data)
+        .await
+        .unwrap();
+    repository
+        .set_user_timezone(created.id, Tz::Europe__Moscow)
+        .await
+        .unwrap();
+    repository
+        .set_user_city(created.id, City::Kazan)
+        .await
+        .unwrap();
+    repository.set_user_locale(created.id, "en").await.unwrap();
+
+    let updated = repository.get_user_by_tg_id(42).await.unwrap();
+    assert_eq!(updated.user_data.first_name, "Pavel");
+    assert_eq!(updated.user_data.age, Some(30));
+    assert_eq!(updated.user_data.hobby.as_deref(), Some("chess"));
+    assert_eq!(updated.user_data.timezone, Some(Tz::Europe__Moscow));
+    assert_eq!(updated.user_data.city, Some(City::Kazan));
+    assert_eq!(updated.user_data.locale.as_deref(), Some("en"));
+    assert_eq!(
+        repository.get_user_locale_by_tg_id(42).await.unwrap(),
+        Some("en".to_string())
+    );
+    assert_eq!(repository.get_user_locale_by_tg_id(7).await.unwrap(), None);
+    assert_eq!(repository.get_user_locale_by_tg_id(1).await.unwrap(), None);
+
+    // columns are mapped by name, so their order in a row does not matter
+    let reordered: BaseAccount =
+        sqlx::query_as("select timezone, locale, city, * from user_accounts where tg_user_id = $1")
+            .bind(42_i64)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+    assert_eq!(reordered.user_data.timezone, Some(Tz::Europe__Moscow));
+    assert_eq!(reordered.user_data.locale.as_deref(), Some("en"));
+    assert_eq!(reordered.user_data.city, Some(City::Kazan));
+
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn events_repository() {
+    let Some(db) = TestDb::create().await else {
+        return;
+    };
+    let accounts = PgAccountsRepository::new(db.pool.clone());
+    let communities = PgCommunitiesRepository::new(db.pool.clone());
+    let repository = PgEventsRepository::new(db.pool.clone());
+    let creator = accounts.create_user(account(42)).await.unwrap();
+    let guest = accounts.create_user(account(7)).await.unwrap();
+    let club = communities
+        .create_community(community("club", creator.id))
+        .await
+        .unwrap();
+
+    let upcoming = repository
+        .create_event(BaseEvent {
+            is_paid: true,
+            price: Some(EventPrice {
+                amount: 500,
+                currency: "RUB".to_string(),
+            }),
+            timezone: Tz::Europe__Moscow,
+            location: Location::try_from_ll(Some(59.93), Some(30.31)),
+            community_id: Some(club.id),
+            description: "board games evening".to_string(),
+            ..event("upcoming", creator.id, tomorrow())
+        })
+        .await
+        .unwrap();
+    assert_ne!(upcoming.id, Uuid::nil());
+    let fetched = repository.get_event_by_uuid(upcoming.id).await.unwrap();
+    assert_eq!(fetched.title, "upcoming");
+    assert_eq!(fetched.timezone, Tz::Europe__Moscow);
+    assert_eq!(fetched.price.map(|price| price.amount), Some(500));
+    assert!(fetched.location.is_some());
+    assert_eq!(fetched.community_id, Some(club.id));
+
+    let finished = repository
+        .create_event(event(
+            "finished",
+            creator.id,
+            Utc::now().naive_utc() - Duration::days(1),
+        ))
+        .await
+        .unwrap();
+    let private = repository
+        .create_event(BaseEvent {
+            is_private: true,
+            ..event("private", creator.id, tomorrow())
+        })
+        .await
+        .unwrap();
+    let moscow = repository
+        .create_event(BaseEvent {
+            city: Some(City::Moscow),
+            subject: EventSubject::Sport,
+            ..event("moscow", creator.id, tomorrow())
+        })
+        .await
+        .unwrap();
+    let past_weekly = (Utc::now() - Duration::days(30))
+        .date_naive()
+        .and_hms_opt(19, 0, 0)
+        .unwrap();
+    let weekly = repository
+        .create_event(BaseEvent {
+            recurrence: Some(EventRecurrence::new(RecurrenceFrequency::Weekly)),
+            ..event("weekly", creator.id, past_weekly)
+        })
+        .await
+        .unwrap();
+    assert!(weekly.recurrence.is_some());
+    let mut ended_by_date = EventRecurrence::new(RecurrenceFrequency::Daily);
+    ended_by_date.until_date = Some(Utc::now().date_naive() - Duration::days(2));
+    repository
+        .create_event(BaseEvent {
+            recurrence: Some(ended_by_date),
+            ..event("ended by date", creator.id, past_weekly)
+        })
+        .await
+        .unwrap();
+    let mut ended_by_count = EventRecurrence::new(RecurrenceFrequency::Weekly);
+    ended_by_count.occurrences_count = Some(3);
+    repository
+        .create_event(BaseEvent {
+            recurrence: Some(ended_by_count),
+            ..event("ended by count", creator.id, past_weekly)
+        })
+        .await
+        .unwrap();
+
+    // finished and past recurring events are not listed
+    assert_eq!(repository.get_all_events().await.unwrap().len(), 3);
+    assert_eq!(
+        repository
+            .get_events_by_title_substr("upcom")
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    let all_public_titles = repository
+        .get_all_public_events(0, 10)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|event| event.title)
+        .collect::<Vec<_>>();
+    assert!(all_public_titles.contains(&"upcoming".to_string()));
+    assert!(!all_public_titles.contains(&"finished".to_string()));
+    assert!(!all_public_titles.contains(&"private".to_string()));
+
+    let public_titles = repository
+        .get_public_events(
+            0,
+            10,
+            &EventSubjectFilter::new(),
+            Some(City::SaintPetersburg),
+            &EventFormatFilter::new(),
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|event| event.title)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        public_titles
+            .iter()
+            .filter(|title| *title == "weekly")
+            .count(),
+        4
+    );
+    assert!(public_titles.contains(&"upcoming".to_string()));
+    assert!(!public_titles.contains(&"ended by date".to_string()));
+    assert!(!public_titles.contains(&"ended by count".to_string()));
+    assert!(!public_titles.contains(&"moscow".to_string()));
+    let second_page = repository
+        .get_public_events(
+            1,
+            2,
+            &EventSubjectFilter::new(),
+            Some(City::SaintPetersburg),
+            &EventFormatFilter::new(),
+        )
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|event| event.title)
+        .collect::<Vec<_>>();
+    assert_eq!(second_page, public_titles[2..4]);
+    assert!(!public_titles.contains(&"private".to_string()));
+
+    let next_weekly = repository
+        .search_upcoming_public_events("WEEK", 10)
+        .await
+        .unwrap();
+    assert_eq!(next_weekly.len(), 1);
+    repository
+        .cancel_event_occurrence(weekly.id, next_weekly[0].datetime_from)
+        .await
+        .unwrap();
+    let weekly = repository.get_event_by_uuid(weekly.id).await.unwrap();
+    assert_eq!(
+        weekly.recurrence.unwrap().cancelled_occurrences,
+        vec![next_weekly[0].datetime_from]
+    );
+    assert_eq!(
+        repository
+            .search_upcoming_public_events("board games", 10)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let community_events = repository.get_community_events(club.id, 10).await.unwrap();
+    assert_eq!(community_events.len(), 1);
+    assert_eq!(community_events[0].id, upcoming.id);
+
+    let token = private.invite_token.clone().unwrap();
+    assert_eq!(
+        repository
+            .get_event_by_invite_token(&token)
+            .await
+            .unwrap()
+            .id,
+        private.id
+    );
+    assert!(!repository
+        .is_event_access_granted(private.id, guest.id)
+        .await
+        .unwrap());
+    repository
+        .grant_event_access(private.id, guest.id)
+        .await
+        .unwrap();
+    repository
+        .grant_event_access(private.id, guest.id)
+        .await
+        .unwrap();
+    assert!(repository
+        .is_event_access_granted(private.id, guest.id)
+        .await
+        .unwrap());
+
+    repository
+        .create_event_tg_binding(100, moscow.id)
+        .await
+        .unwrap();
+
+    let counts = repository.count_events_by_subject().await.unwrap();
+    assert_eq!(counts.get(&EventSubject::Social), Some(&6));
+    assert_eq!(counts.get(&EventSubject::Sport), Some(&1));
+    assert_eq!(counts.get(&EventSubject::Culture), None);
+
+    repository
+        .delete_event(finished.id, creator.id)
+        .await
+        .unwrap();
+    repository
+        .delete_event(private.id, creator.id)
+        .await
+        .unwrap();
+    assert!(matches!(
+        repository.get_event_by_uuid(private.id).await,
+        Err(sqlx::Error::RowNotFound)
+    ));
+    assert_eq!(repository.get_all_events().await.unwrap().len(), 2);
+
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn event_scores_repository() {
+    let Some(db) = TestDb::create().await else {
+        return;
+    };
+    let accounts = PgAccountsRepository::new(db.pool.clone());
+    let events = PgEventsRepository::new(db.pool.clone());
+    let repository = PgEventScoresRepository::new(db.pool.clone());
+    let user = accounts.create_user(account(42)).await.unwrap();
+    let event = events
+        .create_event(event("liked", user.id, tomorrow()))
+        .await
+        .unwrap();
+
+    let score = repository
+        .set_event_score_by_user(event.id, user.id, EventScoreType::Like)
+        .await
+        .unwrap();
+    assert_eq!(score.event_score, EventScoreType::Like);
+    repository
+        .set_event_score_by_user(event.id, user.id, EventScoreType::Dislike)
+        .await
+        .unwrap();
+
+    let scores = repository.get_event_scores_by_user(user.id).await.unwrap();
+    assert_eq!(scores.len(), 1);
+    assert_eq!(scores[0].event_id, event.id);
+    assert_eq!(scores[0].event_score, EventScoreType::Dislike);
+    assert!(repository
+        .get_event_scores_by_user(user.id + 1)
+        .await
+        .unwrap()
+        .is_empty());
+
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn event_participants_repository() {
+    let Some(db) = TestDb::create().await else {
+        return;
+    };
+    let accounts = PgAccountsRepository::new(db.pool.clone());
+    let events = PgEventsRepository::new(db.pool.clone());
+    let repository = PgEventParticipantsRepository::new(db.pool.clone());
+    let creator = accounts.create_user(account(1)).await.unwrap();
+    let first = accounts.create_user(account(2)).await.unwrap();
+    let second = accounts.create_user(account(3)).await.unwrap();
+    let event = events
+        .create_event(BaseEvent {
+            capacity: Some(1),
+            ..event("small", creator.id, tomorrow())
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(
+        repository
+            .get_participation_status(event.id, first.id)
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        repository.join_event(event.id, first.id).await.unwrap(),
+        ParticipationStatus::Going
+    );
+    assert_eq!(
+        repository.join_event(event.id, second.id).await.unwrap(),
+        ParticipationStatus::Waitlisted
+    );
+    assert_eq!(
+        repository.join_event(event.id, first.id).await.unwrap(),
+        ParticipationStatus::Going
+    );
+    assert_eq!(
+        events
+            .get_event_by_uuid(event.id)
+            .await
+            .unwrap()
+            .participants_count,
+        1
+    );
+
+    assert_eq!(
+        repository.leave_event(event.id, first.id).await.unwrap(),
+        Some(3)
+    );
+    assert_eq!(
+        repository
+            .get_participation_status(event.id, second.id)
+            .await
+            .unwrap(),
+        Some(ParticipationStatus::Going)
+    );
+    assert_eq!(
+        repository.leave_event(event.id, second.id).await.unwrap(),
+        None
+    );
+    assert_eq!(
+        repository
+            .get_participation_status(event.id, second.id)
+            .await
+            .unwrap(),
+        None
+    );
+
+    db.drop().await;
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Draft {
+    title: String,
+}
+
+#[tokio::test]
+async fn event_drafts_repository() {
+    let Some(db) = TestDb::create().await else {
+        return;
+    };
+    let accounts = PgAccountsRepository::new(db.pool.clone());
+    let repository: &dyn EventDraftsRepository =
+        &PgEventDraftsRepository::new(db.pool.clone(), Duration::days(1));
+    let user = accounts.create_user(account(42)).await.unwrap();
+    let draft_id = Uuid::new_v4();
+
+    let draft = Draft {
+        title: "first".to_string(),
+    };
+    repository
+        .save_draft(draft_id, user.id, &draft)
+        .await
+        .unwrap();
+    let draft = Draft {
+        title: "second".to_string(),
+    };
+    repository
+        .save_draft(draft_id, user.id, &draft)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        repository
+            .get_draft::<Draft>(draft_id, user.id)
+            .await
+            .unwrap(),
+        draft
+    );
+    assert!(matches!(
+        repository.get_draft::<Draft>(draft_id, user.id + 1).await,
+        Err(sqlx::Error::RowNotFound)
+    ));
+    let drafts = repository
+        .get_account_drafts::<Draft>(user.id)
+        .await
+        .unwrap();
+    assert_eq!(drafts.len(), 1);
+    assert_eq!(drafts[0].id, draft_id);
+    assert_eq!(drafts[0].draft, draft);
+
+    assert_eq!(repository.delete_expired_drafts().await.unwrap(), 0);
+    let expiring = PgEventDraftsRepository::new(db.pool.clone(), Duration::zero());
+    assert_eq!(expiring.delete_expired_drafts().await.unwrap(), 1);
+
+    repository
+        .save_draft(draft_id, user.id, &draft)
+        .await
+        .unwrap();
+    repository.delete_draft(draft_id).await.unwrap();
+    assert!(repository
+        .get_account_drafts::<Draft>(user.id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn group_feeds_repository() {
+    let Some(db) = TestDb::create().await else {
+        return;
+    };
+    let repository = PgGroupFeedsRepository::new(db.pool.clone());
+
+    assert!(repository.get_group_feed(-100).await.unwrap().is_none());
+    let feed = repository
+        .add_group_feed(-100, Some("board games"))
+        .await
+        .unwrap();
+    assert!(feed.is_following(EventSubject::Sport));
+    let feed = repository
+        .add_group_feed(-100, Some("chess"))
+        .await
+        .unwrap();
+    assert_eq!(feed.title.as_deref(), Some("chess"));
+
+    let feed = repository
+        .switch_group_feed_subject(-100, EventSubject::Sport)
+        .await
+        .unwrap();
+    assert!(!feed.is_following(EventSubject::Sport));
+    assert!(repository
+        .get_event_group_chats(EventSubject::Sport, Some(City::Moscow))
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        repository
+            .get_event_group_chats(EventSubject::Social, Some(City::Moscow))
+            .await
+            .unwrap(),
+        vec![-100]
+    );
+
+    let feed = repository
+        .switch_group_feed_city(-100, City::Moscow)
+        .await
+        .unwrap();
+    assert!(!feed.is_following_city(City::Moscow));
+    assert!(feed.is_following_city(City::Kazan));
+    assert!(repository
+        .get_event_group_chats(EventSubject::Social, Some(City::Moscow))
+        .await
+        .unwrap()
+        .is_empty());
+    // online events have no city
+    assert_eq!(
+        repository
+            .get_event_group_chats(EventSubject::Social, None)
+            .await
+            .unwrap(),
+        vec![-100]
+    );
+    let feed = repository
+        .switch_group_feed_city(-100, City::Moscow)
+        .await
+        .unwrap();
+    assert!(feed.is_following_city(City::Moscow));
+    let feed = repository
+        .switch_group_feed_subject(-100, EventSubject::Sport)
+        .await
+        .unwrap();
+    assert!(feed.is_following(EventSubject::Sport));
+
+    repository.delete_group_feed(-100).await.unwrap();
+    assert!(repository.get_group_feed(-100).await.unwrap().is_none());
+    assert!(repository
+        .get_event_group_chats(EventSubject::Social, None)
+        .await
+        .unwrap()
+        .is_empty());
+
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn communities_repository() {
+    let Some(db) = TestDb::create().await else {
+        return;
+    };
+    let accounts = PgAccountsRepository::new(db.pool.clone());
+    let repository = PgCommunitiesRepository::new(db.pool.clone());
+    let owner = accounts.create_user(account(1)).await.unwrap();
+    let member = accounts.create_user(account(2)).await.unwrap();
+
+    let public = repository
+        .create_community(community("public", owner.id))
+        .await
+        .unwrap();
+    assert!(public.invite_token.is_none());
+    let private = repository
+        .create_community(Community {
+            is_private: true,
+            ..community("private", owner.id)
+        })
+        .await
+        .unwrap();
+    assert!(private.is_private);
+
+    assert_eq!(
+        repository.get_community(public.id).await.unwrap().name,
+        "public"
+    );
+    let token = private.invite_token.clone().unwrap();
+    assert_eq!(
+        repository
+            .get_community_by_invite_token(&token)
+            .await
+            .unwrap()
+            .id,
+        private.id
+    );
+    let public_communities = repository.get_public_communities(0, 10).await.unwrap();
+    assert_eq!(public_communities.len(), 1);
+    assert_eq!(public_communities[0].id, public.id);
+    assert_eq!(
+        repository
+            .get_owned_communities(owner.id)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    assert!(repository.is_member(public.id, owner.id).await.unwrap());
+    assert!(!repository.is_member(public.id, member.id).await.unwrap());
+    repository.add_member(public.id, member.id).await.unwrap();
+    repository.add_member(public.id, member.id).await.unwrap();
+    assert!(repository.is_member(public.id, member.id).await.unwrap());
+    assert_eq!(repository.count_members(public.id).await.unwrap(), 2);
+    assert_eq!(repository.get_members(public.id).await.unwrap().len(), 2);
+    let member_communities = repository.get_account_communities(member.id).await.unwrap();
+    assert_eq!(member_communities.len(), 1);
+    assert_eq!(member_communities[0].id, public.id);
+
+    repository
+        .remove_member(public.id, member.id)
+        .await
+        .unwrap();
+    assert!(!repository.is_member(public.id, member.id).await.unwrap());
+    assert_eq!(repository.count_members(public.id).await.unwrap(), 1);
+
+    db.drop().await;
+}
+
+#[tokio::test]
+async fn account_follows_repository() {
+    let Some(db) = TestDb::create().await else {
+        return;
+    };
+    let accounts = PgAccountsRepository::new(db.pool.clone());
+    let repository = PgAccountFollowsRepository::new(db.pool.clone());
+    let organizer = accounts.create_user(account(1)).await.unwrap();
+    let follower = accounts.create_user(account(2)).await.unwrap();
+
+    assert!(!repository
+        .is_following(follower.id, organizer.id)
+        .await
+        .unwrap());
+    repository.follow(follower.id, organizer.id).await.unwrap();
+    repository.follow(follower.id, organizer.id).await.unwrap();
+    assert!(repository
+        .is_following(follower.id, organizer.id)
+        .await
+        .unwrap());
+
+    let organizers = repository
+        .get_followed_organizers(follower.id)
+        .await
+        .unwrap();
+    assert_eq!(organizers.len(), 1);
+    assert_eq!(organizers[0].id, organizer.id);
+    assert_eq!(
+        repository.get_follower_tg_ids(organizer.id).await.unwrap(),
+        vec![2]
+    );
+
+    repository
+        .unfollow(follower.id, organizer.id)
+        .await
+        .unwrap();
+    assert!(!repository
+        .is_following(follower.id, organizer.id)
+        .await
+        .unwrap());
+    assert!(repository
+        .get_follower_tg_ids(organizer.id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    db.drop().await;
+}